@@ -4,22 +4,31 @@ use opentale_blocks::{
     blocks::{
         block::Block, 
         block_types::{
-            basic::Basic, custom::Custom, full::{self, Full}, full_custom::{self, FullCustom}, pillar::{self, Pillar}, reflectable::Reflectable, rotateable::{RotDir, Rotateable}, BlockType
-        }
-    }, table, with_full_paths, with_pillar_paths, AsId
+            basic::Basic, custom::Custom, full::{self, Full}, full_custom::{self, FullCustom}, pillar::{self, Pillar}, reflectable::Reflectable, rotateable::{RotDir, Rotateable}, slab::Slab, slope::Slope, stair::Stair, BlockType
+        },
+        properties::{BlockProperties, SoundGroup}
+    }, tags::BlockTag, table, with_full_paths, with_pillar_paths, AsId
 };
 
-table!(BlockType, enum BlockId, static BLOCKS = {
+table!(BlockType, #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)] enum BlockId, items enum ItemId, static ITEMS, tags Tag { log, mineable_with_pickaxe }, static BLOCKS = {
     let Dirt: Basic = Block::new_basic("dirt_block")
         .with_texture("dirt.gif");
+    #[tags(log)]
     let Log: Pillar = Block::new_pillar("log")
         .with_textures(with_pillar_paths! {
             up: "log_up.gif",
             down: "log_down.gif"
         });
+    #[tags(mineable_with_pickaxe)]
     let IronOre: Basic = Block::new_basic("iron_ore")
         .with_texture("iron_ore.gif")
-        .with_model("ore.bbno$");
+        .with_model("ore.bbno$")
+        .with_properties(BlockProperties::DEFAULT
+            .with_hardness(3.0)
+            .with_blast_resistance(3.0)
+            .with_required_tool("pickaxe")
+        );
+    #[tags(mineable_with_pickaxe)]
     let Furnace: Rotateable<Full, {RotDir::Y}> = Block::new_rotateable(
         Block::new_full("furnace")
             .with_textures(with_full_paths! {
@@ -35,51 +44,58 @@ table!(BlockType, enum BlockId, static BLOCKS = {
                 south: "furnace_south.bbno$",
                 down: "furnace_bottom.bbno$",
             })
+        )
+        .with_properties(BlockProperties::DEFAULT
+            .with_hardness(3.5)
+            .with_sound_group(SoundGroup::Stone)
+            .with_required_tool("pickaxe")
         );
+    #[tags(mineable_with_pickaxe)]
+    #[item(stack_size = 16)]
     let IronBlock: Basic = Block::new_basic("iron_block")
         .with_texture("iron_block.gif")
         .with_model("iron_block.bbno$");
-    let StoneSlab: Reflectable<Pillar> = Block::new_reflectable(
-        Block::new_pillar("stone_slab")
-            .with_textures(pillar::Paths {
-                up: "stone_slab_end.gif",
-                sides: "stone_slab_side.gif",
-                down: "stone_slab_end.gif",
-            })
-            .with_models(pillar::Paths {
-                up: "slab_top.bbno$",
-                sides: "slab_side.bbno$",
-                down: "slab_bottom.bbno$",
-            })
-    );
-    let StoneStair : 
-        Reflectable<Rotateable<FullCustom, {RotDir::Y}>> 
-    = Block::new_reflectable(
+    let StoneSlab: Slab = Block::new_slab("stone_slab")
+        .with_textures(pillar::Paths {
+            up: "stone_slab_end.gif",
+            sides: "stone_slab_side.gif",
+            down: "stone_slab_end.gif",
+        });
+    let StoneStair: Stair = Block::new_stair("stone_stair")
+        .with_textures(pillar::Paths {
+            up: "stone_stair_up.gif",
+            sides: "stone_stair_side.gif",
+            down: "stone_stair_down.gif",
+        });
+    let StoneSlope: Slope = Block::new_slope("stone_slope")
+        .with_texture("stone.gif");
+    let Bookshelf: Reflectable<Rotateable<FullCustom, {RotDir::Y}>> = Block::new_reflectable(
         Block::new_rotateable(
-            Block::new_full_custom("stone_stair")
+            Block::new_full_custom("bookshelf")
                 .with_textures(full_custom::Paths { 
-                    up: "stone_stair_up.gif", 
-                    north: "stone_stair_north.gif", 
-                    west: "stone_stair_west.gif", 
-                    east: "stone_stair_east.gif", 
-                    south: "stone_stair_south.gif", 
-                    down: "stone_stair_down.gif", 
-                    custom: "stone_stair_inner.gif",
+                    up: "bookshelf_up.gif", 
+                    north: "bookshelf_north.gif", 
+                    west: "bookshelf_west.gif", 
+                    east: "bookshelf_east.gif", 
+                    south: "bookshelf_south.gif", 
+                    down: "bookshelf_down.gif", 
+                    custom: "bookshelf_books.gif",
                 })
                 .with_models(full_custom::Paths {
-                    up: "stair_up.bbno$", 
-                    north: "stair_north.bbno$", 
-                    west: "stair_west.bbno$", 
-                    east: "stair_east.bbno$", 
-                    south: "stair_south.bbno$", 
-                    down: "stair_down.bbno$", 
-                    custom: "stair_inner.bbno$", 
+                    up: "bookshelf_up.bbno$", 
+                    north: "bookshelf_north.bbno$", 
+                    west: "bookshelf_west.bbno$", 
+                    east: "bookshelf_east.bbno$", 
+                    south: "bookshelf_south.bbno$", 
+                    down: "bookshelf_down.bbno$", 
+                    custom: "bookshelf_books.bbno$", 
                 })
         )
     );
     let StonePile: Custom = Block::new_custom("stone_pile")
         .with_texture("stone_pile.gif")
         .with_model("pile.bbno$");
+    #[item(none)]
     let SnowPile: Custom = Block::new_custom("stone_pile")
         .with_texture("snow_pile.gif")
         .with_model("pile.bbno$");
@@ -89,6 +105,9 @@ pub fn main() {
     let mut ids = vec![];
     for block in &BLOCKS {
         println!("{} has id {} and index {}", block.name(), block.id(), block.index());
+        if let Some(properties) = block.properties() {
+            println!("  hardness {}, requires tool {:?}", properties.hardness, properties.requires_tool);
+        }
         ids.push(block.id());
         // Here we could generate a table of textures, materials, models, etc.
         //
@@ -99,7 +118,8 @@ pub fn main() {
     println!("");
 
     for id in 0..BlockId::get_id_span() {
-        println!("block id {id} maps to {}", BlockId::from_id(id).to_string());
+        let block = BlockId::from_id(id);
+        println!("block id {id} maps to {} ({block:?})", block.to_string());
     }
 
     println!("");
@@ -109,4 +129,19 @@ pub fn main() {
         let block = &BLOCKS[block_id];
         println!("id {id} maps to block {}", block.name());
     }
+
+    println!("");
+
+    for tag in Tag::ALL {
+        let members: Vec<String> = tag.blocks().map(|block| block.to_string()).collect();
+        println!("#{} contains {}", tag.name(), members.join(", "));
+    }
+
+    println!("");
+
+    for item in ItemId::ALL {
+        let block = item.block();
+        println!("item {} stacks to {} and places {}", ITEMS[*item].name(), item.stack_size(), block.to_string());
+        println!("  drawn as {:?}", ITEMS[*item].icon());
+    }
 }
\ No newline at end of file
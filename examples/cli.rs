@@ -0,0 +1,35 @@
+//! The `opentale-blocks` tool, run over the demo table registered below.
+//!
+//! To run the tool over your own blocks, add a binary like this one to your
+//! crate and register your table with `cli_main!` in place of this one.
+//!
+//! ```text
+//! cargo run --features cli --example cli -- dump
+//! ```
+
+#![feature(const_trait_impl)]
+
+use opentale_blocks::{
+    blocks::{
+        block::Block,
+        block_types::{basic::Basic, pillar::Pillar, rotateable::{RotDir, Rotateable}, full::Full, slab::Slab, BlockType}
+    }, cli_main, table, with_pillar_paths, AsId
+};
+
+table!(BlockType, enum BlockId, static BLOCKS = {
+    let Dirt: Basic = Block::new_basic("dirt_block")
+        .with_texture("dirt.gif");
+    let Log: Pillar = Block::new_pillar("log")
+        .with_textures(with_pillar_paths! {
+            up: "log_up.gif",
+            down: "log_down.gif"
+        });
+    let Furnace: Rotateable<Full, {RotDir::Y}> = Block::new_rotateable(
+        Block::new_full("furnace")
+            .with_texture("furnace.gif")
+    );
+    let StoneSlab: Slab = Block::new_slab("stone_slab")
+        .with_texture("stone.gif");
+});
+
+cli_main!(BlockId, BLOCKS);
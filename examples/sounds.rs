@@ -0,0 +1,116 @@
+#![feature(const_trait_impl)]
+
+use opentale_blocks::{table, AsId, Buildable, HasBuildVariants};
+
+pub trait SoundEvent: Sync {
+    fn name(&self) -> &'static str;
+    fn id(&self) -> usize;
+    fn volume(&self) -> f32;
+    fn pitch(&self) -> f32;
+    fn subtitle(&self) -> Option<&'static str>;
+}
+
+pub struct Sound {
+    name: &'static str,
+    volume: f32,
+    pitch: f32,
+    subtitle: Option<&'static str>,
+    id: usize,
+}
+
+impl Sound {
+    pub const fn new(name: &'static str) -> Sound {
+        Sound::new_with_name(name)
+    }
+
+    pub const fn with_volume(mut self, volume: f32) -> Sound {
+        self.volume = volume;
+        self
+    }
+
+    pub const fn with_pitch(mut self, pitch: f32) -> Sound {
+        self.pitch = pitch;
+        self
+    }
+
+    pub const fn with_subtitle(mut self, subtitle: &'static str) -> Sound {
+        self.subtitle = Some(subtitle);
+        self
+    }
+}
+
+// sounds have no textures, so they don't take any texture layers
+impl const Buildable for Sound {
+    fn new_with_name(name: &'static str) -> Self {
+        Sound {
+            name,
+            volume: 1.0,
+            pitch: 1.0,
+            subtitle: None,
+            id: 0,
+        }
+    }
+    fn with_id(mut self, id: usize) -> Self {
+        self.id = id;
+        self
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+}
+
+impl HasBuildVariants for Sound {
+    type Variants = ();
+}
+
+impl SoundEvent for Sound {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    fn pitch(&self) -> f32 {
+        self.pitch
+    }
+
+    fn subtitle(&self) -> Option<&'static str> {
+        self.subtitle
+    }
+}
+
+// there's no such thing as an empty sound, so the enum has no null variant
+table!(SoundEvent, enum SoundId(), static SOUNDS = {
+    let UiClick: Sound = Sound::new("ui.click")
+        .with_volume(0.5);
+    let DoorOpen: Sound = Sound::new("block.door.open")
+        .with_subtitle("Door creaks");
+    let DoorClose: Sound = Sound::new("block.door.close")
+        .with_pitch(0.9)
+        .with_subtitle("Door closes");
+    let Thunder: Sound = Sound::new("weather.thunder")
+        .with_volume(10.0)
+        .with_subtitle("Thunder roars");
+});
+
+fn main() {
+    for sound in &SOUNDS {
+        let id = SoundId::from_id(sound.id());
+        println!("{} ({}) is {}", id.to_string(), sound.id(), sound.name());
+        println!("  volume {}, pitch {}", sound.volume(), sound.pitch());
+        if let Some(subtitle) = sound.subtitle() {
+            println!("  subtitled \"{subtitle}\"");
+        }
+    }
+
+    println!("");
+
+    let thunder = &SOUNDS[SoundId::Thunder(())];
+    println!("{} sounds, thunder plays at volume {}", SoundId::get_id_span(), thunder.volume());
+}
@@ -0,0 +1,411 @@
+//! Animated textures and the texture manifest.
+//!
+//! Every texture index of a table, the `layer` of a mesh [`Vertex`](crate::meshing::Vertex),
+//! gets an [`AnimatedTexture`] record in the [`TextureManifest`], telling which
+//! layers of the texture array hold its frames.  Uploading the records to the
+//! GPU lets shaders animate every texture the same way:
+//!
+//! ```glsl
+//! Animation animation = animations[texture_index];
+//! float position = time / animation.frame_time;
+//! uint frame = uint(position) % animation.frame_count;
+//! uint layer = animation.first_layer + tile * animation.frame_count + frame;
+//! ```
+//!
+//! where `tile` is the vertex's connected texture tile or texture variant, 0 for other textures.
+//! [`AnimatedTexture::frame_at`] does the same on the CPU, including ping-pong and interpolation.
+
+#[cfg(feature = "gif")]
+use std::path::Path;
+
+use crate::{blocks::{block::FaceDir, block_types::BlockType}, colormap::TintSource, ctm::CtmLayout, table::Table};
+
+/// How long a frame lasts when a GIF doesn't say, in seconds
+pub const DEFAULT_FRAME_TIME: f32 = 0.1;
+
+/// Where the frames of one texture are in the texture array.
+///
+/// Laid out to be uploaded to the GPU as is.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AnimatedTexture {
+    /// The layer of the first frame
+    pub first_layer: u32,
+    /// The number of frames, in consecutive layers.  Textures that
+    /// aren't animated have a single frame.
+    pub frame_count: u32,
+    /// How long each frame lasts, in seconds
+    pub frame_time: f32,
+    /// [`AnimatedTexture::INTERPOLATE`] and [`AnimatedTexture::PING_PONG`]
+    pub flags: u32,
+}
+
+impl AnimatedTexture {
+    /// Blend each frame into the next one instead of switching at once
+    pub const INTERPOLATE: u32 = 1;
+    /// Play the frames forwards then backwards instead of looping back to the first one
+    pub const PING_PONG: u32 = 2;
+
+    /// A texture with a single frame
+    pub const fn still(layer: u32) -> AnimatedTexture {
+        AnimatedTexture {
+            first_layer: layer,
+            frame_count: 1,
+            frame_time: DEFAULT_FRAME_TIME,
+            flags: 0,
+        }
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.frame_count > 1
+    }
+
+    /// The frames of one tile of a connected texture or one texture
+    /// variant, whose tiles each take `frame_count` consecutive layers
+    pub const fn tile(self, tile: u32) -> AnimatedTexture {
+        AnimatedTexture {
+            first_layer: self.first_layer + tile * self.frame_count,
+            ..self
+        }
+    }
+
+    /// The layers to show `time` seconds into the animation.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # use opentale_blocks::animation::AnimatedTexture;
+    ///
+    /// let animation = AnimatedTexture {
+    ///     first_layer: 10,
+    ///     frame_count: 3,
+    ///     frame_time: 0.5,
+    ///     flags: AnimatedTexture::PING_PONG | AnimatedTexture::INTERPOLATE,
+    /// };
+    /// // frames play 0, 1, 2, 1, 0, 1, ...
+    /// let frame = animation.frame_at(1.75);
+    /// assert_eq!((frame.layer, frame.next_layer, frame.blend), (11, 10, 0.5));
+    /// assert_eq!(animation.frame_at(2.0).layer, 10);
+    /// ```
+    pub fn frame_at(&self, time: f32) -> FrameSample {
+        let count = self.frame_count.max(1) as u64;
+        if count == 1 || self.frame_time <= 0.0 {
+            return FrameSample { layer: self.first_layer, next_layer: self.first_layer, blend: 0.0 };
+        }
+        let position = (time / self.frame_time).max(0.0);
+        let ping_pong = self.flags & Self::PING_PONG != 0;
+        let cycle = if ping_pong { 2 * (count - 1) } else { count };
+        let frame = |step: u64| {
+            let step = step % cycle;
+            let frame = if step >= count { cycle - step } else { step };
+            self.first_layer + frame as u32
+        };
+        let step = position.floor() as u64;
+        FrameSample {
+            layer: frame(step),
+            next_layer: frame(step + 1),
+            blend: if self.flags & Self::INTERPOLATE != 0 { position.fract() } else { 0.0 },
+        }
+    }
+}
+
+/// The layers to sample for an animated texture at some point in time
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FrameSample {
+    pub layer: u32,
+    pub next_layer: u32,
+    /// How far to blend from `layer` towards `next_layer`,
+    /// always 0 for textures that don't interpolate
+    pub blend: f32,
+}
+
+/// How an animated texture is played
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct AnimationOptions {
+    /// See [`AnimatedTexture::INTERPOLATE`]
+    pub interpolate: bool,
+    /// See [`AnimatedTexture::PING_PONG`]
+    pub ping_pong: bool,
+}
+
+impl AnimationOptions {
+    fn flags(self) -> u32 {
+        let mut flags = 0;
+        if self.interpolate {
+            flags |= AnimatedTexture::INTERPOLATE;
+        }
+        if self.ping_pong {
+            flags |= AnimatedTexture::PING_PONG;
+        }
+        flags
+    }
+}
+
+/// What goes in one layer of the texture array
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Layer {
+    /// The texture file, or `None` for the debug texture
+    pub path: Option<&'static str>,
+    /// The frame of the texture, 0 for textures that aren't animated
+    pub frame: u32,
+    /// The tile of a connected texture's sheet along with its layout,
+    /// or `None` for textures that don't connect.  Texture variants are
+    /// separate files, so they don't need a tile.
+    pub tile: Option<(CtmLayout, u32)>,
+}
+
+/// The layers of the texture array, and the [`AnimatedTexture`]
+/// record of every texture index of a table.
+///
+/// Layer 0 and texture index 0 are the debug texture, used by
+/// texture slots that have no file.  Texture slots sharing a file
+/// share its layers.  Connected textures and texture variants take
+/// a run of layers per tile or variant, each holding every frame of it.
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::{Block, FaceDir}, block_types::{basic::Basic, pillar::Pillar, BlockType}}, animation::{AnimationOptions, TextureManifest}, with_pillar_paths, AsId};
+///
+/// table!(BlockType, enum BlockId, static BLOCKS = {
+///     let Dirt: Basic = Block::new_basic("dirt")
+///         .with_texture("dirt.gif");
+///     let Furnace: Pillar = Block::new_pillar("furnace")
+///         .with_textures(with_pillar_paths! {
+///             up: "furnace_top.gif",
+///             sides: "furnace_fire.gif"
+///         });
+/// });
+///
+/// let mut manifest = TextureManifest::new(&BLOCKS, |path| match path {
+///     "furnace_fire.gif" => (4, 0.25),
+///     _ => (1, 0.1),
+/// });
+/// manifest.set_options("furnace_fire.gif", AnimationOptions { interpolate: true, ping_pong: false });
+///
+/// let fire = manifest.face(&BLOCKS[BlockId::Furnace(())], 0, FaceDir::North);
+/// assert_eq!((fire.first_layer, fire.frame_count, fire.frame_time), (3, 4, 0.25));
+/// // the furnace has no bottom texture, so it uses the debug texture
+/// assert_eq!(manifest.face(&BLOCKS[BlockId::Furnace(())], 0, FaceDir::Down).first_layer, 0);
+/// assert_eq!(manifest.layers().len(), 7);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextureManifest {
+    records: Vec<AnimatedTexture>,
+    tints: Vec<TintSource>,
+    layers: Vec<Layer>,
+}
+
+impl TextureManifest {
+    /// Lays out the textures of a table, given the frame count
+    /// and frame time of every texture file
+    pub fn new<T: 'static + ?Sized + BlockType>(
+        table: &Table<T>,
+        mut frames: impl FnMut(&'static str) -> (u32, f32)
+    ) -> TextureManifest {
+        let mut manifest = TextureManifest {
+            records: vec![AnimatedTexture::still(0)],
+            tints: vec![TintSource::None],
+            layers: vec![Layer { path: None, frame: 0, tile: None }],
+        };
+        // the frames of every file, and the record of every run of tiles
+        let mut frame_cache: Vec<(&'static str, (u32, f32))> = vec![];
+        let mut runs: Vec<(Vec<Layer>, AnimatedTexture)> = vec![];
+        for block in table {
+            let end = block.index() + block.texture_span();
+            if manifest.records.len() < end {
+                manifest.records.resize(end, AnimatedTexture::still(0));
+                manifest.tints.resize(end, TintSource::None);
+            }
+            for slot in 0..block.texture_span() {
+                manifest.tints[block.index() + slot] = block.tint_source(slot);
+                let Some(path) = block.texture_path(slot) else {
+                    continue;
+                };
+                // every tile of a connected texture, or every variant, or the one texture
+                let tiles: Vec<Layer> = match (block.connected_texture(slot), block.texture_variants(slot)) {
+                    (Some(layout), _) => (0..layout.tile_count() as u32)
+                        .map(|tile| Layer { path: Some(path), frame: 0, tile: Some((layout, tile)) })
+                        .collect(),
+                    (None, Some(variants)) => variants.iter()
+                        .map(|(path, _)| Layer { path: Some(path), frame: 0, tile: None })
+                        .collect(),
+                    (None, None) => vec![Layer { path: Some(path), frame: 0, tile: None }],
+                };
+                let record = match runs.iter().find(|(existing, _)| *existing == tiles) {
+                    Some((_, record)) => *record,
+                    None => {
+                        let mut frames_of = |path: &'static str| {
+                            match frame_cache.iter().find(|(file, _)| *file == path) {
+                                Some((_, frames)) => *frames,
+                                None => {
+                                    let (frame_count, frame_time) = frames(path);
+                                    frame_cache.push((path, (frame_count.max(1), frame_time)));
+                                    (frame_count.max(1), frame_time)
+                                }
+                            }
+                        };
+                        // every tile takes as many layers as the first one has frames
+                        let (frame_count, frame_time) = frames_of(path);
+                        let record = AnimatedTexture {
+                            first_layer: manifest.layers.len() as u32,
+                            frame_count,
+                            frame_time,
+                            flags: 0,
+                        };
+                        for tile in &tiles {
+                            let (own_count, _) = frames_of(tile.path.unwrap());
+                            manifest.layers.extend((0..frame_count).map(|frame| Layer {
+                                frame: frame % own_count,
+                                ..*tile
+                            }));
+                        }
+                        runs.push((tiles, record));
+                        record
+                    }
+                };
+                manifest.records[block.index() + slot] = record;
+            }
+        }
+        manifest
+    }
+
+    /// Lays out the textures of a table, reading the frames of every GIF
+    /// relative to the `root` asset directory.
+    ///
+    /// Files that can't be read take up a single layer,
+    /// [`Table::validate_assets`] reports them.
+    #[cfg(feature = "gif")]
+    pub fn load<T: 'static + ?Sized + BlockType>(table: &Table<T>, root: &Path) -> TextureManifest {
+        TextureManifest::new(table, |path| {
+            let path = root.join(path);
+            let is_gif = path.extension().is_some_and(|extension| extension == "gif");
+            match is_gif.then(|| gif_frames(&path)) {
+                Some(Ok(frames)) => (frames.delays.len() as u32, frames.frame_time()),
+                _ => (1, DEFAULT_FRAME_TIME),
+            }
+        })
+    }
+
+    /// Sets how every texture slot using a file is played
+    pub fn set_options(&mut self, path: &str, options: AnimationOptions) {
+        let first_layers: Vec<u32> = self.layers.iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.path == Some(path) && layer.frame == 0 && layer.tile.is_none_or(|(_, tile)| tile == 0))
+            .map(|(index, _)| index as u32)
+            .collect();
+        for record in &mut self.records {
+            if first_layers.contains(&record.first_layer) {
+                record.flags = options.flags();
+            }
+        }
+    }
+
+    /// The record of every texture index, to be indexed by a vertex's `layer`
+    pub fn records(&self) -> &[AnimatedTexture] {
+        &self.records
+    }
+
+    /// The tint of every texture index, packed by [`TintSource::to_bits`]
+    /// to be uploaded alongside the records
+    pub fn tints(&self) -> Vec<u32> {
+        self.tints.iter().map(|tint| tint.to_bits()).collect()
+    }
+
+    pub fn tint(&self, texture_index: usize) -> TintSource {
+        self.tints.get(texture_index).copied().unwrap_or_default()
+    }
+
+    pub fn get(&self, texture_index: usize) -> AnimatedTexture {
+        self.records.get(texture_index).copied().unwrap_or(AnimatedTexture::still(0))
+    }
+
+    /// The record of a face of one of a block's states,
+    /// where `state` is relative to the block's id
+    pub fn face<T: ?Sized + BlockType>(&self, block: &T, state: usize, face: FaceDir) -> AnimatedTexture {
+        self.get(block.index() + block.texture_slot(state, face))
+    }
+
+    /// What goes in each layer of the texture array
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+}
+
+/// The frames of a GIF
+#[cfg(feature = "gif")]
+#[derive(Clone, Debug)]
+pub struct GifFrames {
+    /// The width and height of the image
+    pub size: [u32; 2],
+    /// Every frame as RGBA, with earlier frames drawn under
+    /// later ones as the GIF's disposal methods say
+    pub frames: Vec<Vec<u8>>,
+    /// How long each frame lasts, in hundredths of a second
+    pub delays: Vec<u16>,
+}
+
+#[cfg(feature = "gif")]
+impl GifFrames {
+    /// The average time a frame lasts, in seconds
+    pub fn frame_time(&self) -> f32 {
+        let total: u32 = self.delays.iter().map(|delay| *delay as u32).sum();
+        if total == 0 {
+            return DEFAULT_FRAME_TIME;
+        }
+        total as f32 / self.delays.len() as f32 / 100.0
+    }
+}
+
+/// Decodes every frame of a GIF
+#[cfg(feature = "gif")]
+pub fn gif_frames(path: &Path) -> std::io::Result<GifFrames> {
+    use std::io;
+
+    let file = std::fs::File::open(path)?;
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(io::BufReader::new(file))
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    let [width, height] = [decoder.width() as usize, decoder.height() as usize];
+    let mut canvas = vec![0; width * height * 4];
+    let mut gif = GifFrames { size: [width as u32, height as u32], frames: vec![], delays: vec![] };
+    while let Some(frame) = decoder.read_next_frame()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?
+    {
+        let previous = canvas.clone();
+        let (left, top) = (frame.left as usize, frame.top as usize);
+        for y in 0..frame.height as usize {
+            for x in 0..frame.width as usize {
+                let (cx, cy) = (left + x, top + y);
+                if cx >= width || cy >= height {
+                    continue;
+                }
+                let source = &frame.buffer[(y * frame.width as usize + x) * 4..][..4];
+                // fully transparent pixels let the previous frame show through
+                if source[3] != 0 {
+                    canvas[(cy * width + cx) * 4..][..4].copy_from_slice(source);
+                }
+            }
+        }
+        gif.frames.push(canvas.clone());
+        gif.delays.push(frame.delay);
+        match frame.dispose {
+            gif::DisposalMethod::Background => {
+                for y in top..(top + frame.height as usize).min(height) {
+                    for x in left..(left + frame.width as usize).min(width) {
+                        canvas[(y * width + x) * 4..][..4].fill(0);
+                    }
+                }
+            }
+            gif::DisposalMethod::Previous => canvas = previous,
+            _ => {}
+        }
+    }
+    if gif.frames.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "the GIF has no frames"));
+    }
+    Ok(gif)
+}
@@ -0,0 +1,221 @@
+use std::{collections::HashSet, fmt, io, path::{Path, PathBuf}};
+
+use crate::{blocks::block_types::BlockType, model::{ModelError, ModelErrorKind, Models}, table::Table};
+
+/// The file extensions of assets, used to find unused files
+pub const ASSET_EXTENSIONS: &[&str] = &["png", "gif", "bbno$"];
+
+/// The problems found by [`Table::validate_assets`]
+#[derive(Debug, Default)]
+pub struct Report {
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    /// Whether no problems were found at all
+    pub fn is_clean(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Whether any problem would break the table at runtime
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Problem> {
+        self.problems.iter().filter(|problem| problem.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Problem> {
+        self.problems.iter().filter(|problem| !problem.is_error())
+    }
+}
+
+/// One line per problem, errors first
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for problem in self.errors() {
+            writeln!(f, "error: {problem}")?;
+        }
+        for problem in self.warnings() {
+            writeln!(f, "warning: {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum Problem {
+    /// A block references a file that doesn't exist
+    MissingFile { block: &'static str, path: PathBuf },
+    /// A texture isn't a PNG or GIF image that can be read
+    UnreadableImage { block: &'static str, path: PathBuf, reason: String },
+    /// A connected texture's sheet can't be split into its grid of square tiles
+    NotTileable { block: &'static str, path: PathBuf, size: [u32; 2], grid: [u32; 2] },
+    /// A texture, or the tiles of a connected texture, isn't the size most textures are
+    SizeMismatch { path: PathBuf, size: [u32; 2], expected: [u32; 2] },
+    /// A model exists but is malformed, or doesn't lie on its face
+    Model(ModelError),
+    /// A file is referenced by more than one block
+    DuplicateReference { path: &'static str, blocks: Vec<&'static str> },
+    /// An asset in the directory isn't referenced by any block
+    UnusedFile { path: PathBuf },
+}
+
+impl Problem {
+    /// Duplicate references and unused files are warnings, everything else is an error
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Problem::DuplicateReference { .. } | Problem::UnusedFile { .. })
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::MissingFile { block, path } => {
+                write!(f, "{block}: {} doesn't exist", path.display())
+            }
+            Problem::UnreadableImage { block, path, reason } => {
+                write!(f, "{block}: couldn't read texture {}: {reason}", path.display())
+            }
+            Problem::NotTileable { block, path, size, grid } => write!(
+                f,
+                "{block}: connected texture {} is {}x{}, which can't be split into {}x{} square tiles",
+                path.display(), size[0], size[1], grid[0], grid[1]
+            ),
+            Problem::SizeMismatch { path, size, expected } => write!(
+                f,
+                "texture {} is {}x{}, but most textures are {}x{}",
+                path.display(), size[0], size[1], expected[0], expected[1]
+            ),
+            Problem::Model(error) => write!(f, "{error}"),
+            Problem::DuplicateReference { path, blocks } => {
+                write!(f, "{path} is used by {}", blocks.join(", "))
+            }
+            Problem::UnusedFile { path } => write!(f, "{} isn't used by any block", path.display()),
+        }
+    }
+}
+
+pub(crate) fn validate<T: 'static + ?Sized + BlockType>(table: &Table<T>, root: &Path) -> Report {
+    let mut report = Report::default();
+    let mut references: Vec<(&'static str, Vec<&'static str>)> = vec![];
+    let mut reference = |path: &'static str, block: &'static str| {
+        match references.iter_mut().find(|(existing, _)| *existing == path) {
+            Some((_, blocks)) if !blocks.contains(&block) => blocks.push(block),
+            Some(_) => {}
+            None => references.push((path, vec![block])),
+        }
+    };
+
+    let mut sizes: Vec<(PathBuf, [u32; 2])> = vec![];
+    for block in table {
+        for path in block.texture_paths() {
+            reference(path, block.name());
+            let full_path = root.join(path);
+            if sizes.iter().any(|(existing, _)| *existing == full_path) {
+                continue;
+            }
+            let layout = (0..block.texture_span())
+                .find(|slot| block.texture_path(*slot) == Some(path))
+                .and_then(|slot| block.connected_texture(slot));
+            match (image_size(&full_path), layout) {
+                (Ok(size), None) => sizes.push((full_path, size)),
+                // connected textures are compared by the size of their tiles
+                (Ok(size), Some(layout)) => {
+                    let grid = layout.grid();
+                    let tile = [size[0] / grid[0], size[1] / grid[1]];
+                    if tile[0] == 0 || tile[0] != tile[1] || tile[0] * grid[0] != size[0] || tile[1] * grid[1] != size[1] {
+                        report.problems.push(Problem::NotTileable { block: block.name(), path: full_path, size, grid });
+                    } else {
+                        sizes.push((full_path, tile));
+                    }
+                }
+                (Err(error), _) if error.kind() == io::ErrorKind::NotFound => {
+                    report.problems.push(Problem::MissingFile { block: block.name(), path: full_path });
+                }
+                (Err(error), _) => report.problems.push(Problem::UnreadableImage {
+                    block: block.name(),
+                    path: full_path,
+                    reason: error.to_string(),
+                }),
+            }
+        }
+        for (_, path) in block.model_paths() {
+            reference(path, block.name());
+        }
+    }
+
+    if let Err(errors) = Models::load(table, root) {
+        report.problems.extend(errors.into_iter().map(|error| match error.kind {
+            ModelErrorKind::Missing => Problem::MissingFile { block: error.block, path: error.path },
+            _ => Problem::Model(error),
+        }));
+    }
+
+    // textures are stored in one array, so they must all be the same size
+    let mut counts: Vec<([u32; 2], usize)> = vec![];
+    for (_, size) in &sizes {
+        match counts.iter_mut().find(|(existing, _)| existing == size) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*size, 1)),
+        }
+    }
+    // the first size seen wins ties
+    let expected = counts.iter().rev().max_by_key(|(_, count)| *count).map(|(size, _)| *size);
+    if let Some(expected) = expected {
+        report.problems.extend(sizes.iter()
+            .filter(|(_, size)| *size != expected)
+            .map(|(path, size)| Problem::SizeMismatch { path: path.clone(), size: *size, expected }));
+    }
+
+    let referenced: HashSet<PathBuf> = references.iter().map(|(path, _)| root.join(path)).collect();
+    let mut files = vec![];
+    asset_files(root, &mut files);
+    files.sort();
+    report.problems.extend(files.into_iter()
+        .filter(|path| !referenced.contains(path))
+        .map(|path| Problem::UnusedFile { path }));
+
+    report.problems.extend(references.into_iter()
+        .filter(|(_, blocks)| blocks.len() > 1)
+        .map(|(path, blocks)| Problem::DuplicateReference { path, blocks }));
+    report
+}
+
+/// Every asset under a directory, by the extensions in [`ASSET_EXTENSIONS`]
+fn asset_files(directory: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            asset_files(&path, files);
+        } else if path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| ASSET_EXTENSIONS.contains(&extension))
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// The width and height of a PNG or GIF image, read from its header
+pub fn image_size(path: &Path) -> io::Result<[u32; 2]> {
+    let bytes = std::fs::read(path)?;
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let header = bytes.get(16..24).ok_or_else(|| invalid("truncated PNG header"))?;
+        let width = u32::from_be_bytes(header[0..4].try_into().unwrap());
+        let height = u32::from_be_bytes(header[4..8].try_into().unwrap());
+        Ok([width, height])
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        let header = bytes.get(6..10).ok_or_else(|| invalid("truncated GIF header"))?;
+        let width = u16::from_le_bytes([header[0], header[1]]);
+        let height = u16::from_le_bytes([header[2], header[3]]);
+        Ok([width as u32, height as u32])
+    } else {
+        Err(invalid("not a PNG or GIF image"))
+    }
+}
@@ -0,0 +1,218 @@
+//! What blocks do, as opposed to what they look like.
+//!
+//! A table entry attaches a [`BlockBehavior`] with `#[behavior(Value)]`,
+//! usually a unit struct, and the table's enum implements [`HasBehavior`],
+//! whose `behavior` method finds the behaviour of any block, falling back to
+//! [`NoBehavior`].
+//! Behaviours only see the world through the small [`World`] trait, which
+//! [`ChunkGrid`] implements, so they can be tested without a game engine.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::{Block, FaceDir}, block_types::{basic::Basic, BlockType}}, behavior::{BlockBehavior, HasBehavior, World}, chunk::{BlockPos, ChunkGrid}, AsId};
+//!
+//! table!(BlockType, enum BlockId, static BLOCKS = {
+//!     let Dirt: Basic = Block::new_basic("dirt");
+//!     #[behavior(Falling)]
+//!     let Sand: Basic = Block::new_basic("sand");
+//!     #[behavior(Lamp)]
+//!     let LampOff: Basic = Block::new_basic("lamp_off");
+//!     let LampOn: Basic = Block::new_basic("lamp_on");
+//! });
+//!
+//! struct Falling;
+//!
+//! impl BlockBehavior<BlockId> for Falling {
+//!     fn on_neighbor_changed(&self, world: &mut dyn World, pos: BlockPos, state: &BlockId, _neighbor: BlockPos) {
+//!         let below = [pos[0], pos[1] - 1, pos[2]];
+//!         if world.get(below) == 0 {
+//!             world.set_id(pos, &BlockId::Air(()));
+//!             world.set_id(below, state);
+//!         }
+//!     }
+//! }
+//!
+//! struct Lamp;
+//!
+//! impl BlockBehavior<BlockId> for Lamp {
+//!     fn on_use(&self, world: &mut dyn World, pos: BlockPos, _state: &BlockId, _face: FaceDir) -> bool {
+//!         world.set_id(pos, &BlockId::LampOn(()));
+//!         true
+//!     }
+//! }
+//!
+//! let mut world = ChunkGrid::new(BlockId::Air(()).to_id());
+//! world.set_id([0, 0, 0], &BlockId::Dirt(()));
+//! world.set_id([0, 2, 0], &BlockId::Sand(()));
+//! world.set_id([0, 3, 0], &BlockId::LampOff(()));
+//!
+//! // the sand falls when told a neighbour changed
+//! let sand = world.get_id::<BlockId>([0, 2, 0]);
+//! sand.behavior().on_neighbor_changed(&mut world, [0, 2, 0], &sand, [0, 1, 0]);
+//! assert_eq!(world.get([0, 2, 0]), 0);
+//! assert_eq!(world.get_id::<BlockId>([0, 1, 0]).to_string(), "Sand");
+//!
+//! // dirt doesn't do anything
+//! let dirt = BlockId::Dirt(());
+//! assert!(!dirt.behavior().on_use(&mut world, [0, 0, 0], &dirt, FaceDir::Up));
+//!
+//! let lamp = BlockId::LampOff(());
+//! assert!(lamp.behavior().on_use(&mut world, [0, 3, 0], &lamp, FaceDir::North));
+//! assert_eq!(world.get_id::<BlockId>([0, 3, 0]).to_string(), "LampOn");
+//! ```
+
+use std::ops::Range;
+
+use crate::{block_entity::{BlockEntity, HasBlockEntity}, blocks::block::FaceDir, chunk::{chunk_pos, local_pos, BlockPos, ChunkGrid}, AsId};
+
+/// Hooks called when things happen to a block, each doing nothing by default.
+///
+/// `I` is the enum of the table the block is in, and `state` is
+/// the state of the block the hook is called for.
+pub trait BlockBehavior<I>: Sync {
+    /// After the block was placed at `pos`
+    fn on_place(&self, _world: &mut dyn World, _pos: BlockPos, _state: &I) {}
+
+    /// After the block at `pos` was broken, with the state it had
+    fn on_break(&self, _world: &mut dyn World, _pos: BlockPos, _state: &I) {}
+
+    /// After the block at `neighbor`, next to this one, changed
+    fn on_neighbor_changed(&self, _world: &mut dyn World, _pos: BlockPos, _state: &I, _neighbor: BlockPos) {}
+
+    /// When the block is picked for a random tick, if it [ticks randomly](BlockBehavior::ticks_randomly)
+    fn on_random_tick(&self, _world: &mut dyn World, _pos: BlockPos, _state: &I) {}
+
+    /// When a tick the block scheduled with [`World::schedule_tick`] is due
+    fn on_scheduled_tick(&self, _world: &mut dyn World, _pos: BlockPos, _state: &I) {}
+
+    /// Whether the block is picked for random ticks, like crops that grow
+    fn ticks_randomly(&self) -> bool {
+        false
+    }
+
+    /// When a player uses the block by clicking `face`, returning whether
+    /// anything happened, so the use shouldn't go on to place a block
+    fn on_use(&self, _world: &mut dyn World, _pos: BlockPos, _state: &I, _face: FaceDir) -> bool {
+        false
+    }
+}
+
+/// The behaviour of blocks that don't do anything
+pub struct NoBehavior;
+
+impl<I> BlockBehavior<I> for NoBehavior {}
+
+/// Implemented by a table's enum, from the `#[behavior(Value)]` of its entries.
+///
+/// This is where blocks are dispatched to their behaviour, there's no lookup
+/// through the table.
+pub trait HasBehavior: AsId + Sized {
+    /// This block's behaviour, or [`NoBehavior`] if it has none
+    fn behavior(&self) -> &'static dyn BlockBehavior<Self>;
+}
+
+/// The blocks a behaviour can read and change
+pub trait World {
+    /// The id of a block
+    fn get(&self, pos: BlockPos) -> usize;
+
+    /// Sets the id of a block, returning the id that was replaced.
+    ///
+    /// This doesn't create block entities, use `place` on `dyn World` for blocks that have one.
+    fn set(&mut self, pos: BlockPos, id: usize) -> usize;
+
+    /// The block entity of a block, if it has one
+    fn block_entity_mut(&mut self, _pos: BlockPos) -> Option<&mut dyn BlockEntity> {
+        None
+    }
+
+    /// Gives the block at `pos` a block entity belonging to the block with
+    /// the given state ids, replacing the one it had.
+    /// Worlds without block entities drop it.
+    fn insert_block_entity(&mut self, _pos: BlockPos, _ids: Range<usize>, _data: Box<dyn BlockEntity>) {}
+
+    /// Asks for the block at `pos` to get a scheduled tick in `delay` ticks,
+    /// if it's still the same state then.  Worlds without ticks ignore it.
+    fn schedule_tick(&mut self, _pos: BlockPos, _delay: u64) {}
+}
+
+impl dyn World + '_ {
+    pub fn get_id<I: AsId>(&self, pos: BlockPos) -> I {
+        I::from_id(self.get(pos))
+    }
+
+    pub fn set_id<I: AsId>(&mut self, pos: BlockPos, block: &I) -> usize {
+        self.set(pos, block.to_id())
+    }
+
+    /// Sets a block, creating its block entity if it has one and doesn't
+    /// already, and returns the id that was replaced.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::{Block, FaceDir}, block_types::{basic::Basic, BlockType}}, behavior::{BlockBehavior, HasBehavior, World}, chunk::{BlockPos, ChunkGrid}, AsId};
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct Contents {
+    ///     items: u32,
+    /// }
+    ///
+    /// table!(BlockType, enum BlockId, static BLOCKS = {
+    ///     #[behavior(Unpack)]
+    ///     let Crate: Basic = Block::new_basic("crate");
+    ///     let Chest: Basic with Contents = Block::new_basic("chest");
+    /// });
+    ///
+    /// /// Turns into a chest when used
+    /// struct Unpack;
+    ///
+    /// impl BlockBehavior<BlockId> for Unpack {
+    ///     fn on_use(&self, world: &mut dyn World, pos: BlockPos, _state: &BlockId, _face: FaceDir) -> bool {
+    ///         world.place(pos, &BlockId::Chest(()));
+    ///         true
+    ///     }
+    /// }
+    ///
+    /// let mut world = ChunkGrid::new(BlockId::Air(()).to_id());
+    /// let block = BlockId::Crate(());
+    /// world.set_id([0, 0, 0], &block);
+    /// block.behavior().on_use(&mut world, [0, 0, 0], &block, FaceDir::Up);
+    /// let chest = world.block_entity([0, 0, 0]).unwrap();
+    /// assert_eq!(chest.downcast_ref::<Contents>().unwrap().items, 0);
+    /// ```
+    pub fn place<I: HasBlockEntity>(&mut self, pos: BlockPos, block: &I) -> usize {
+        let old = self.set(pos, block.to_id());
+        if self.block_entity_mut(pos).is_none()
+            && let Some(data) = block.new_block_entity()
+        {
+            self.insert_block_entity(pos, block.state_ids(), data);
+        }
+        old
+    }
+}
+
+impl World for ChunkGrid {
+    fn get(&self, pos: BlockPos) -> usize {
+        ChunkGrid::get(self, pos)
+    }
+
+    fn set(&mut self, pos: BlockPos, id: usize) -> usize {
+        ChunkGrid::set(self, pos, id)
+    }
+
+    fn block_entity_mut(&mut self, pos: BlockPos) -> Option<&mut dyn BlockEntity> {
+        ChunkGrid::block_entity_mut(self, pos)
+    }
+
+    fn insert_block_entity(&mut self, pos: BlockPos, ids: Range<usize>, data: Box<dyn BlockEntity>) {
+        if let Some(chunk) = self.chunk_mut(chunk_pos(pos)) {
+            chunk.block_entities_mut().insert(local_pos(pos), ids, data);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! The `opentale-blocks` tool without a table, offering only `diff` and `region`.
+//!
+//! To inspect and validate your own blocks, build the tool in your crate
+//! with `cli_main!`, see `examples/cli.rs`.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    opentale_blocks::cli::run_without_table(std::env::args())
+}
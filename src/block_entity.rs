@@ -0,0 +1,171 @@
+//! Per block data that doesn't fit in a state id.
+//!
+//! Furnaces, chests and signs keep data like their contents or text in a
+//! block entity.  A table entry declares its block entity type after its own,
+//! like `let Chest: Basic with ChestData = ...;`, where the type implements
+//! `Default` and `Clone`, and the table's enum implements [`HasBlockEntity`]
+//! to create it.
+//!
+//! Every [`Chunk`](crate::chunk::Chunk) keeps the block entities of its blocks
+//! in a [`BlockEntityStore`].  Placing a block with [`Chunk::place`](crate::chunk::Chunk::place)
+//! creates its default entity, and setting a block to anything but another
+//! state of the same block removes its entity.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, full::Full, rotateable::{Rotateable, RotDir, YRotFacing}, BlockType}}, block_entity::HasBlockEntity, chunk::Chunk, AsId};
+//!
+//! #[derive(Clone, Default)]
+//! struct FurnaceData {
+//!     fuel: u32,
+//! }
+//!
+//! table!(BlockType, enum BlockId, static BLOCKS = {
+//!     let Stone: Basic = Block::new_basic("stone");
+//!     let Furnace: Rotateable<Full, { RotDir::Y }> with FurnaceData = Block::new_rotateable(Block::new_full("furnace"));
+//! });
+//!
+//! assert!(BlockId::Furnace((YRotFacing::East, ())).has_block_entity());
+//! assert!(!BlockId::Stone(()).has_block_entity());
+//!
+//! let mut chunk = Chunk::filled(BlockId::Air(()).to_id());
+//! chunk.place([1, 2, 3], &BlockId::Furnace((YRotFacing::North, ())));
+//! let furnace = chunk.block_entity_mut([1, 2, 3]).unwrap();
+//! furnace.downcast_mut::<FurnaceData>().unwrap().fuel = 8;
+//!
+//! // turning the furnace keeps its data
+//! chunk.place([1, 2, 3], &BlockId::Furnace((YRotFacing::West, ())));
+//! let furnace = chunk.block_entity([1, 2, 3]).unwrap();
+//! assert_eq!(furnace.downcast_ref::<FurnaceData>().unwrap().fuel, 8);
+//!
+//! // replacing it removes its data
+//! chunk.set_id([1, 2, 3], &BlockId::Stone(()));
+//! assert!(chunk.block_entity([1, 2, 3]).is_none());
+//! assert!(chunk.block_entities().is_empty());
+//! ```
+
+use std::{any::Any, collections::BTreeMap, ops::Range};
+
+use crate::{chunk::{Chunk, CHUNK_VOLUME}, AsId};
+
+/// The data of a block entity, any type that can be cloned and sent between threads
+pub trait BlockEntity: Any + Send + Sync {
+    fn clone_entity(&self) -> Box<dyn BlockEntity>;
+}
+
+impl<T: Any + Send + Sync + Clone> BlockEntity for T {
+    fn clone_entity(&self) -> Box<dyn BlockEntity> {
+        Box::new(self.clone())
+    }
+}
+
+impl dyn BlockEntity {
+    pub fn is<T: BlockEntity>(&self) -> bool {
+        (self as &dyn Any).is::<T>()
+    }
+
+    pub fn downcast_ref<T: BlockEntity>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref()
+    }
+
+    pub fn downcast_mut<T: BlockEntity>(&mut self) -> Option<&mut T> {
+        (self as &mut dyn Any).downcast_mut()
+    }
+}
+
+impl Clone for Box<dyn BlockEntity> {
+    fn clone(&self) -> Self {
+        self.clone_entity()
+    }
+}
+
+/// Implemented by a table's enum, from the block entity types its entries are declared `with`
+pub trait HasBlockEntity: AsId {
+    /// Whether this block keeps a block entity
+    fn has_block_entity(&self) -> bool;
+
+    /// This block's entity with its default data,
+    /// or `None` if the block has no block entity
+    fn new_block_entity(&self) -> Option<Box<dyn BlockEntity>>;
+
+    /// The ids of every state of this block, which share its block entity
+    fn state_ids(&self) -> Range<usize>;
+}
+
+#[derive(Clone)]
+struct Entry {
+    /// The ids of the states of the block the entity belongs to
+    ids: Range<usize>,
+    data: Box<dyn BlockEntity>,
+}
+
+/// The block entities of a chunk, by position relative to the chunk
+#[derive(Clone, Default)]
+pub struct BlockEntityStore {
+    /// Entries by index in the chunk's storage
+    entries: BTreeMap<u16, Entry>,
+}
+
+impl BlockEntityStore {
+    pub fn new() -> BlockEntityStore {
+        BlockEntityStore::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, local: [usize; 3]) -> Option<&dyn BlockEntity> {
+        self.entries.get(&Self::key(local)).map(|entry| entry.data.as_ref())
+    }
+
+    pub fn get_mut(&mut self, local: [usize; 3]) -> Option<&mut dyn BlockEntity> {
+        self.entries.get_mut(&Self::key(local)).map(|entry| entry.data.as_mut())
+    }
+
+    /// The ids of the block a block entity belongs to
+    pub fn state_ids(&self, local: [usize; 3]) -> Option<Range<usize>> {
+        self.entries.get(&Self::key(local)).map(|entry| entry.ids.clone())
+    }
+
+    /// Adds a block entity belonging to the block with the given
+    /// state ids, returning the one it replaced
+    pub fn insert(
+        &mut self,
+        local: [usize; 3],
+        ids: Range<usize>,
+        data: Box<dyn BlockEntity>
+    ) -> Option<Box<dyn BlockEntity>> {
+        self.entries.insert(Self::key(local), Entry { ids, data }).map(|entry| entry.data)
+    }
+
+    pub fn remove(&mut self, local: [usize; 3]) -> Option<Box<dyn BlockEntity>> {
+        self.entries.remove(&Self::key(local)).map(|entry| entry.data)
+    }
+
+    /// Every block entity and its position, in the chunk's storage order
+    pub fn iter(&self) -> impl Iterator<Item = ([usize; 3], &dyn BlockEntity)> + '_ {
+        self.entries.iter().map(|(index, entry)| (Chunk::position(*index as usize), entry.data.as_ref()))
+    }
+
+    /// Removes the block entity at a position if its block was replaced by `id`
+    pub(crate) fn id_changed(&mut self, index: usize, id: usize) {
+        let key = index as u16;
+        if self.entries.get(&key).is_some_and(|entry| !entry.ids.contains(&id)) {
+            self.entries.remove(&key);
+        }
+    }
+
+    fn key(local: [usize; 3]) -> u16 {
+        let index = Chunk::index(local);
+        debug_assert!(index < CHUNK_VOLUME);
+        index as u16
+    }
+}
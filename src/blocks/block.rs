@@ -1,4 +1,4 @@
-use crate::{blocks::block_types::{basic::Basic, custom::Custom, full::Full, full_custom::FullCustom, pillar::Pillar, reflectable::Reflectable, rotateable::{RotDir, Rotateable}, BlockType}, Buildable};
+use crate::{blocks::block_types::{basic::Basic, custom::Custom, full::Full, full_custom::FullCustom, pillar::Pillar, reflectable::Reflectable, rotateable::{RotDir, Rotateable}, slab::Slab, slope::Slope, stair::Stair, BlockType}, Buildable};
 
 pub struct Block;
 
@@ -58,6 +58,43 @@ impl Block {
         Pillar::new_with_name(name)
     }
 
+    /// Builds a new Slab Block with default values and a specified name.
+    /// 
+    /// A Slab Block is a half block that can sit in the bottom or top half
+    /// of its space, or be doubled up into a full block.  Its geometry,
+    /// states, and culling are generated from the slab type,
+    /// so it only needs a pillar texture set.
+    /// 
+    /// Should only ever be used inside the `table` macro.  Blocks have
+    /// pivotal data that can only be safely set inside the `table` macro.
+    pub const fn new_slab(name: &'static str) -> Slab {
+        Slab::new_with_name(name)
+    }
+
+    /// Builds a new Stair Block with default values and a specified name.
+    /// 
+    /// A Stair Block can face any horizontal direction, be flipped upside down,
+    /// and bend into inner and outer corners.  Its geometry, states, and culling 
+    /// are generated from its shape, so it only needs a pillar texture set.
+    /// 
+    /// Should only ever be used inside the `table` macro.  Blocks have
+    /// pivotal data that can only be safely set inside the `table` macro.
+    pub const fn new_stair(name: &'static str) -> Stair {
+        Stair::new_with_name(name)
+    }
+
+    /// Builds a new Slope Block with default values and a specified name.
+    /// 
+    /// A Slope Block is a wedge that can face any horizontal direction
+    /// and be flipped upside down.  Its geometry, states, and culling 
+    /// are generated, so it only needs a pillar texture set.
+    /// 
+    /// Should only ever be used inside the `table` macro.  Blocks have
+    /// pivotal data that can only be safely set inside the `table` macro.
+    pub const fn new_slope(name: &'static str) -> Slope {
+        Slope::new_with_name(name)
+    }
+
     /// Builds a new Rotateable Block from another Block.
     /// 
     /// A Rotateable Block is a block that can be rotated in various ways 
@@ -139,12 +176,72 @@ impl Block {
 /// The direction of a face.
 /// 
 /// Used for relative indexing from a texture index
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum FaceDir {
     Up, North, West, East, South, Down
 }
 
+impl FaceDir {
+    /// Every face in relative indexing order
+    pub const ALL: [FaceDir; 6] = [
+        FaceDir::Up, 
+        FaceDir::North, 
+        FaceDir::West, 
+        FaceDir::East, 
+        FaceDir::South, 
+        FaceDir::Down
+    ];
+
+    /// The unit vector pointing out of this face.
+    /// 
+    /// Block space uses +x as east, +y as up, and +z as south.
+    pub const fn normal(self) -> [i32; 3] {
+        match self {
+            FaceDir::Up    => [ 0,  1,  0],
+            FaceDir::North => [ 0,  0, -1],
+            FaceDir::West  => [-1,  0,  0],
+            FaceDir::East  => [ 1,  0,  0],
+            FaceDir::South => [ 0,  0,  1],
+            FaceDir::Down  => [ 0, -1,  0],
+        }
+    }
+
+    /// The face whose normal is the given unit vector.
+    /// 
+    /// Panics if `normal` isn't an axis aligned unit vector.
+    pub const fn from_normal(normal: [i32; 3]) -> FaceDir {
+        match normal {
+            [ 0,  1,  0] => FaceDir::Up,
+            [ 0,  0, -1] => FaceDir::North,
+            [-1,  0,  0] => FaceDir::West,
+            [ 1,  0,  0] => FaceDir::East,
+            [ 0,  0,  1] => FaceDir::South,
+            [ 0, -1,  0] => FaceDir::Down,
+            _ => panic!("normal is not an axis aligned unit vector")
+        }
+    }
+
+    /// The face pointing in the opposite direction
+    pub const fn opposite(self) -> FaceDir {
+        match self {
+            FaceDir::Up    => FaceDir::Down,
+            FaceDir::North => FaceDir::South,
+            FaceDir::West  => FaceDir::East,
+            FaceDir::East  => FaceDir::West,
+            FaceDir::South => FaceDir::North,
+            FaceDir::Down  => FaceDir::Up,
+        }
+    }
+
+    /// This face's position in relative indexing order
+    pub const fn to_index(self) -> usize {
+        self as usize
+    }
+}
+
 /// A Flag representing the culling properties of a face 
 /// and its corresponding texture and model
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CullingFlag {
     /// This face can not be culled nor does it cull other faces. 
     /// 
@@ -1,4 +1,4 @@
-use crate::{blocks::block_types::BlockType, Buildable, HasBuildVariants};
+use crate::{blocks::{block::FaceDir, block_types::{north_model_on, slot_paths, BlockType}, const_into::ConstInto, geometry::BlockTransform, properties::BlockProperties}, colormap::TintSource, ctm::CtmLayout, variants::TextureVariants, Buildable, HasBuildVariants};
 
 /// A Block where all faces use the same texture and model
 pub struct Basic {
@@ -6,8 +6,16 @@ pub struct Basic {
     pub(crate) name: &'static str,
     /// If no texture is defined, the default texture (a debug texture) will be used.
     pub(crate) texture: Option<&'static str>,
+    /// If no layout is defined, the texture is a single tile.
+    pub(crate) connected: Option<CtmLayout>,
+    /// If no variants are defined, every block uses the same texture.
+    pub(crate) variants: Option<TextureVariants>,
+    /// If no tint is defined, the texture isn't tinted.
+    pub(crate) tint: TintSource,
     /// If no model is defined, the default model (a normal cube face) will be used.
     pub(crate) model: Option<&'static str>,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
     /// a number that represents this block in memory
     pub(crate) id: usize,
     /// a value that represents the index 
@@ -21,10 +29,38 @@ impl Basic {
         self
     } 
 
+    /// Picks the texture from weighted alternatives by each block's position
+    pub const fn with_texture_variants(
+        mut self,
+        variants: impl const ConstInto<TextureVariants>
+    ) -> Self {
+        let variants = variants.const_into();
+        self.texture = Some(variants.first());
+        self.variants = Some(variants);
+        self
+    }
+
+    /// Colours the texture, such as by the grass or foliage colormap
+    pub const fn with_tint(mut self, tint: TintSource) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /// Makes the texture a sheet of connected texture tiles
+    pub const fn with_connected_texture(mut self, layout: CtmLayout) -> Self {
+        self.connected = Some(layout);
+        self
+    }
+
     pub const fn with_model(mut self, model: &'static str) -> Self {
         self.model = Some(model);
         self
     } 
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
 }
 
 impl const Buildable for Basic {
@@ -32,7 +68,11 @@ impl const Buildable for Basic {
         Basic {
             name,
             texture: None,
+            connected: None,
+            variants: None,
+            tint: TintSource::None,
             model: None,
+            properties: None,
             id: 0,
             index: 0
         }
@@ -52,6 +92,9 @@ impl const Buildable for Basic {
     fn set_id(&mut self, id: usize) {
         self.id = id;
     }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
 }
 
 impl HasBuildVariants for Basic {
@@ -70,8 +113,51 @@ impl BlockType for Basic {
         self.index
     }
 
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
     /// a number that represents this block in memory
     fn id(&self) -> usize {
         self.id
     }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    /// The model is shared by every face
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.texture.filter(|_| slot == 0)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        slot_paths(self)
+    }
+
+    fn connected_texture(&self, slot: usize) -> Option<CtmLayout> {
+        self.connected.filter(|_| slot == 0)
+    }
+
+    fn texture_variants(&self, slot: usize) -> Option<&TextureVariants> {
+        self.variants.as_ref().filter(|_| slot == 0)
+    }
+
+    fn tint_source(&self, slot: usize) -> TintSource {
+        if slot == 0 { self.tint } else { TintSource::None }
+    }
+
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        self.model.map(|model| (Some(FaceDir::North), model)).into_iter().collect()
+    }
+
+    /// The model is turned to face each face of the block
+    fn state_models(&self, _state: usize) -> Vec<(Option<FaceDir>, &'static str, BlockTransform)> {
+        let Some(model) = self.model else {
+            return vec![];
+        };
+        FaceDir::ALL.into_iter().map(|face| (Some(face), model, north_model_on(face))).collect()
+    }
 }
\ No newline at end of file
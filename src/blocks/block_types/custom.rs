@@ -1,4 +1,4 @@
-use crate::{blocks::block_types::BlockType, Buildable, HasBuildVariants};
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::BlockType, properties::BlockProperties}, Buildable, HasBuildVariants};
 
 /// A Block that has a custom model and is not culled by 
 /// nor culls other faces.
@@ -9,6 +9,8 @@ pub struct Custom {
     pub(crate) texture: Option<&'static str>,
     /// If no model is defined, the default cube model (a normal cube) will be used.
     pub(crate) model: Option<&'static str>,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
     /// a number that represents this block in memory
     pub(crate) id: usize,
     /// a value that represents the index 
@@ -26,6 +28,11 @@ impl Custom {
         self.model = Some(model);
         self
     } 
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
 }
 
 impl const Buildable for Custom {
@@ -34,6 +41,7 @@ impl const Buildable for Custom {
             name,
             texture: None,
             model: None,
+            properties: None,
             id: 0,
             index: 0
         }
@@ -53,6 +61,9 @@ impl const Buildable for Custom {
     fn set_id(&mut self, id: usize) {
         self.id = id;
     }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
 }
 
 impl HasBuildVariants for Custom {
@@ -71,8 +82,40 @@ impl BlockType for Custom {
         self.index
     }
 
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
     /// a number that represents this block in memory
     fn id(&self) -> usize {
         self.id
     }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.texture.filter(|_| slot == 0)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        self.texture.into_iter().collect()
+    }
+
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        self.model.map(|model| (None, model)).into_iter().collect()
+    }
+
+    /// Custom models never cull nor get culled by other faces
+    fn culling(&self, _state: usize, _face: FaceDir) -> CullingFlag {
+        CullingFlag::None
+    }
+
+    /// Custom blocks are drawn from their models
+    fn is_cube(&self, _state: usize) -> bool {
+        false
+    }
 }
\ No newline at end of file
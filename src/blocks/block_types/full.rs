@@ -1,4 +1,4 @@
-use crate::{blocks::{block_types::BlockType, const_into::ConstInto}, Buildable, HasBuildVariants};
+use crate::{blocks::{block::FaceDir, block_types::{slot_paths, BlockType}, const_into::ConstInto, properties::BlockProperties}, colormap::TintSource, ctm::CtmLayout, variants::TextureVariants, Buildable, HasBuildVariants};
 
 /// A Block where each face has a unique texture and model
 pub struct Full {
@@ -6,8 +6,19 @@ pub struct Full {
     pub(crate) name: &'static str,
     /// If no texture is defined, the default texture (a debug texture) will be used.
     pub(crate) texture: FullTexture,
+    /// The layout of each face's connected texture, in relative indexing order.
+    /// If no layout is defined, the face's texture is a single tile.
+    pub(crate) connected: [Option<CtmLayout>; 6],
+    /// The weighted alternatives of each texture, in relative indexing order.
+    /// If no variants are defined, every block uses the same texture.
+    pub(crate) variants: [Option<TextureVariants>; 6],
+    /// The tint of each texture, in relative indexing order.
+    /// If no tint is defined, the texture isn't tinted.
+    pub(crate) tints: [TintSource; 6],
     /// If no model is defined, the default model (a normal cube face) will be used.
     pub(crate) models: PathsInternal,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
     /// a number that represents this block in memory
     pub(crate) id: usize,
     /// a value that represents the index 
@@ -29,6 +40,28 @@ impl Full {
         self
     } 
 
+    /// Makes a face's texture a sheet of connected texture tiles
+    pub const fn with_connected_texture(mut self, face: FaceDir, layout: CtmLayout) -> Self {
+        self.connected[face.to_index()] = Some(layout);
+        self
+    }
+
+    /// Picks a face's texture from weighted alternatives by each block's position
+    pub const fn with_texture_variants(
+        mut self,
+        face: FaceDir,
+        variants: impl const ConstInto<TextureVariants>
+    ) -> Self {
+        self.variants[face.to_index()] = Some(variants.const_into());
+        self
+    }
+
+    /// Colours a face's texture, such as by the grass or foliage colormap
+    pub const fn with_tint(mut self, face: FaceDir, tint: TintSource) -> Self {
+        self.tints[face.to_index()] = tint;
+        self
+    }
+
     pub const fn with_models(
         mut self, 
         models: impl const ConstInto<PathsInternal>
@@ -36,6 +69,11 @@ impl Full {
         self.models = models.const_into();
         self
     } 
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
 }
 
 impl const Buildable for Full {
@@ -43,6 +81,9 @@ impl const Buildable for Full {
         Full {
             name,
             texture: FullTexture::Single(None),
+            connected: [None; 6],
+            variants: [None; 6],
+            tints: [TintSource::None; 6],
             models: PathsInternal { 
                 up: None, 
                 north: None, 
@@ -51,6 +92,7 @@ impl const Buildable for Full {
                 south: None, 
                 down: None 
             },
+            properties: None,
             id: 0,
             index: 0
         }
@@ -70,6 +112,9 @@ impl const Buildable for Full {
     fn set_id(&mut self, id: usize) {
         self.id = id;
     }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
 }
 
 impl HasBuildVariants for Full {
@@ -88,10 +133,63 @@ impl BlockType for Full {
         self.index
     }
 
+    fn texture_span(&self) -> usize {
+        Self::get_texture_size()
+    }
+
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
     /// a number that represents this block in memory
     fn id(&self) -> usize {
         self.id
     }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        match self.variants.get(slot) {
+            Some(Some(variants)) => Some(variants.first()),
+            _ => self.texture.path(slot),
+        }
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        slot_paths(self)
+    }
+
+    fn connected_texture(&self, slot: usize) -> Option<CtmLayout> {
+        self.connected.get(slot).copied().flatten()
+    }
+
+    fn texture_variants(&self, slot: usize) -> Option<&TextureVariants> {
+        self.variants.get(slot)?.as_ref()
+    }
+
+    fn tint_source(&self, slot: usize) -> TintSource {
+        self.tints.get(slot).copied().unwrap_or_default()
+    }
+
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        [
+            (Some(FaceDir::Up), self.models.up),
+            (Some(FaceDir::North), self.models.north),
+            (Some(FaceDir::West), self.models.west),
+            (Some(FaceDir::East), self.models.east),
+            (Some(FaceDir::South), self.models.south),
+            (Some(FaceDir::Down), self.models.down),
+        ].into_iter().filter_map(|(face, model)| Some((face, model?))).collect()
+    }
+
+    /// Each face has its own texture, in relative indexing order
+    fn texture_slot(&self, _state: usize, face: FaceDir) -> usize {
+        face.to_index()
+    }
 }
 
 pub enum FullTexture {
@@ -99,6 +197,46 @@ pub enum FullTexture {
     Multiple(PathsInternal)
 }
 
+impl FullTexture {
+    /// The texture file of a slot, in relative indexing order
+    pub fn path(&self, slot: usize) -> Option<&'static str> {
+        match self {
+            FullTexture::Single(path) => path.filter(|_| slot < 6),
+            FullTexture::Multiple(paths) => match slot {
+                0 => paths.up,
+                1 => paths.north,
+                2 => paths.west,
+                3 => paths.east,
+                4 => paths.south,
+                5 => paths.down,
+                _ => None,
+            },
+        }
+    }
+
+    /// Every texture file set, without repeats
+    pub fn paths(&self) -> Vec<&'static str> {
+        let all: Vec<&'static str> = match self {
+            FullTexture::Single(path) => path.iter().copied().collect(),
+            FullTexture::Multiple(paths) => [
+                paths.up,
+                paths.north,
+                paths.west,
+                paths.east,
+                paths.south,
+                paths.down,
+            ].into_iter().flatten().collect(),
+        };
+        let mut paths = vec![];
+        for path in all {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
 /// A strict helper struct for constructing a fully-defined [`PathsInternal`] instance.
 ///
 /// Unlike [`with_full_paths!`], `Paths` requires **all six fields** to be
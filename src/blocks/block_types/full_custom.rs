@@ -1,4 +1,4 @@
-use crate::{blocks::{block_types::BlockType, const_into::ConstInto}, Buildable, HasBuildVariants};
+use crate::{blocks::{block::FaceDir, block_types::BlockType, const_into::ConstInto, properties::BlockProperties}, Buildable, HasBuildVariants};
 
 /// A Block where each face has a unique texture and model 
 /// plus a custom texture and model that cannot cull 
@@ -10,6 +10,8 @@ pub struct FullCustom {
     pub(crate) texture: FullCustomTexture,
     /// If no model is defined, the default model (a normal cube face) will be used.
     pub(crate) models: PathsInternal,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
     /// a number that represents this block in memory
     pub(crate) id: usize,
     /// a value that represents the index 
@@ -38,6 +40,11 @@ impl FullCustom {
         self.models = models.const_into();
         self
     } 
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
 }
 
 impl const Buildable for FullCustom {
@@ -54,6 +61,7 @@ impl const Buildable for FullCustom {
                 down: None,
                 custom: None,
             },
+            properties: None,
             id: 0,
             index: 0
         }
@@ -73,6 +81,9 @@ impl const Buildable for FullCustom {
     fn set_id(&mut self, id: usize) {
         self.id = id;
     }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
 }
 
 impl HasBuildVariants for FullCustom {
@@ -91,10 +102,49 @@ impl BlockType for FullCustom {
         self.index
     }
 
+    fn texture_span(&self) -> usize {
+        Self::get_texture_size()
+    }
+
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
     /// a number that represents this block in memory
     fn id(&self) -> usize {
         self.id
     }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.texture.path(slot)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        self.texture.paths()
+    }
+
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        [
+            (Some(FaceDir::Up), self.models.up),
+            (Some(FaceDir::North), self.models.north),
+            (Some(FaceDir::West), self.models.west),
+            (Some(FaceDir::East), self.models.east),
+            (Some(FaceDir::South), self.models.south),
+            (Some(FaceDir::Down), self.models.down),
+            (None, self.models.custom),
+        ].into_iter().filter_map(|(face, model)| Some((face, model?))).collect()
+    }
+
+    /// Each face has its own texture, in relative indexing order
+    fn texture_slot(&self, _state: usize, face: FaceDir) -> usize {
+        face.to_index()
+    }
 }
 
 pub enum FullCustomTexture {
@@ -102,6 +152,47 @@ pub enum FullCustomTexture {
     Multiple(PathsInternal)
 }
 
+impl FullCustomTexture {
+    /// The texture file of a slot, in relative indexing order
+    pub fn path(&self, slot: usize) -> Option<&'static str> {
+        match self {
+            FullCustomTexture::Single(path) => path.filter(|_| slot < 6),
+            FullCustomTexture::Multiple(paths) => match slot {
+                0 => paths.up,
+                1 => paths.north,
+                2 => paths.west,
+                3 => paths.east,
+                4 => paths.south,
+                5 => paths.down,
+                _ => None,
+            },
+        }
+    }
+
+    /// Every texture file set, without repeats
+    pub fn paths(&self) -> Vec<&'static str> {
+        let all: Vec<&'static str> = match self {
+            FullCustomTexture::Single(path) => path.iter().copied().collect(),
+            FullCustomTexture::Multiple(paths) => [
+                paths.up,
+                paths.north,
+                paths.west,
+                paths.east,
+                paths.south,
+                paths.down,
+                paths.custom,
+            ].into_iter().flatten().collect(),
+        };
+        let mut paths = vec![];
+        for path in all {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
 /// A strict helper struct for constructing a fully-defined [`PathsInternal`] instance.
 ///
 /// Unlike [`with_full_paths!`], `Paths` requires **all six fields** to be
@@ -1,3 +1,5 @@
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::rotateable::AllRotFacing, geometry::{BlockTransform, Quad}, properties::BlockProperties, shape::VoxelShape}, colormap::TintSource, ctm::CtmLayout, variants::TextureVariants};
+
 pub mod basic;
 pub mod custom;
 pub mod full_custom;
@@ -5,9 +7,180 @@ pub mod full;
 pub mod pillar;
 pub mod rotateable;
 pub mod reflectable;
+pub mod slab;
+pub mod slope;
+pub mod stair;
 
 pub trait BlockType: Send + Sync {
     fn name(&self) -> &'static str;
     fn id(&self) -> usize;
     fn index(&self) -> usize;
-}
\ No newline at end of file
+
+    /// The number of textures this block uses, starting from its index
+    fn texture_span(&self) -> usize {
+        1
+    }
+
+    /// The number of states this block has, 
+    /// and so the number of ids it takes up
+    fn id_span(&self) -> usize {
+        1
+    }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        None
+    }
+
+    /// The block light given off by one of this block's states,
+    /// where `state` is relative to this block's id.
+    fn light_emission(&self, state: usize) -> u8 {
+        self.properties().map_or(0, |properties| properties.light_emission.get(state))
+    }
+
+    /// How much light is lost passing through one of this block's states,
+    /// where `state` is relative to this block's id.
+    /// 
+    /// Unless set in the block's properties, states made of six 
+    /// faces that fully cull block all light, and other states block none.
+    fn light_opacity(&self, state: usize) -> u8 {
+        if let Some(opacity) = self.properties().and_then(|properties| properties.light_opacity) {
+            return opacity;
+        }
+        let solid = FaceDir::ALL.iter()
+            .all(|face| matches!(self.culling(state, *face), CullingFlag::Both));
+        if solid { 15 } else { 0 }
+    }
+
+    /// The culling behaviour of a face for one of this block's states,
+    /// where `state` is relative to this block's id.
+    /// 
+    /// Most blocks are solid cubes, so the default culls and is culled by every face.
+    fn culling(&self, _state: usize, _face: FaceDir) -> CullingFlag {
+        CullingFlag::Both
+    }
+
+    /// Geometry generated by the block itself for one of its states,
+    /// where `state` is relative to this block's id.
+    /// 
+    /// Returns `None` when the block is built from its textures and models instead.
+    fn quads(&self, _state: usize) -> Option<Vec<Quad>> {
+        None
+    }
+
+    /// Whether one of this block's states is drawn as a textured cube,
+    /// where `state` is relative to this block's id.
+    ///
+    /// States that aren't cubes are drawn from their quads, or their models if they have none.
+    fn is_cube(&self, _state: usize) -> bool {
+        true
+    }
+
+    /// The texture used by a face of one of this block's states, relative to the block's index,
+    /// where `state` is relative to this block's id.
+    fn texture_slot(&self, _state: usize, _face: FaceDir) -> usize {
+        0
+    }
+
+    /// The texture file used for a texture slot, relative to the block's index
+    fn texture_path(&self, _slot: usize) -> Option<&'static str> {
+        None
+    }
+
+    /// The layout of a texture slot's connected texture, relative to the block's index.
+    ///
+    /// Returns `None` for slots whose texture is a single tile.
+    fn connected_texture(&self, _slot: usize) -> Option<CtmLayout> {
+        None
+    }
+
+    /// The weighted alternatives of a texture slot, relative to the block's index.
+    ///
+    /// Returns `None` for slots that always use the same texture.
+    fn texture_variants(&self, _slot: usize) -> Option<&TextureVariants> {
+        None
+    }
+
+    /// Where the colour of a texture slot comes from, relative to the block's index
+    fn tint_source(&self, _slot: usize) -> TintSource {
+        TintSource::None
+    }
+
+    /// The texture files this block is built from, each listed once
+    fn texture_paths(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// The model files this block is built from, along with the face each is made for.
+    ///
+    /// Face models shared by several faces are made for the north face,
+    /// and models of the whole block, such as custom models, have no face.
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        vec![]
+    }
+
+    /// The models drawn for one of this block's states, where `state` is relative to this block's id,
+    /// each with the face of the state it's drawn in place of and the transform that puts it there.
+    ///
+    /// By default every model from [`BlockType::model_paths`] is drawn where it was made for.
+    fn state_models(&self, _state: usize) -> Vec<(Option<FaceDir>, &'static str, BlockTransform)> {
+        self.model_paths()
+            .into_iter()
+            .map(|(face, path)| (face, path, BlockTransform::IDENTITY))
+            .collect()
+    }
+
+    /// The space one of this block's states takes up, derived from
+    /// the block's type, where `state` is relative to this block's id.
+    ///
+    /// Most blocks are solid cubes, so the default fills the whole block.
+    fn shape(&self, _state: usize) -> VoxelShape {
+        VoxelShape::FULL
+    }
+
+    /// The shape entities collide with for one of this block's states,
+    /// where `state` is relative to this block's id.
+    ///
+    /// Unless set in the block's properties, this is the block's [`BlockType::shape`].
+    fn collision_shape(&self, state: usize) -> VoxelShape {
+        match self.properties().and_then(|properties| properties.collision_shape) {
+            Some(boxes) => VoxelShape::new(boxes),
+            None => self.shape(state),
+        }
+    }
+
+    /// The shape outlined and hit when looking at one of this block's states,
+    /// where `state` is relative to this block's id.
+    ///
+    /// Unless set in the block's properties, this is the block's [`BlockType::shape`],
+    /// so blocks without collision can still be selected.
+    fn selection_shape(&self, state: usize) -> VoxelShape {
+        match self.properties().and_then(|properties| properties.selection_shape) {
+            Some(boxes) => VoxelShape::new(boxes),
+            None => self.shape(state),
+        }
+    }
+}
+
+/// Places a face model made for the north face on `face`
+pub(crate) const fn north_model_on(face: FaceDir) -> BlockTransform {
+    AllRotFacing::from_face(face).transform()
+}
+
+/// The texture files used by every texture slot of a block, including
+/// every variant, each listed once
+pub(crate) fn slot_paths<B: ?Sized + BlockType>(block: &B) -> Vec<&'static str> {
+    let mut paths = vec![];
+    for slot in 0..block.texture_span() {
+        let slot_paths: Vec<&'static str> = match block.texture_variants(slot) {
+            Some(variants) => variants.iter().map(|(path, _)| path).collect(),
+            None => block.texture_path(slot).into_iter().collect(),
+        };
+        for path in slot_paths {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
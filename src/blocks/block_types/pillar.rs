@@ -1,4 +1,4 @@
-use crate::{blocks::{block_types::BlockType, const_into::ConstInto}, Buildable, HasBuildVariants};
+use crate::{blocks::{block::FaceDir, block_types::{north_model_on, slot_paths, BlockType}, const_into::ConstInto, geometry::BlockTransform, properties::BlockProperties}, colormap::TintSource, ctm::CtmLayout, variants::TextureVariants, Buildable, HasBuildVariants};
 
 /// A Block where the top, bottom, and side faces 
 /// have different textures and models, meaning that each side face 
@@ -8,8 +8,19 @@ pub struct Pillar {
     pub(crate) name: &'static str,
     /// If no texture is defined, the default texture (a debug texture) will be used.
     pub(crate) texture: PillarTexture,
+    /// The layout of the up, sides, and down connected textures.
+    /// If no layout is defined, the texture is a single tile.
+    pub(crate) connected: [Option<CtmLayout>; 3],
+    /// The weighted alternatives of each texture, for the up, sides, and down textures.
+    /// If no variants are defined, every block uses the same texture.
+    pub(crate) variants: [Option<TextureVariants>; 3],
+    /// The tint of each texture, for the up, sides, and down textures.
+    /// If no tint is defined, the texture isn't tinted.
+    pub(crate) tints: [TintSource; 3],
     /// If no model is defined, the default model (a normal cube face) will be used.
     pub(crate) models: PathsInternal,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
     /// a number that represents this block in memory
     pub(crate) id: usize,
     /// a value that represents the index 
@@ -31,6 +42,34 @@ impl Pillar {
         self
     } 
 
+    /// Makes a face's texture a sheet of connected texture tiles.
+    ///
+    /// The side faces share a texture, so connecting one connects them all.
+    pub const fn with_connected_texture(mut self, face: FaceDir, layout: CtmLayout) -> Self {
+        self.connected[texture_slot(face)] = Some(layout);
+        self
+    }
+
+    /// Picks a face's texture from weighted alternatives by each block's position
+    ///
+    /// The side faces share a texture, so setting one sets them all.
+    pub const fn with_texture_variants(
+        mut self,
+        face: FaceDir,
+        variants: impl const ConstInto<TextureVariants>
+    ) -> Self {
+        self.variants[texture_slot(face)] = Some(variants.const_into());
+        self
+    }
+
+    /// Colours a face's texture, such as by the grass or foliage colormap
+    ///
+    /// The side faces share a texture, so tinting one tints them all.
+    pub const fn with_tint(mut self, face: FaceDir, tint: TintSource) -> Self {
+        self.tints[texture_slot(face)] = tint;
+        self
+    }
+
     pub const fn with_models(
         mut self, 
         models: impl const ConstInto<PathsInternal>
@@ -38,6 +77,11 @@ impl Pillar {
         self.models = models.const_into();
         self
     } 
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
 }
 
 impl const Buildable for Pillar {
@@ -45,11 +89,15 @@ impl const Buildable for Pillar {
         Pillar {
             name,
             texture: PillarTexture::Single(None),
+            connected: [None; 3],
+            variants: [None; 3],
+            tints: [TintSource::None; 3],
             models: PathsInternal { 
                 up: None, 
                 sides: None, 
                 down: None 
             },
+            properties: None,
             id: 0,
             index: 0
         }
@@ -69,6 +117,9 @@ impl const Buildable for Pillar {
     fn set_id(&mut self, id: usize) {
         self.id = id;
     }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
 }
 
 impl HasBuildVariants for Pillar {
@@ -87,10 +138,84 @@ impl BlockType for Pillar {
         self.index
     }
 
+    fn texture_span(&self) -> usize {
+        Self::get_texture_size()
+    }
+
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
     /// a number that represents this block in memory
     fn id(&self) -> usize {
         self.id
     }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    /// The sides model is shared by every side face
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        match self.variants.get(slot) {
+            Some(Some(variants)) => Some(variants.first()),
+            _ => self.texture.path(slot),
+        }
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        slot_paths(self)
+    }
+
+    fn connected_texture(&self, slot: usize) -> Option<CtmLayout> {
+        self.connected.get(slot).copied().flatten()
+    }
+
+    fn texture_variants(&self, slot: usize) -> Option<&TextureVariants> {
+        self.variants.get(slot)?.as_ref()
+    }
+
+    fn tint_source(&self, slot: usize) -> TintSource {
+        self.tints.get(slot).copied().unwrap_or_default()
+    }
+
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        [
+            (Some(FaceDir::Up), self.models.up),
+            (Some(FaceDir::North), self.models.sides),
+            (Some(FaceDir::Down), self.models.down),
+        ].into_iter().filter_map(|(face, model)| Some((face, model?))).collect()
+    }
+
+    /// The sides model is turned to face each side face of the block
+    fn state_models(&self, _state: usize) -> Vec<(Option<FaceDir>, &'static str, BlockTransform)> {
+        FaceDir::ALL.into_iter()
+            .filter_map(|face| {
+                let (model, transform) = match face {
+                    FaceDir::Up => (self.models.up, BlockTransform::IDENTITY),
+                    FaceDir::Down => (self.models.down, BlockTransform::IDENTITY),
+                    side => (self.models.sides, north_model_on(side)),
+                };
+                Some((Some(face), model?, transform))
+            })
+            .collect()
+    }
+
+    fn texture_slot(&self, _state: usize, face: FaceDir) -> usize {
+        texture_slot(face)
+    }
+}
+
+/// The texture of a face relative to the index of a block 
+/// using a pillar texture set, laid out as up, sides, then down.
+pub const fn texture_slot(face: FaceDir) -> usize {
+    match face {
+        FaceDir::Up => 0,
+        FaceDir::Down => 2,
+        _ => 1,
+    }
 }
 
 pub enum PillarTexture {
@@ -98,6 +223,40 @@ pub enum PillarTexture {
     Multiple(PathsInternal)
 }
 
+impl PillarTexture {
+    /// The texture file of a slot, laid out as up, sides, then down
+    pub fn path(&self, slot: usize) -> Option<&'static str> {
+        match self {
+            PillarTexture::Single(path) => path.filter(|_| slot < 3),
+            PillarTexture::Multiple(paths) => match slot {
+                0 => paths.up,
+                1 => paths.sides,
+                2 => paths.down,
+                _ => None,
+            },
+        }
+    }
+
+    /// Every texture file set, without repeats
+    pub fn paths(&self) -> Vec<&'static str> {
+        let all: Vec<&'static str> = match self {
+            PillarTexture::Single(path) => path.iter().copied().collect(),
+            PillarTexture::Multiple(paths) => [
+                paths.up,
+                paths.sides,
+                paths.down,
+            ].into_iter().flatten().collect(),
+        };
+        let mut paths = vec![];
+        for path in all {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
 /// A strict helper struct for constructing a fully-defined [`PathsInternal`] instance.
 ///
 /// Unlike [`with_full_paths!`], `Paths` requires **all six fields** to be
@@ -1,4 +1,4 @@
-use crate::{blocks::block_types::BlockType, AsId, Buildable, HasBuildVariants};
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::BlockType, geometry::{BlockTransform, Quad}, properties::BlockProperties, shape::VoxelShape}, colormap::TintSource, ctm::CtmLayout, variants::TextureVariants, AsId, Buildable, HasBuildVariants};
 
 /// A Block that can be reflected across the xz plane
 pub struct Reflectable<B> 
@@ -9,10 +9,21 @@ where
     pub(crate) block_data: B
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ReflectionFacing {
     Up, Down
 }
 
+impl ReflectionFacing {
+    /// The transform from an upright block to this spin
+    pub const fn transform(&self) -> BlockTransform {
+        match self {
+            ReflectionFacing::Up => BlockTransform::IDENTITY,
+            ReflectionFacing::Down => BlockTransform::REFLECT_Y,
+        }
+    }
+}
+
 impl AsId for ReflectionFacing {
     type Name = &'static str;
     const NAME: Self::Name = "spin";
@@ -44,6 +55,16 @@ impl AsId for ReflectionFacing {
     }
 }
 
+impl<B> Reflectable<B> 
+where 
+    B: const Buildable + BlockType + Sized
+{
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.block_data.set_properties(properties);
+        self
+    }
+}
+
 impl<B> const Buildable for Reflectable<B> 
 where 
     B: const Buildable + BlockType + Sized
@@ -71,6 +92,9 @@ where
     fn set_id(&mut self, id: usize) {
         self.block_data.set_id(id);
     }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.block_data.set_properties(properties);
+    }
 }
 
 impl<B> HasBuildVariants for Reflectable<B> 
@@ -80,9 +104,22 @@ where
     type Variants = (ReflectionFacing, B::Variants);
 }
 
+impl<B> Reflectable<B>
+where
+    B: BlockType + HasBuildVariants + Sized
+{
+    /// Splits a state into the transform of its spin
+    /// and the state of the inner block
+    fn split_state(state: usize) -> (BlockTransform, usize) {
+        let inner_span = <B::Variants as AsId>::get_id_span();
+        let spin = ReflectionFacing::from_id(state / inner_span);
+        (spin.transform(), state % inner_span)
+    }
+}
+
 impl<B> BlockType for Reflectable<B>
 where
-    B: BlockType + Sized
+    B: BlockType + HasBuildVariants + Sized
 {
     fn name(&self) -> &'static str {
         self.block_data.name()
@@ -95,4 +132,85 @@ where
     fn index(&self) -> usize {
         self.block_data.index()
     }
+
+    fn texture_span(&self) -> usize {
+        self.block_data.texture_span()
+    }
+
+    fn id_span(&self) -> usize {
+        2 * self.block_data.id_span()
+    }
+
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.block_data.properties()
+    }
+
+    /// Models are made for the unrotated block
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.block_data.texture_path(slot)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        self.block_data.texture_paths()
+    }
+
+    fn connected_texture(&self, slot: usize) -> Option<CtmLayout> {
+        self.block_data.connected_texture(slot)
+    }
+
+    fn texture_variants(&self, slot: usize) -> Option<&TextureVariants> {
+        self.block_data.texture_variants(slot)
+    }
+
+    fn tint_source(&self, slot: usize) -> TintSource {
+        self.block_data.tint_source(slot)
+    }
+
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        self.block_data.model_paths()
+    }
+
+    fn state_models(&self, state: usize) -> Vec<(Option<FaceDir>, &'static str, BlockTransform)> {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.state_models(inner)
+            .into_iter()
+            .map(|(face, path, inner)| (face.map(|face| transform.apply_face(face)), path, inner.then(transform)))
+            .collect()
+    }
+
+    fn culling(&self, state: usize, face: FaceDir) -> CullingFlag {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.culling(inner, transform.inverse().apply_face(face))
+    }
+
+    fn quads(&self, state: usize) -> Option<Vec<Quad>> {
+        let (transform, inner) = Self::split_state(state);
+        let quads = self.block_data.quads(inner)?;
+        Some(quads.into_iter().map(|quad| quad.transformed(transform)).collect())
+    }
+
+    fn shape(&self, state: usize) -> VoxelShape {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.shape(inner).transformed(transform)
+    }
+
+    fn collision_shape(&self, state: usize) -> VoxelShape {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.collision_shape(inner).transformed(transform)
+    }
+
+    fn selection_shape(&self, state: usize) -> VoxelShape {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.selection_shape(inner).transformed(transform)
+    }
+
+    fn is_cube(&self, state: usize) -> bool {
+        let (_, inner) = Self::split_state(state);
+        self.block_data.is_cube(inner)
+    }
+
+    fn texture_slot(&self, state: usize, face: FaceDir) -> usize {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.texture_slot(inner, transform.inverse().apply_face(face))
+    }
 }
\ No newline at end of file
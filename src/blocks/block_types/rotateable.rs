@@ -1,6 +1,6 @@
 use std::marker::ConstParamTy;
 
-use crate::{blocks::block_types::BlockType, AsId, Buildable, HasBuildVariants};
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::BlockType, geometry::{BlockTransform, Quad}, properties::BlockProperties, shape::VoxelShape}, colormap::TintSource, ctm::CtmLayout, variants::TextureVariants, AsId, Buildable, HasBuildVariants};
 
 /// A Block that can be rotated in various ways constrained by [`RotationDir`]
 pub struct Rotateable<B, const ROT: RotDir> 
@@ -23,10 +23,23 @@ pub enum RotDir {
     All
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum YRotFacing {
     North, West, East, South
 }
 
+impl YRotFacing {
+    /// The transform from a north facing block to this facing
+    pub const fn transform(&self) -> BlockTransform {
+        match self {
+            YRotFacing::North => BlockTransform::IDENTITY,
+            YRotFacing::West  => BlockTransform::rotate_y(3),
+            YRotFacing::East  => BlockTransform::rotate_y(1),
+            YRotFacing::South => BlockTransform::rotate_y(2),
+        }
+    }
+}
+
 impl AsId for YRotFacing {
     type Name = &'static str;
     const NAME: Self::Name = "facing";
@@ -64,10 +77,22 @@ impl AsId for YRotFacing {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum AxisRotFacing {
     X, Y, Z
 }
 
+impl AxisRotFacing {
+    /// The transform from a block aligned to the Y axis to this axis
+    pub const fn transform(&self) -> BlockTransform {
+        match self {
+            AxisRotFacing::X => BlockTransform::rotate_z(1),
+            AxisRotFacing::Y => BlockTransform::IDENTITY,
+            AxisRotFacing::Z => BlockTransform::rotate_x(1),
+        }
+    }
+}
+
 impl AsId for AxisRotFacing {
     type Name = &'static str;
     const NAME: Self::Name = "axis";
@@ -102,10 +127,37 @@ impl AsId for AxisRotFacing {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum AllRotFacing {
     Up, North, West, East, South, Down
 }
 
+impl AllRotFacing {
+    /// The facing pointing towards a face
+    pub const fn from_face(face: FaceDir) -> AllRotFacing {
+        match face {
+            FaceDir::Up    => AllRotFacing::Up,
+            FaceDir::North => AllRotFacing::North,
+            FaceDir::West  => AllRotFacing::West,
+            FaceDir::East  => AllRotFacing::East,
+            FaceDir::South => AllRotFacing::South,
+            FaceDir::Down  => AllRotFacing::Down,
+        }
+    }
+
+    /// The transform from a north facing block to this facing
+    pub const fn transform(&self) -> BlockTransform {
+        match self {
+            AllRotFacing::Up    => BlockTransform::rotate_x(1),
+            AllRotFacing::North => BlockTransform::IDENTITY,
+            AllRotFacing::West  => BlockTransform::rotate_y(3),
+            AllRotFacing::East  => BlockTransform::rotate_y(1),
+            AllRotFacing::South => BlockTransform::rotate_y(2),
+            AllRotFacing::Down  => BlockTransform::rotate_x(3),
+        }
+    }
+}
+
 impl AsId for AllRotFacing {
     type Name = &'static str;
     const NAME: Self::Name = "direction";
@@ -149,6 +201,16 @@ impl AsId for AllRotFacing {
     }
 }
 
+impl<B, const ROT: RotDir> Rotateable<B, ROT> 
+where 
+    B: const Buildable + BlockType + Sized
+{
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.block_data.set_properties(properties);
+        self
+    }
+}
+
 impl<B, const ROT: RotDir> const Buildable for Rotateable<B, ROT> 
 where 
     B: const Buildable + BlockType + Sized,
@@ -183,6 +245,9 @@ where
     fn set_id(&mut self, id: usize) {
         self.block_data.set_id(id);
     }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.block_data.set_properties(properties);
+    }
 }
 
 impl<B> HasBuildVariants for Rotateable<B, {RotDir::Y}>
@@ -206,9 +271,27 @@ where
     type Variants = (AllRotFacing, B::Variants);
 }
 
+impl<B, const ROT: RotDir> Rotateable<B, ROT>
+where
+    B: BlockType + HasBuildVariants + Sized
+{
+    /// Splits a state into the transform of its facing
+    /// and the state of the inner block
+    fn split_state(state: usize) -> (BlockTransform, usize) {
+        let inner_span = <B::Variants as AsId>::get_id_span();
+        let facing = state / inner_span;
+        let transform = match ROT {
+            RotDir::Y => YRotFacing::from_id(facing).transform(),
+            RotDir::Axis => AxisRotFacing::from_id(facing).transform(),
+            RotDir::All => AllRotFacing::from_id(facing).transform(),
+        };
+        (transform, state % inner_span)
+    }
+}
+
 impl<B, const ROT: RotDir> BlockType for Rotateable<B, ROT>
 where
-    B: BlockType + Sized
+    B: BlockType + HasBuildVariants + Sized
 {
     fn name(&self) -> &'static str {
         self.block_data.name()
@@ -221,4 +304,89 @@ where
     fn index(&self) -> usize {
         self.block_data.index()
     }
+
+    fn texture_span(&self) -> usize {
+        self.block_data.texture_span()
+    }
+
+    fn id_span(&self) -> usize {
+        self.block_data.id_span() * match ROT {
+            RotDir::Y => 4,
+            RotDir::Axis => 3,
+            RotDir::All => 6,
+        }
+    }
+
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.block_data.properties()
+    }
+
+    /// Models are made for the unrotated block
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.block_data.texture_path(slot)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        self.block_data.texture_paths()
+    }
+
+    fn connected_texture(&self, slot: usize) -> Option<CtmLayout> {
+        self.block_data.connected_texture(slot)
+    }
+
+    fn texture_variants(&self, slot: usize) -> Option<&TextureVariants> {
+        self.block_data.texture_variants(slot)
+    }
+
+    fn tint_source(&self, slot: usize) -> TintSource {
+        self.block_data.tint_source(slot)
+    }
+
+    fn model_paths(&self) -> Vec<(Option<FaceDir>, &'static str)> {
+        self.block_data.model_paths()
+    }
+
+    fn state_models(&self, state: usize) -> Vec<(Option<FaceDir>, &'static str, BlockTransform)> {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.state_models(inner)
+            .into_iter()
+            .map(|(face, path, inner)| (face.map(|face| transform.apply_face(face)), path, inner.then(transform)))
+            .collect()
+    }
+
+    fn culling(&self, state: usize, face: FaceDir) -> CullingFlag {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.culling(inner, transform.inverse().apply_face(face))
+    }
+
+    fn quads(&self, state: usize) -> Option<Vec<Quad>> {
+        let (transform, inner) = Self::split_state(state);
+        let quads = self.block_data.quads(inner)?;
+        Some(quads.into_iter().map(|quad| quad.transformed(transform)).collect())
+    }
+
+    fn shape(&self, state: usize) -> VoxelShape {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.shape(inner).transformed(transform)
+    }
+
+    fn collision_shape(&self, state: usize) -> VoxelShape {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.collision_shape(inner).transformed(transform)
+    }
+
+    fn selection_shape(&self, state: usize) -> VoxelShape {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.selection_shape(inner).transformed(transform)
+    }
+
+    fn is_cube(&self, state: usize) -> bool {
+        let (_, inner) = Self::split_state(state);
+        self.block_data.is_cube(inner)
+    }
+
+    fn texture_slot(&self, state: usize, face: FaceDir) -> usize {
+        let (transform, inner) = Self::split_state(state);
+        self.block_data.texture_slot(inner, transform.inverse().apply_face(face))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,206 @@
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::{pillar::{self, PathsInternal, PillarTexture}, BlockType}, const_into::ConstInto, geometry::{BlockTransform, Octants, Quad}, properties::BlockProperties, shape::{Aabb, VoxelShape}}, AsId, Buildable, HasBuildVariants};
+
+/// A half block that sits in either the bottom or top half of its space,
+/// or is doubled up into a full block.
+///
+/// Slabs use a pillar texture set: the top and bottom faces use the
+/// `up` and `down` textures, and every side face uses the `sides` texture.
+pub struct Slab {
+    /// The block's technical name
+    pub(crate) name: &'static str,
+    /// If no texture is defined, the default texture (a debug texture) will be used.
+    pub(crate) texture: PillarTexture,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
+    /// a number that represents this block in memory
+    pub(crate) id: usize,
+    /// a value that represents the index
+    /// of this block's corresponding texture and material
+    pub(crate) index: usize,
+}
+
+impl Slab {
+    pub const fn with_texture(mut self, texture: &'static str) -> Self {
+        self.texture = PillarTexture::Single(Some(texture));
+        self
+    }
+
+    pub const fn with_textures(
+        mut self,
+        textures: impl const ConstInto<PathsInternal>
+    ) -> Self {
+        self.texture = PillarTexture::Multiple(textures.const_into());
+        self
+    }
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// A shape set in the block's properties, which is that of a bottom slab
+    /// and is flipped upside down for top slabs.  Double slabs use it unchanged.
+    fn property_shape(&self, state: usize, shape: impl Fn(&BlockProperties) -> Option<&'static [Aabb]>) -> VoxelShape {
+        match self.properties.as_ref().and_then(shape) {
+            Some(boxes) if matches!(SlabType::from_id(state), SlabType::Top) => {
+                VoxelShape::new(boxes).transformed(BlockTransform::REFLECT_Y)
+            }
+            Some(boxes) => VoxelShape::new(boxes),
+            None => self.shape(state),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SlabType {
+    Bottom, Top, Double
+}
+
+impl SlabType {
+    /// The parts of the block this slab fills
+    pub const fn octants(&self) -> Octants {
+        match self {
+            SlabType::Bottom => Octants::BOTTOM,
+            SlabType::Top    => Octants::TOP,
+            SlabType::Double => Octants::FULL,
+        }
+    }
+}
+
+impl AsId for SlabType {
+    type Name = &'static str;
+    const NAME: Self::Name = "type";
+
+    fn from_id(id: usize) -> Self {
+        match id {
+            0 => Self::Bottom,
+            1 => Self::Top,
+            2 => Self::Double,
+            _ => panic!("{id} is an invalid Id for SlabType")
+        }
+    }
+
+    fn to_id(&self) -> usize {
+        match self {
+            SlabType::Bottom => 0,
+            SlabType::Top    => 1,
+            SlabType::Double => 2,
+        }
+    }
+
+    fn get_id_span() -> usize {
+        3
+    }
+
+    fn to_string(&self) -> String {
+        Self::NAME.to_string() + ": " + match self {
+            SlabType::Bottom => "bottom",
+            SlabType::Top    => "top",
+            SlabType::Double => "double",
+        }
+    }
+}
+
+impl const Buildable for Slab {
+    fn new_with_name(name: &'static str) -> Self {
+        Slab {
+            name,
+            texture: PillarTexture::Single(None),
+            properties: None,
+            id: 0,
+            index: 0
+        }
+    }
+    fn get_texture_size() -> usize {3usize}
+    fn get_id_span() -> usize {3usize}
+    fn with_index(mut self, idx: usize) -> Self {
+        self.index = idx;
+        self
+    }
+    fn set_index(&mut self, idx: usize) {
+        self.index = idx;
+    }
+    fn with_id(mut self, id: usize) -> Self {
+        self.id = id;
+        self
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
+}
+
+impl HasBuildVariants for Slab {
+    type Variants = (SlabType, ());
+}
+
+impl BlockType for Slab {
+    /// This block's technical name
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// a value that represents the index
+    /// of this block's corresponding texture and material
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn texture_span(&self) -> usize {
+        Self::get_texture_size()
+    }
+
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
+    /// a number that represents this block in memory
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.texture.path(slot)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        self.texture.paths()
+    }
+
+    fn culling(&self, state: usize, face: FaceDir) -> CullingFlag {
+        SlabType::from_id(state).octants().culling(face)
+    }
+
+    fn quads(&self, state: usize) -> Option<Vec<Quad>> {
+        Some(SlabType::from_id(state).octants().quads(pillar::texture_slot))
+    }
+
+    fn shape(&self, state: usize) -> VoxelShape {
+        VoxelShape::from_octants(SlabType::from_id(state).octants())
+    }
+
+    fn collision_shape(&self, state: usize) -> VoxelShape {
+        self.property_shape(state, |properties| properties.collision_shape)
+    }
+
+    fn selection_shape(&self, state: usize) -> VoxelShape {
+        self.property_shape(state, |properties| properties.selection_shape)
+    }
+
+    /// Only double slabs are drawn as cubes
+    fn is_cube(&self, state: usize) -> bool {
+        matches!(SlabType::from_id(state), SlabType::Double)
+    }
+
+    fn texture_slot(&self, _state: usize, face: FaceDir) -> usize {
+        pillar::texture_slot(face)
+    }
+}
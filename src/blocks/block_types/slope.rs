@@ -0,0 +1,207 @@
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::{pillar::{self, PathsInternal, PillarTexture}, reflectable::ReflectionFacing, rotateable::YRotFacing, BlockType}, const_into::ConstInto, geometry::{BlockTransform, Quad}, properties::BlockProperties, shape::{Aabb, VoxelShape}}, AsId, Buildable, HasBuildVariants};
+
+/// A wedge that rises from one edge of the block to the opposite edge.
+/// It can face any horizontal direction and be flipped upside down.
+///
+/// Slopes use a pillar texture set: the sloped face uses the `up` texture,
+/// the bottom face uses the `down` texture, and the back and
+/// triangular side faces use the `sides` texture.
+pub struct Slope {
+    /// The block's technical name
+    pub(crate) name: &'static str,
+    /// If no texture is defined, the default texture (a debug texture) will be used.
+    pub(crate) texture: PillarTexture,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
+    /// a number that represents this block in memory
+    pub(crate) id: usize,
+    /// a value that represents the index
+    /// of this block's corresponding texture and material
+    pub(crate) index: usize,
+}
+
+impl Slope {
+    pub const fn with_texture(mut self, texture: &'static str) -> Self {
+        self.texture = PillarTexture::Single(Some(texture));
+        self
+    }
+
+    pub const fn with_textures(
+        mut self,
+        textures: impl const ConstInto<PathsInternal>
+    ) -> Self {
+        self.texture = PillarTexture::Multiple(textures.const_into());
+        self
+    }
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    fn transform(state: usize) -> BlockTransform {
+        let (spin, (facing, ())) = <Self as HasBuildVariants>::Variants::from_id(state);
+        facing.transform().then(spin.transform())
+    }
+
+    /// A shape set in the block's properties, turned to face the way the state does
+    fn property_shape(&self, state: usize, shape: impl Fn(&BlockProperties) -> Option<&'static [Aabb]>) -> VoxelShape {
+        match self.properties.as_ref().and_then(shape) {
+            Some(boxes) => VoxelShape::new(boxes).transformed(Self::transform(state)),
+            None => self.shape(state),
+        }
+    }
+
+    /// The culling behaviour of an upright slope rising towards the north
+    const fn base_culling(face: FaceDir) -> CullingFlag {
+        match face {
+            FaceDir::Down | FaceDir::North => CullingFlag::Both,
+            FaceDir::West | FaceDir::East => CullingFlag::Culling,
+            FaceDir::Up | FaceDir::South => CullingFlag::None,
+        }
+    }
+
+    /// The shape of an upright slope rising towards the north,
+    /// approximated by four steps that contain the whole slope
+    const BASE_SHAPE: VoxelShape = VoxelShape::new(&[
+        Aabb::new([0.0, 0.0, 0.0], [1.0, 0.25, 1.0]),
+        Aabb::new([0.0, 0.25, 0.0], [1.0, 0.5, 0.75]),
+        Aabb::new([0.0, 0.5, 0.0], [1.0, 0.75, 0.5]),
+        Aabb::new([0.0, 0.75, 0.0], [1.0, 1.0, 0.25]),
+    ]);
+
+    /// The geometry of an upright slope rising towards the north
+    fn base_quads() -> Vec<Quad> {
+        let side = pillar::texture_slot(FaceDir::North);
+        let full = |face: FaceDir, texture: usize| {
+            Quad::cuboid_face(face, [0.0; 3], [1.0; 3], texture)
+        };
+        // the side faces are triangles, stored as quads with a repeated corner
+        let triangle = |face: FaceDir, dropped: usize| {
+            let mut quad = full(face, side);
+            let kept = (dropped + 1) % 4;
+            quad.positions[dropped] = quad.positions[kept];
+            quad.uvs[dropped] = quad.uvs[kept];
+            quad
+        };
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        vec![
+            full(FaceDir::Down, pillar::texture_slot(FaceDir::Down)),
+            full(FaceDir::North, side),
+            triangle(FaceDir::West, 2),
+            triangle(FaceDir::East, 3),
+            Quad {
+                positions: [[0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+                uvs: [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]],
+                normal: [0.0, diagonal, diagonal],
+                texture: pillar::texture_slot(FaceDir::Up),
+                cull_face: None,
+            },
+        ]
+    }
+}
+
+impl const Buildable for Slope {
+    fn new_with_name(name: &'static str) -> Self {
+        Slope {
+            name,
+            texture: PillarTexture::Single(None),
+            properties: None,
+            id: 0,
+            index: 0
+        }
+    }
+    fn get_texture_size() -> usize {3usize}
+    /// 2 spins and 4 facings
+    fn get_id_span() -> usize {8usize}
+    fn with_index(mut self, idx: usize) -> Self {
+        self.index = idx;
+        self
+    }
+    fn set_index(&mut self, idx: usize) {
+        self.index = idx;
+    }
+    fn with_id(mut self, id: usize) -> Self {
+        self.id = id;
+        self
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
+}
+
+impl HasBuildVariants for Slope {
+    type Variants = (ReflectionFacing, (YRotFacing, ()));
+}
+
+impl BlockType for Slope {
+    /// This block's technical name
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// a value that represents the index
+    /// of this block's corresponding texture and material
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn texture_span(&self) -> usize {
+        Self::get_texture_size()
+    }
+
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
+    /// a number that represents this block in memory
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.texture.path(slot)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        self.texture.paths()
+    }
+
+    fn culling(&self, state: usize, face: FaceDir) -> CullingFlag {
+        Self::base_culling(Self::transform(state).inverse().apply_face(face))
+    }
+
+    fn quads(&self, state: usize) -> Option<Vec<Quad>> {
+        let transform = Self::transform(state);
+        Some(Self::base_quads().into_iter().map(|quad| quad.transformed(transform)).collect())
+    }
+
+    fn shape(&self, state: usize) -> VoxelShape {
+        Self::BASE_SHAPE.transformed(Self::transform(state))
+    }
+
+    fn collision_shape(&self, state: usize) -> VoxelShape {
+        self.property_shape(state, |properties| properties.collision_shape)
+    }
+
+    fn selection_shape(&self, state: usize) -> VoxelShape {
+        self.property_shape(state, |properties| properties.selection_shape)
+    }
+
+    fn is_cube(&self, _state: usize) -> bool {
+        false
+    }
+
+    fn texture_slot(&self, _state: usize, face: FaceDir) -> usize {
+        pillar::texture_slot(face)
+    }
+}
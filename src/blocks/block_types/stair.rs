@@ -0,0 +1,226 @@
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::{pillar::{self, PathsInternal, PillarTexture}, reflectable::ReflectionFacing, rotateable::YRotFacing, BlockType}, const_into::ConstInto, geometry::{BlockTransform, Octants, Quad}, properties::BlockProperties, shape::{Aabb, VoxelShape}}, AsId, Buildable, HasBuildVariants};
+
+/// A stair that can face any horizontal direction, be flipped upside down,
+/// and bend into inner and outer corners.
+///
+/// Stairs use a pillar texture set: the top and bottom faces use the
+/// `up` and `down` textures, and every side face uses the `sides` texture.
+pub struct Stair {
+    /// The block's technical name
+    pub(crate) name: &'static str,
+    /// If no texture is defined, the default texture (a debug texture) will be used.
+    pub(crate) texture: PillarTexture,
+    /// Gameplay properties, if none are defined the block has no properties.
+    pub(crate) properties: Option<BlockProperties>,
+    /// a number that represents this block in memory
+    pub(crate) id: usize,
+    /// a value that represents the index
+    /// of this block's corresponding texture and material
+    pub(crate) index: usize,
+}
+
+impl Stair {
+    pub const fn with_texture(mut self, texture: &'static str) -> Self {
+        self.texture = PillarTexture::Single(Some(texture));
+        self
+    }
+
+    pub const fn with_textures(
+        mut self,
+        textures: impl const ConstInto<PathsInternal>
+    ) -> Self {
+        self.texture = PillarTexture::Multiple(textures.const_into());
+        self
+    }
+
+    pub const fn with_properties(mut self, properties: BlockProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// The rotation and reflection of a stair state from an upright, north facing stair
+    fn transform(state: usize) -> BlockTransform {
+        let (spin, (facing, _)) = <Self as HasBuildVariants>::Variants::from_id(state);
+        facing.transform().then(spin.transform())
+    }
+
+    /// The parts of the block a stair state fills
+    fn octants(state: usize) -> Octants {
+        let (_, (_, (shape, ()))) = <Self as HasBuildVariants>::Variants::from_id(state);
+        shape.octants().transformed(Self::transform(state))
+    }
+
+    /// A shape set in the block's properties, turned to face the way the state does.
+    ///
+    /// Every shape of stair shares the same override.
+    fn property_shape(&self, state: usize, shape: impl Fn(&BlockProperties) -> Option<&'static [Aabb]>) -> VoxelShape {
+        match self.properties.as_ref().and_then(shape) {
+            Some(boxes) => VoxelShape::new(boxes).transformed(Self::transform(state)),
+            None => self.shape(state),
+        }
+    }
+}
+
+/// The shape of a stair, where left and right are from the point of view
+/// of someone walking up the stair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StairShape {
+    Straight, InnerLeft, InnerRight, OuterLeft, OuterRight
+}
+
+impl StairShape {
+    /// The parts of the block an upright, north facing stair of this shape fills
+    pub const fn octants(&self) -> Octants {
+        match self {
+            StairShape::Straight   => Octants::BOTTOM.with(0, 1, 0).with(1, 1, 0),
+            StairShape::InnerLeft  => Octants::BOTTOM.with(0, 1, 0).with(1, 1, 0).with(0, 1, 1),
+            StairShape::InnerRight => Octants::BOTTOM.with(0, 1, 0).with(1, 1, 0).with(1, 1, 1),
+            StairShape::OuterLeft  => Octants::BOTTOM.with(0, 1, 0),
+            StairShape::OuterRight => Octants::BOTTOM.with(1, 1, 0),
+        }
+    }
+}
+
+impl AsId for StairShape {
+    type Name = &'static str;
+    const NAME: Self::Name = "shape";
+
+    fn from_id(id: usize) -> Self {
+        match id {
+            0 => Self::Straight,
+            1 => Self::InnerLeft,
+            2 => Self::InnerRight,
+            3 => Self::OuterLeft,
+            4 => Self::OuterRight,
+            _ => panic!("{id} is an invalid Id for StairShape")
+        }
+    }
+
+    fn to_id(&self) -> usize {
+        match self {
+            StairShape::Straight   => 0,
+            StairShape::InnerLeft  => 1,
+            StairShape::InnerRight => 2,
+            StairShape::OuterLeft  => 3,
+            StairShape::OuterRight => 4,
+        }
+    }
+
+    fn get_id_span() -> usize {
+        5
+    }
+
+    fn to_string(&self) -> String {
+        Self::NAME.to_string() + ": " + match self {
+            StairShape::Straight   => "straight",
+            StairShape::InnerLeft  => "inner_left",
+            StairShape::InnerRight => "inner_right",
+            StairShape::OuterLeft  => "outer_left",
+            StairShape::OuterRight => "outer_right",
+        }
+    }
+}
+
+impl const Buildable for Stair {
+    fn new_with_name(name: &'static str) -> Self {
+        Stair {
+            name,
+            texture: PillarTexture::Single(None),
+            properties: None,
+            id: 0,
+            index: 0
+        }
+    }
+    fn get_texture_size() -> usize {3usize}
+    /// 2 spins, 4 facings, and 5 shapes
+    fn get_id_span() -> usize {40usize}
+    fn with_index(mut self, idx: usize) -> Self {
+        self.index = idx;
+        self
+    }
+    fn set_index(&mut self, idx: usize) {
+        self.index = idx;
+    }
+    fn with_id(mut self, id: usize) -> Self {
+        self.id = id;
+        self
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    fn set_properties(&mut self, properties: BlockProperties) {
+        self.properties = Some(properties);
+    }
+}
+
+impl HasBuildVariants for Stair {
+    type Variants = (ReflectionFacing, (YRotFacing, (StairShape, ())));
+}
+
+impl BlockType for Stair {
+    /// This block's technical name
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// a value that represents the index
+    /// of this block's corresponding texture and material
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn texture_span(&self) -> usize {
+        Self::get_texture_size()
+    }
+
+    /// the number of states this block has
+    fn id_span(&self) -> usize {
+        <Self as Buildable>::get_id_span()
+    }
+
+    /// a number that represents this block in memory
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Gameplay properties shared by every state of this block
+    fn properties(&self) -> Option<&BlockProperties> {
+        self.properties.as_ref()
+    }
+
+    fn texture_path(&self, slot: usize) -> Option<&'static str> {
+        self.texture.path(slot)
+    }
+
+    fn texture_paths(&self) -> Vec<&'static str> {
+        self.texture.paths()
+    }
+
+    fn culling(&self, state: usize, face: FaceDir) -> CullingFlag {
+        Self::octants(state).culling(face)
+    }
+
+    fn quads(&self, state: usize) -> Option<Vec<Quad>> {
+        Some(Self::octants(state).quads(pillar::texture_slot))
+    }
+
+    fn shape(&self, state: usize) -> VoxelShape {
+        VoxelShape::from_octants(Self::octants(state))
+    }
+
+    fn collision_shape(&self, state: usize) -> VoxelShape {
+        self.property_shape(state, |properties| properties.collision_shape)
+    }
+
+    fn selection_shape(&self, state: usize) -> VoxelShape {
+        self.property_shape(state, |properties| properties.selection_shape)
+    }
+
+    fn is_cube(&self, _state: usize) -> bool {
+        false
+    }
+
+    fn texture_slot(&self, _state: usize, face: FaceDir) -> usize {
+        pillar::texture_slot(face)
+    }
+}
@@ -0,0 +1,408 @@
+use crate::blocks::block::{CullingFlag, FaceDir};
+
+/// A rotation or reflection of block space that maps the unit cube onto itself.
+///
+/// Transforms pivot around the center of the block,
+/// so a transformed block always stays inside of its own cell.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct BlockTransform {
+    /// Row major matrix, only ever containing 0, 1, or -1
+    matrix: [[i32; 3]; 3]
+}
+
+impl BlockTransform {
+    pub const IDENTITY: BlockTransform = BlockTransform {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]]
+    };
+
+    /// Rotates a quarter turn around the Y axis
+    /// such that north turns into east
+    const ROTATE_Y: BlockTransform = BlockTransform {
+        matrix: [[0, 0, -1], [0, 1, 0], [1, 0, 0]]
+    };
+
+    /// Rotates a quarter turn around the X axis
+    /// such that north turns into up
+    const ROTATE_X: BlockTransform = BlockTransform {
+        matrix: [[1, 0, 0], [0, 0, -1], [0, 1, 0]]
+    };
+
+    /// Rotates a quarter turn around the Z axis
+    /// such that up turns into east
+    const ROTATE_Z: BlockTransform = BlockTransform {
+        matrix: [[0, 1, 0], [-1, 0, 0], [0, 0, 1]]
+    };
+
+    /// Reflects across the yz plane, swapping east and west
+    pub const REFLECT_X: BlockTransform = BlockTransform {
+        matrix: [[-1, 0, 0], [0, 1, 0], [0, 0, 1]]
+    };
+
+    /// Reflects across the xz plane, swapping up and down
+    pub const REFLECT_Y: BlockTransform = BlockTransform {
+        matrix: [[1, 0, 0], [0, -1, 0], [0, 0, 1]]
+    };
+
+    /// Reflects across the xy plane, swapping north and south
+    pub const REFLECT_Z: BlockTransform = BlockTransform {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, -1]]
+    };
+
+    /// `turns` quarter turns around the Y axis, where one turn maps north to east
+    pub const fn rotate_y(turns: u32) -> BlockTransform {
+        Self::IDENTITY.repeat(Self::ROTATE_Y, turns)
+    }
+
+    /// `turns` quarter turns around the X axis, where one turn maps north to up
+    pub const fn rotate_x(turns: u32) -> BlockTransform {
+        Self::IDENTITY.repeat(Self::ROTATE_X, turns)
+    }
+
+    /// `turns` quarter turns around the Z axis, where one turn maps up to east
+    pub const fn rotate_z(turns: u32) -> BlockTransform {
+        Self::IDENTITY.repeat(Self::ROTATE_Z, turns)
+    }
+
+    const fn repeat(self, step: BlockTransform, turns: u32) -> BlockTransform {
+        let mut result = self;
+        let mut i = 0;
+        while i < turns % 4 {
+            result = result.then(step);
+            i += 1;
+        }
+        result
+    }
+
+    /// Applies `self` first, then `next`
+    pub const fn then(self, next: BlockTransform) -> BlockTransform {
+        let mut matrix = [[0; 3]; 3];
+        let mut row = 0;
+        while row < 3 {
+            let mut col = 0;
+            while col < 3 {
+                let mut k = 0;
+                while k < 3 {
+                    matrix[row][col] += next.matrix[row][k] * self.matrix[k][col];
+                    k += 1;
+                }
+                col += 1;
+            }
+            row += 1;
+        }
+        BlockTransform { matrix }
+    }
+
+    /// The transform that undoes this one
+    pub const fn inverse(self) -> BlockTransform {
+        // the matrix is orthogonal, so its inverse is its transpose
+        let mut matrix = [[0; 3]; 3];
+        let mut row = 0;
+        while row < 3 {
+            let mut col = 0;
+            while col < 3 {
+                matrix[row][col] = self.matrix[col][row];
+                col += 1;
+            }
+            row += 1;
+        }
+        BlockTransform { matrix }
+    }
+
+    /// Whether this transform mirrors geometry,
+    /// which flips the winding order of any polygon it's applied to
+    pub const fn is_reflection(self) -> bool {
+        let m = self.matrix;
+        let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+        det < 0
+    }
+
+    /// Transforms a direction vector
+    pub const fn apply_vector(self, v: [i32; 3]) -> [i32; 3] {
+        let m = self.matrix;
+        [
+            m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+            m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+            m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+        ]
+    }
+
+    /// Transforms the direction a face is pointing
+    pub const fn apply_face(self, face: FaceDir) -> FaceDir {
+        FaceDir::from_normal(self.apply_vector(face.normal()))
+    }
+
+    /// Transforms a point in block space (0..1 on each axis)
+    pub fn apply_point(self, p: [f32; 3]) -> [f32; 3] {
+        let m = self.matrix;
+        let c = [p[0] - 0.5, p[1] - 0.5, p[2] - 0.5];
+        let mut out = [0.0; 3];
+        for (row, out) in out.iter_mut().enumerate() {
+            *out = m[row][0] as f32 * c[0]
+                + m[row][1] as f32 * c[1]
+                + m[row][2] as f32 * c[2]
+                + 0.5;
+        }
+        out
+    }
+}
+
+/// A textured quad in block space (0..1 on each axis).
+///
+/// Corners are ordered counter-clockwise when viewed from the side
+/// that `normal` points towards.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Quad {
+    pub positions: [[f32; 3]; 4],
+    pub uvs: [[f32; 2]; 4],
+    pub normal: [f32; 3],
+    /// The texture this quad uses,
+    /// relative to the index of the block that owns it
+    pub texture: usize,
+    /// The face of the block this quad lies on.
+    ///
+    /// If the neighbouring block in this direction culls, this quad is removed.
+    /// Quads that don't lie on the edge of the block are never culled.
+    pub cull_face: Option<FaceDir>,
+}
+
+impl Quad {
+    /// Builds the quad covering `face` of the box spanning `min` to `max`.
+    ///
+    /// Uvs are locked to block space, so a half height side uses
+    /// the matching half of its texture.
+    pub fn cuboid_face(
+        face: FaceDir,
+        min: [f32; 3],
+        max: [f32; 3],
+        texture: usize
+    ) -> Quad {
+        // (u axis, v axis) such that u cross v points out of the face
+        let (u, v): ([i32; 3], [i32; 3]) = match face {
+            FaceDir::Up    => ([ 1, 0,  0], [0, 0, -1]),
+            FaceDir::Down  => ([ 1, 0,  0], [0, 0,  1]),
+            FaceDir::North => ([-1, 0,  0], [0, 1,  0]),
+            FaceDir::South => ([ 1, 0,  0], [0, 1,  0]),
+            FaceDir::West  => ([ 0, 0,  1], [0, 1,  0]),
+            FaceDir::East  => ([ 0, 0, -1], [0, 1,  0]),
+        };
+        let normal = face.normal();
+        let corner = |along_u: bool, along_v: bool| {
+            let mut p = [0.0; 3];
+            for axis in 0..3 {
+                p[axis] = if normal[axis] > 0 {
+                    max[axis]
+                } else if normal[axis] < 0 {
+                    min[axis]
+                } else if u[axis] != 0 {
+                    if (u[axis] > 0) == along_u { max[axis] } else { min[axis] }
+                } else if (v[axis] > 0) == along_v {
+                    max[axis]
+                } else {
+                    min[axis]
+                };
+            }
+            p
+        };
+        let uv = |p: [f32; 3]| {
+            let along = |dir: [i32; 3]| {
+                let axis = dir.iter().position(|c| *c != 0).unwrap();
+                if dir[axis] > 0 { p[axis] } else { 1.0 - p[axis] }
+            };
+            [along(u), 1.0 - along(v)]
+        };
+        let positions = [
+            corner(false, false),
+            corner(true, false),
+            corner(true, true),
+            corner(false, true)
+        ];
+        let on_edge = if normal.iter().sum::<i32>() > 0 {
+            positions[0].iter().zip(normal).any(|(p, n)| n != 0 && *p >= 1.0)
+        } else {
+            positions[0].iter().zip(normal).any(|(p, n)| n != 0 && *p <= 0.0)
+        };
+        Quad {
+            positions,
+            uvs: positions.map(uv),
+            normal: normal.map(|n| n as f32),
+            texture,
+            cull_face: if on_edge { Some(face) } else { None },
+        }
+    }
+
+    /// Applies a transform to this quad,
+    /// keeping its winding order facing outwards
+    pub fn transformed(self, transform: BlockTransform) -> Quad {
+        let mut positions = self.positions.map(|p| transform.apply_point(p));
+        let mut uvs = self.uvs;
+        if transform.is_reflection() {
+            positions.reverse();
+            uvs.reverse();
+        }
+        let n = self.normal;
+        let m = transform.matrix;
+        let normal = [0, 1, 2].map(|row| {
+            m[row][0] as f32 * n[0] + m[row][1] as f32 * n[1] + m[row][2] as f32 * n[2]
+        });
+        Quad {
+            positions,
+            uvs,
+            normal,
+            texture: self.texture,
+            cull_face: self.cull_face.map(|face| transform.apply_face(face)),
+        }
+    }
+}
+
+/// A block split into eight octants, each one either filled or empty.
+///
+/// Bit `x + 2 * z + 4 * y` is set when the octant at
+/// `(x, y, z)`, with each coordinate being 0 or 1, is filled.
+/// Slabs and every stair shape can be described this way.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Octants(pub u8);
+
+impl Octants {
+    pub const EMPTY: Octants = Octants(0);
+    pub const BOTTOM: Octants = Octants(0x0F);
+    pub const TOP: Octants = Octants(0xF0);
+    pub const FULL: Octants = Octants(0xFF);
+
+    const fn bit(x: usize, y: usize, z: usize) -> u8 {
+        1 << (x + 2 * z + 4 * y)
+    }
+
+    pub const fn contains(self, x: usize, y: usize, z: usize) -> bool {
+        self.0 & Self::bit(x, y, z) != 0
+    }
+
+    pub const fn with(self, x: usize, y: usize, z: usize) -> Octants {
+        Octants(self.0 | Self::bit(x, y, z))
+    }
+
+    pub const fn transformed(self, transform: BlockTransform) -> Octants {
+        let mut result = 0;
+        let mut i: usize = 0;
+        while i < 8 {
+            if self.0 & (1 << i) != 0 {
+                let (x, z, y) = (i & 1, (i >> 1) & 1, (i >> 2) & 1);
+                // map 0 and 1 onto -1 and 1 so the center of the block is the origin
+                let centered = [2 * x as i32 - 1, 2 * y as i32 - 1, 2 * z as i32 - 1];
+                let [x, y, z] = transform.apply_vector(centered);
+                result |= Self::bit(
+                    ((x + 1) / 2) as usize,
+                    ((y + 1) / 2) as usize,
+                    ((z + 1) / 2) as usize
+                );
+            }
+            i += 1;
+        }
+        Octants(result)
+    }
+
+    /// The culling behaviour of one of the block's faces.
+    ///
+    /// A face that is fully covered can cull and be culled,
+    /// a partially covered face can only be culled,
+    /// and an uncovered face does neither.
+    pub const fn culling(self, face: FaceDir) -> CullingFlag {
+        let normal = face.normal();
+        let mut covered = 0;
+        let mut i = 0;
+        while i < 8 {
+            let cell = [i & 1, (i >> 2) & 1, (i >> 1) & 1];
+            let mut on_face = true;
+            let mut axis = 0;
+            while axis < 3 {
+                if normal[axis] > 0 && cell[axis] != 1
+                || normal[axis] < 0 && cell[axis] != 0 {
+                    on_face = false;
+                }
+                axis += 1;
+            }
+            if on_face && self.contains(cell[0], cell[1], cell[2]) {
+                covered += 1;
+            }
+            i += 1;
+        }
+        match covered {
+            0 => CullingFlag::None,
+            4 => CullingFlag::Both,
+            _ => CullingFlag::Culling,
+        }
+    }
+
+    /// Builds the visible quads of the filled octants, merging neighbouring
+    /// quads where possible.  `texture` picks the texture used by each face.
+    pub fn quads(self, texture: impl Fn(FaceDir) -> usize) -> Vec<Quad> {
+        let mut quads = vec![];
+        for face in FaceDir::ALL {
+            let normal = face.normal();
+            let axis = normal.iter().position(|n| *n != 0).unwrap();
+            let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+            for layer in 0..2 {
+                // which of the 2x2 cells in this layer have an exposed face
+                let mut exposed = [[false; 2]; 2];
+                for (i, row) in exposed.iter_mut().enumerate() {
+                    for (j, cell) in row.iter_mut().enumerate() {
+                        let mut pos = [0i32; 3];
+                        pos[axis] = layer;
+                        pos[a] = i as i32;
+                        pos[b] = j as i32;
+                        let next = [0, 1, 2].map(|k| pos[k] + normal[k]);
+                        let filled = |p: [i32; 3]| {
+                            p.iter().all(|c| (0..2).contains(c))
+                                && self.contains(p[0] as usize, p[1] as usize, p[2] as usize)
+                        };
+                        *cell = filled(pos) && !filled(next);
+                    }
+                }
+                for (i0, i1, j0, j1) in Self::merge(exposed) {
+                    let mut min = [0.0; 3];
+                    let mut max = [0.0; 3];
+                    min[axis] = layer as f32 * 0.5;
+                    max[axis] = min[axis] + 0.5;
+                    min[a] = i0 as f32 * 0.5;
+                    max[a] = (i1 + 1) as f32 * 0.5;
+                    min[b] = j0 as f32 * 0.5;
+                    max[b] = (j1 + 1) as f32 * 0.5;
+                    quads.push(Quad::cuboid_face(face, min, max, texture(face)));
+                }
+            }
+        }
+        quads
+    }
+
+    /// Greedily merges a 2x2 grid of cells into rectangles,
+    /// returned as inclusive `(i0, i1, j0, j1)` ranges
+    fn merge(mut cells: [[bool; 2]; 2]) -> Vec<(usize, usize, usize, usize)> {
+        let mut rects = vec![];
+        if cells.iter().flatten().all(|c| *c) {
+            return vec![(0, 1, 0, 1)];
+        }
+        for (i, row) in cells.iter_mut().enumerate() {
+            if row[0] && row[1] {
+                rects.push((i, i, 0, 1));
+                *row = [false; 2];
+            }
+        }
+        let [first, second] = &mut cells;
+        for (j, (a, b)) in first.iter_mut().zip(second.iter_mut()).enumerate() {
+            if *a && *b {
+                rects.push((0, 1, j, j));
+                *a = false;
+                *b = false;
+            }
+        }
+        for (i, row) in cells.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                if *cell {
+                    rects.push((i, i, j, j));
+                }
+            }
+        }
+        rects
+    }
+}
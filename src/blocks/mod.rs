@@ -1,3 +1,6 @@
 pub mod block;
 pub mod block_types;
-pub mod const_into;
\ No newline at end of file
+pub mod const_into;
+pub mod geometry;
+pub mod properties;
+pub mod shape;
\ No newline at end of file
@@ -0,0 +1,194 @@
+use crate::blocks::shape::Aabb;
+
+/// Gameplay properties shared by every state of a block.
+///
+/// Properties are optional, blocks without them should be treated
+/// by gameplay systems as using [`BlockProperties::DEFAULT`].
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}, properties::{BlockProperties, SoundGroup}}, AsId};
+///
+/// table!(BlockType, enum BlockId, static BLOCKS = {
+///     let Stone: Basic = Block::new_basic("stone")
+///         .with_texture("stone.gif")
+///         .with_properties(BlockProperties::DEFAULT
+///             .with_hardness(1.5)
+///             .with_blast_resistance(6.0)
+///             .with_sound_group(SoundGroup::Stone)
+///             .with_required_tool("pickaxe")
+///         );
+/// });
+///
+/// let stone = &BLOCKS[BlockId::from_id(1)];
+/// assert_eq!(stone.properties().unwrap().hardness, 1.5);
+/// assert_eq!(stone.properties().unwrap().requires_tool, Some("pickaxe"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BlockProperties {
+    /// How long the block takes to break.
+    ///
+    /// Negative values mean the block can't be broken.
+    pub hardness: f32,
+    /// How well the block resists explosions
+    pub blast_resistance: f32,
+    /// How much the block slows down entities moving on top of it
+    pub friction: f32,
+    /// How much velocity entities keep when sliding on top of the block,
+    /// such as on ice.
+    pub slipperiness: f32,
+    /// The sounds played when the block is placed, broken, or walked on
+    pub sound_group: SoundGroup,
+    /// How likely the block is to catch fire, 0 meaning it never burns
+    pub flammability: u8,
+    /// The kind of tool needed for the block to drop anything when broken.
+    ///
+    /// If no tool is set, the block drops when broken by anything.
+    pub requires_tool: Option<&'static str>,
+    /// The block light this block gives off, from 0 to 15
+    pub light_emission: LightEmission,
+    /// How much light is lost passing through this block, from 0 to 15.
+    ///
+    /// If no opacity is set, states that are full cubes block all light,
+    /// and every other state lets all light through.
+    pub light_opacity: Option<u8>,
+    /// The boxes entities collide with, in block space.
+    ///
+    /// If no shape is set, the shape derived from the block's type is used.
+    /// An empty shape lets entities walk through the block.
+    pub collision_shape: Option<&'static [Aabb]>,
+    /// The boxes outlined and hit when looking at the block, in block space.
+    ///
+    /// If no shape is set, the shape derived from the block's type is used.
+    pub selection_shape: Option<&'static [Aabb]>,
+}
+
+impl BlockProperties {
+    pub const DEFAULT: BlockProperties = BlockProperties {
+        hardness: 1.0,
+        blast_resistance: 1.0,
+        friction: 0.6,
+        slipperiness: 0.0,
+        sound_group: SoundGroup::Stone,
+        flammability: 0,
+        requires_tool: None,
+        light_emission: LightEmission::Constant(0),
+        light_opacity: None,
+        collision_shape: None,
+        selection_shape: None,
+    };
+
+    pub const fn with_hardness(mut self, hardness: f32) -> Self {
+        self.hardness = hardness;
+        self
+    }
+
+    pub const fn with_blast_resistance(mut self, blast_resistance: f32) -> Self {
+        self.blast_resistance = blast_resistance;
+        self
+    }
+
+    pub const fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub const fn with_slipperiness(mut self, slipperiness: f32) -> Self {
+        self.slipperiness = slipperiness;
+        self
+    }
+
+    pub const fn with_sound_group(mut self, sound_group: SoundGroup) -> Self {
+        self.sound_group = sound_group;
+        self
+    }
+
+    pub const fn with_flammability(mut self, flammability: u8) -> Self {
+        self.flammability = flammability;
+        self
+    }
+
+    pub const fn with_required_tool(mut self, tool: &'static str) -> Self {
+        self.requires_tool = Some(tool);
+        self
+    }
+
+    pub const fn with_light_emission(mut self, emission: u8) -> Self {
+        assert!(emission <= 15, "light emission must be between 0 and 15");
+        self.light_emission = LightEmission::Constant(emission);
+        self
+    }
+
+    /// Sets the light given off by each state of the block, indexed by 
+    /// the state's id relative to the block's id.
+    /// 
+    /// Useful for blocks that only give off light sometimes, such as a lit furnace.
+    pub const fn with_state_light_emission(mut self, emission: &'static [u8]) -> Self {
+        let mut i = 0;
+        while i < emission.len() {
+            assert!(emission[i] <= 15, "light emission must be between 0 and 15");
+            i += 1;
+        }
+        self.light_emission = LightEmission::PerState(emission);
+        self
+    }
+
+    pub const fn with_light_opacity(mut self, opacity: u8) -> Self {
+        assert!(opacity <= 15, "light opacity must be between 0 and 15");
+        self.light_opacity = Some(opacity);
+        self
+    }
+
+    /// Sets the collision shape of an unrotated state, like a bottom slab or an
+    /// upright north facing stair, rotated and reflected along with the block's other states
+    pub const fn with_collision_shape(mut self, boxes: &'static [Aabb]) -> Self {
+        self.collision_shape = Some(boxes);
+        self
+    }
+
+    /// Sets the selection shape of an unrotated state, like a bottom slab or an
+    /// upright north facing stair, rotated and reflected along with the block's other states
+    pub const fn with_selection_shape(mut self, boxes: &'static [Aabb]) -> Self {
+        self.selection_shape = Some(boxes);
+        self
+    }
+}
+
+/// The block light given off by a block
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LightEmission {
+    /// Every state gives off the same light
+    Constant(u8),
+    /// Each state gives off its own light, indexed by the state's id 
+    /// relative to the block's id.  States past the end give off no light.
+    PerState(&'static [u8]),
+}
+
+impl LightEmission {
+    pub const fn get(&self, state: usize) -> u8 {
+        match self {
+            LightEmission::Constant(emission) => *emission,
+            LightEmission::PerState(emission) if state < emission.len() => emission[state],
+            LightEmission::PerState(_) => 0,
+        }
+    }
+}
+
+/// The set of sounds a block plays
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundGroup {
+    Stone,
+    Wood,
+    Gravel,
+    Grass,
+    Sand,
+    Snow,
+    Metal,
+    Glass,
+    Wool,
+    /// A sound group defined by the game, referenced by name
+    Custom(&'static str),
+}
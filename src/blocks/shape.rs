@@ -0,0 +1,242 @@
+use std::borrow::Cow;
+
+use crate::blocks::{block::FaceDir, geometry::{BlockTransform, Octants}};
+
+/// An axis aligned box in block space (0..1 on each axis)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Aabb {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl Aabb {
+    /// The box covering the whole block
+    pub const FULL: Aabb = Aabb::new([0.0; 3], [1.0; 3]);
+
+    pub const fn new(min: [f32; 3], max: [f32; 3]) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// This box moved by `offset`
+    pub fn offset(self, offset: [f32; 3]) -> Aabb {
+        Aabb {
+            min: [0, 1, 2].map(|axis| self.min[axis] + offset[axis]),
+            max: [0, 1, 2].map(|axis| self.max[axis] + offset[axis]),
+        }
+    }
+
+    /// Whether the insides of two boxes overlap, boxes that only touch don't intersect
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|axis| self.min[axis] < other.max[axis] && other.min[axis] < self.max[axis])
+    }
+
+    pub fn contains(&self, point: [f32; 3]) -> bool {
+        (0..3).all(|axis| self.min[axis] <= point[axis] && point[axis] <= self.max[axis])
+    }
+
+    /// Applies a block transform to this box
+    pub fn transformed(self, transform: BlockTransform) -> Aabb {
+        let a = transform.apply_point(self.min);
+        let b = transform.apply_point(self.max);
+        Aabb {
+            min: [0, 1, 2].map(|axis| a[axis].min(b[axis])),
+            max: [0, 1, 2].map(|axis| a[axis].max(b[axis])),
+        }
+    }
+
+    /// Casts a ray against this box, returning the distance along `dir`
+    /// at which the ray enters the box and the face it enters through.
+    ///
+    /// Rays starting inside of the box don't hit it.
+    pub fn raycast(&self, origin: [f32; 3], dir: [f32; 3], max_t: f32) -> Option<RayHit> {
+        let mut t_enter = f32::NEG_INFINITY;
+        let mut t_exit = f32::INFINITY;
+        let mut face = None;
+        for axis in 0..3 {
+            if dir[axis] == 0.0 {
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t_min = (self.min[axis] - origin[axis]) / dir[axis];
+            let t_max = (self.max[axis] - origin[axis]) / dir[axis];
+            let (near, far) = if t_min < t_max { (t_min, t_max) } else { (t_max, t_min) };
+            if near > t_enter {
+                t_enter = near;
+                let mut normal = [0; 3];
+                normal[axis] = if dir[axis] > 0.0 { -1 } else { 1 };
+                face = Some(FaceDir::from_normal(normal));
+            }
+            t_exit = t_exit.min(far);
+        }
+        if t_enter > t_exit || t_enter < 0.0 || t_enter > max_t {
+            return None;
+        }
+        Some(RayHit {
+            t: t_enter,
+            point: [0, 1, 2].map(|axis| origin[axis] + dir[axis] * t_enter),
+            face: face?,
+        })
+    }
+}
+
+/// Where a ray or a moving box hit a shape
+#[derive(Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// How far along the ray or motion the hit happened,
+    /// in multiples of the ray's direction
+    pub t: f32,
+    /// The point that was hit, or for moving boxes,
+    /// where the box's center would have been
+    pub point: [f32; 3],
+    /// The face of the shape that was hit
+    pub face: FaceDir,
+}
+
+/// The shape of a block state, made of a union of boxes in block space.
+///
+/// Used to ask what space a block takes up, for example for collision
+/// or for the outline of the block being looked at.
+///
+/// ### Example
+///
+/// ```rust
+/// # use opentale_blocks::blocks::shape::{Aabb, VoxelShape};
+///
+/// const BOTTOM_SLAB: VoxelShape = VoxelShape::new(&[Aabb::new([0.0; 3], [1.0, 0.5, 1.0])]);
+///
+/// // looking straight down from above the block
+/// let hit = BOTTOM_SLAB.raycast([0.5, 2.0, 0.5], [0.0, -1.0, 0.0], 10.0).unwrap();
+/// assert_eq!(hit.t, 1.5);
+///
+/// // a box falling onto the slab stops on top of it
+/// let falling = Aabb::new([0.25, 1.0, 0.25], [0.75, 1.5, 0.75]);
+/// let hit = BOTTOM_SLAB.sweep(falling, [0.0, -2.0, 0.0]).unwrap();
+/// assert_eq!(hit.t, 0.25);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct VoxelShape {
+    boxes: Cow<'static, [Aabb]>,
+}
+
+impl VoxelShape {
+    /// A shape taking up no space
+    pub const EMPTY: VoxelShape = VoxelShape::new(&[]);
+    /// A shape filling the whole block
+    pub const FULL: VoxelShape = VoxelShape::new(&[Aabb::FULL]);
+
+    pub const fn new(boxes: &'static [Aabb]) -> VoxelShape {
+        VoxelShape {
+            boxes: Cow::Borrowed(boxes),
+        }
+    }
+
+    pub fn from_boxes(boxes: Vec<Aabb>) -> VoxelShape {
+        VoxelShape {
+            boxes: Cow::Owned(boxes),
+        }
+    }
+
+    /// Builds a shape out of the filled octants of a block,
+    /// merging neighbouring octants into larger boxes.
+    pub fn from_octants(octants: Octants) -> VoxelShape {
+        let mut remaining = octants;
+        let mut boxes = vec![];
+        for y in 0..2 {
+            for z in 0..2 {
+                for x in 0..2 {
+                    if !remaining.contains(x, y, z) {
+                        continue;
+                    }
+                    // grow along x, then z, then y while every octant is free
+                    let fits = |x1: usize, y1: usize, z1: usize| {
+                        (y..=y1).all(|yy| (z..=z1).all(|zz| (x..=x1).all(|xx| {
+                            remaining.contains(xx, yy, zz)
+                        })))
+                    };
+                    let x1 = if x == 0 && fits(1, y, z) { 1 } else { x };
+                    let z1 = if z == 0 && fits(x1, y, 1) { 1 } else { z };
+                    let y1 = if y == 0 && fits(x1, 1, z1) { 1 } else { y };
+                    for yy in y..=y1 {
+                        for zz in z..=z1 {
+                            for xx in x..=x1 {
+                                remaining = Octants(remaining.0 & !Octants::EMPTY.with(xx, yy, zz).0);
+                            }
+                        }
+                    }
+                    boxes.push(Aabb::new(
+                        [x as f32 * 0.5, y as f32 * 0.5, z as f32 * 0.5],
+                        [(x1 + 1) as f32 * 0.5, (y1 + 1) as f32 * 0.5, (z1 + 1) as f32 * 0.5],
+                    ));
+                }
+            }
+        }
+        VoxelShape::from_boxes(boxes)
+    }
+
+    pub fn boxes(&self) -> &[Aabb] {
+        &self.boxes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// The smallest box containing the whole shape, if it isn't empty
+    pub fn bounds(&self) -> Option<Aabb> {
+        let mut boxes = self.boxes.iter();
+        let first = *boxes.next()?;
+        Some(boxes.fold(first, |bounds, aabb| Aabb {
+            min: [0, 1, 2].map(|axis| bounds.min[axis].min(aabb.min[axis])),
+            max: [0, 1, 2].map(|axis| bounds.max[axis].max(aabb.max[axis])),
+        }))
+    }
+
+    /// Applies a block transform to every box in this shape
+    pub fn transformed(&self, transform: BlockTransform) -> VoxelShape {
+        if transform == BlockTransform::IDENTITY {
+            return self.clone();
+        }
+        VoxelShape::from_boxes(self.boxes.iter().map(|aabb| aabb.transformed(transform)).collect())
+    }
+
+    pub fn contains(&self, point: [f32; 3]) -> bool {
+        self.boxes.iter().any(|aabb| aabb.contains(point))
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.boxes.iter().any(|aabb| aabb.intersects(other))
+    }
+
+    /// Casts a ray against this shape, returning the closest hit
+    /// within `max_t` multiples of `dir`.
+    ///
+    /// `origin` is relative to the block's corner, so to cast against a block
+    /// in the world, subtract the block's position from the ray's origin.
+    pub fn raycast(&self, origin: [f32; 3], dir: [f32; 3], max_t: f32) -> Option<RayHit> {
+        self.boxes.iter()
+            .filter_map(|aabb| aabb.raycast(origin, dir, max_t))
+            .min_by(|a, b| a.t.total_cmp(&b.t))
+    }
+
+    /// Sweeps a box along `motion`, returning the first hit against this shape.
+    ///
+    /// The hit's `t` is the fraction of `motion` the box can travel
+    /// before touching the shape.  Boxes already overlapping
+    /// the shape don't hit it.
+    pub fn sweep(&self, moving: Aabb, motion: [f32; 3]) -> Option<RayHit> {
+        let half = [0, 1, 2].map(|axis| (moving.max[axis] - moving.min[axis]) / 2.0);
+        let center = [0, 1, 2].map(|axis| moving.min[axis] + half[axis]);
+        // growing each box by the moving box's size turns the sweep into a ray cast
+        self.boxes.iter()
+            .filter_map(|aabb| {
+                let grown = Aabb {
+                    min: [0, 1, 2].map(|axis| aabb.min[axis] - half[axis]),
+                    max: [0, 1, 2].map(|axis| aabb.max[axis] + half[axis]),
+                };
+                grown.raycast(center, motion, 1.0)
+            })
+            .min_by(|a, b| a.t.total_cmp(&b.t))
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use crate::{block_entity::{BlockEntity, BlockEntityStore, HasBlockEntity}, blocks::block::FaceDir, AsId};
+
+/// The length of each side of a chunk
+pub const CHUNK_SIZE: usize = 16;
+/// The number of blocks in a chunk
+pub const CHUNK_VOLUME: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+/// The position of a block in the world
+pub type BlockPos = [i32; 3];
+/// The position of a chunk in the world, in chunks rather than blocks
+pub type ChunkPos = [i32; 3];
+
+/// The chunk containing a block
+pub fn chunk_pos(pos: BlockPos) -> ChunkPos {
+    pos.map(|c| c.div_euclid(CHUNK_SIZE as i32))
+}
+
+/// The position of a block relative to the chunk containing it
+pub fn local_pos(pos: BlockPos) -> [usize; 3] {
+    pos.map(|c| c.rem_euclid(CHUNK_SIZE as i32) as usize)
+}
+
+/// The world position of a block inside of a chunk
+pub fn world_pos(chunk: ChunkPos, local: [usize; 3]) -> BlockPos {
+    [0, 1, 2].map(|axis| chunk[axis] * CHUNK_SIZE as i32 + local[axis] as i32)
+}
+
+/// The block next to `pos` in the direction of `face`
+pub fn offset(pos: BlockPos, face: FaceDir) -> BlockPos {
+    let normal = face.normal();
+    [pos[0] + normal[0], pos[1] + normal[1], pos[2] + normal[2]]
+}
+
+/// A cube of [`CHUNK_SIZE`] blocks on each side, storing block state ids.
+///
+/// Ids are stored as indices into a palette of the distinct ids in the chunk,
+/// packed into as few bits as the palette allows.  A chunk filled
+/// with a single id stores no per block data at all.
+///
+/// The chunk also keeps the block entities of its blocks, see [`crate::block_entity`].
+#[derive(Clone)]
+pub struct Chunk {
+    /// Every distinct id in the chunk
+    palette: Vec<usize>,
+    /// The number of bits used by each palette index, 0 if the palette has one entry
+    bits: u32,
+    /// Palette indices, packed without crossing the boundary of a word
+    data: Vec<u64>,
+    block_entities: BlockEntityStore,
+}
+
+impl Chunk {
+    /// A chunk where every block has the same id, like a
+    /// chunk of air from the id of a table's null variant
+    pub fn filled(id: usize) -> Chunk {
+        Chunk {
+            palette: vec![id],
+            bits: 0,
+            data: vec![],
+            block_entities: BlockEntityStore::new(),
+        }
+    }
+
+    /// The index of a block in the chunk's storage,
+    /// ordered by x, then z, then y
+    pub const fn index(local: [usize; 3]) -> usize {
+        local[0] + local[2] * CHUNK_SIZE + local[1] * CHUNK_SIZE * CHUNK_SIZE
+    }
+
+    /// The position of a block from its index in the chunk's storage
+    pub const fn position(index: usize) -> [usize; 3] {
+        [
+            index % CHUNK_SIZE,
+            index / (CHUNK_SIZE * CHUNK_SIZE),
+            (index / CHUNK_SIZE) % CHUNK_SIZE
+        ]
+    }
+
+    /// The distinct ids in the chunk.
+    ///
+    /// The palette may contain ids no longer in the chunk until [`Chunk::compact`] is called.
+    pub fn palette(&self) -> &[usize] {
+        &self.palette
+    }
+
+    /// Whether every block in the chunk has the same id, like air
+    pub fn is_filled_with(&self, id: usize) -> bool {
+        self.palette.iter().all(|entry| *entry == id) || self.ids().all(|entry| entry == id)
+    }
+
+    /// The id of the block at a position relative to the chunk
+    pub fn get(&self, local: [usize; 3]) -> usize {
+        self.get_index(Self::index(local))
+    }
+
+    /// The block at a position relative to the chunk
+    pub fn get_id<I: AsId>(&self, local: [usize; 3]) -> I {
+        I::from_id(self.get(local))
+    }
+
+    /// Sets the id of the block at a position relative to the chunk,
+    /// returning the id that was replaced.
+    ///
+    /// Removes the block entity at the position, unless the new id
+    /// is another state of the block it belongs to.
+    pub fn set(&mut self, local: [usize; 3], id: usize) -> usize {
+        let index = Self::index(local);
+        let old = self.get_index(index);
+        if old == id {
+            return old;
+        }
+        self.block_entities.id_changed(index, id);
+        let palette_index = match self.palette.iter().position(|entry| *entry == id) {
+            Some(palette_index) => palette_index,
+            None => {
+                self.palette.push(id);
+                if self.palette.len() > 1 << self.bits {
+                    self.repack(Self::bits_for(self.palette.len()));
+                }
+                self.palette.len() - 1
+            }
+        };
+        self.set_palette_index(index, palette_index);
+        old
+    }
+
+    /// Sets the block at a position relative to the chunk,
+    /// returning the id that was replaced
+    pub fn set_id<I: AsId>(&mut self, local: [usize; 3], block: &I) -> usize {
+        self.set(local, block.to_id())
+    }
+
+    /// Sets the block at a position relative to the chunk, creating its
+    /// block entity if it has one and doesn't already, and returns the
+    /// id that was replaced
+    pub fn place<I: HasBlockEntity>(&mut self, local: [usize; 3], block: &I) -> usize {
+        let old = self.set(local, block.to_id());
+        if self.block_entities.get(local).is_none()
+            && let Some(data) = block.new_block_entity()
+        {
+            self.block_entities.insert(local, block.state_ids(), data);
+        }
+        old
+    }
+
+    pub fn block_entity(&self, local: [usize; 3]) -> Option<&dyn BlockEntity> {
+        self.block_entities.get(local)
+    }
+
+    pub fn block_entity_mut(&mut self, local: [usize; 3]) -> Option<&mut dyn BlockEntity> {
+        self.block_entities.get_mut(local)
+    }
+
+    pub fn block_entities(&self) -> &BlockEntityStore {
+        &self.block_entities
+    }
+
+    pub fn block_entities_mut(&mut self) -> &mut BlockEntityStore {
+        &mut self.block_entities
+    }
+
+    /// Every id in the chunk in storage order
+    pub fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..CHUNK_VOLUME).map(|index| self.get_index(index))
+    }
+
+    /// Removes palette entries that are no longer used,
+    /// shrinking the chunk's storage if possible
+    pub fn compact(&mut self) {
+        let mut used = vec![false; self.palette.len()];
+        for index in 0..CHUNK_VOLUME {
+            used[self.get_palette_index(index)] = true;
+        }
+        if used.iter().all(|used| *used) {
+            return;
+        }
+        let ids: Vec<usize> = self.ids().collect();
+        let palette: Vec<usize> = self.palette.iter()
+            .zip(used)
+            .filter_map(|(id, used)| used.then_some(*id))
+            .collect();
+        let block_entities = std::mem::take(&mut self.block_entities);
+        *self = Chunk::filled(palette[0]);
+        for (index, id) in ids.into_iter().enumerate() {
+            self.set(Self::position(index), id);
+        }
+        self.block_entities = block_entities;
+    }
+
+    /// The palette, the bits used by each index and the packed
+    /// indices, for saving the chunk without unpacking it
+    pub(crate) fn raw_parts(&self) -> (&[usize], u32, &[u64]) {
+        (&self.palette, self.bits, &self.data)
+    }
+
+    /// Rebuilds a chunk from its [raw parts](Chunk::raw_parts),
+    /// or returns `None` if they don't fit together.
+    ///
+    /// Ids listed more than once in the palette are merged into one entry.
+    pub(crate) fn from_raw_parts(palette: Vec<usize>, bits: u32, data: Vec<u64>) -> Option<Chunk> {
+        if palette.is_empty() || bits != Self::bits_for(palette.len()) {
+            return None;
+        }
+        let words = if bits == 0 { 0 } else { CHUNK_VOLUME.div_ceil(64 / bits as usize) };
+        if data.len() != words {
+            return None;
+        }
+        let chunk = Chunk { palette, bits, data, block_entities: BlockEntityStore::new() };
+        if !(0..CHUNK_VOLUME).all(|index| chunk.get_palette_index(index) < chunk.palette.len()) {
+            return None;
+        }
+        let distinct = chunk.palette.iter()
+            .enumerate()
+            .all(|(i, id)| !chunk.palette[..i].contains(id));
+        if distinct {
+            return Some(chunk);
+        }
+        let mut merged = Chunk::filled(chunk.palette[0]);
+        for (index, id) in chunk.ids().enumerate() {
+            merged.set(Self::position(index), id);
+        }
+        Some(merged)
+    }
+
+    fn bits_for(palette_len: usize) -> u32 {
+        if palette_len <= 1 {
+            0
+        } else {
+            usize::BITS - (palette_len - 1).leading_zeros()
+        }
+    }
+
+    fn get_index(&self, index: usize) -> usize {
+        self.palette[self.get_palette_index(index)]
+    }
+
+    fn get_palette_index(&self, index: usize) -> usize {
+        if self.bits == 0 {
+            return 0;
+        }
+        let per_word = 64 / self.bits as usize;
+        let word = self.data[index / per_word];
+        let shift = (index % per_word) as u32 * self.bits;
+        ((word >> shift) & ((1 << self.bits) - 1)) as usize
+    }
+
+    fn set_palette_index(&mut self, index: usize, palette_index: usize) {
+        let per_word = 64 / self.bits as usize;
+        let shift = (index % per_word) as u32 * self.bits;
+        let mask = ((1u64 << self.bits) - 1) << shift;
+        let word = &mut self.data[index / per_word];
+        *word = (*word & !mask) | ((palette_index as u64) << shift);
+    }
+
+    fn repack(&mut self, bits: u32) {
+        let indices: Vec<usize> = (0..CHUNK_VOLUME)
+            .map(|index| self.get_palette_index(index))
+            .collect();
+        let per_word = 64 / bits as usize;
+        self.bits = bits;
+        self.data = vec![0; CHUNK_VOLUME.div_ceil(per_word)];
+        for (index, palette_index) in indices.into_iter().enumerate() {
+            self.set_palette_index(index, palette_index);
+        }
+    }
+}
+
+/// A sparse grid of chunks making up a world, or part of one.
+///
+/// Blocks in chunks that aren't loaded have the grid's air id, given when
+/// it's created, and chunks loaded by setting a block start out filled with it.
+#[derive(Clone)]
+pub struct ChunkGrid {
+    chunks: HashMap<ChunkPos, Chunk>,
+    air: usize,
+}
+
+impl ChunkGrid {
+    /// An empty grid, where every block has the id `air`, usually
+    /// the id of the null variant of a table
+    pub fn new(air: usize) -> ChunkGrid {
+        ChunkGrid {
+            chunks: HashMap::new(),
+            air,
+        }
+    }
+
+    /// The id of blocks in chunks that aren't loaded
+    pub fn air(&self) -> usize {
+        self.air
+    }
+
+    /// Adds a chunk to the grid, returning the chunk it replaced
+    pub fn insert_chunk(&mut self, pos: ChunkPos, chunk: Chunk) -> Option<Chunk> {
+        self.chunks.insert(pos, chunk)
+    }
+
+    pub fn remove_chunk(&mut self, pos: ChunkPos) -> Option<Chunk> {
+        self.chunks.remove(&pos)
+    }
+
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&Chunk> {
+        self.chunks.get(&pos)
+    }
+
+    pub fn chunk_mut(&mut self, pos: ChunkPos) -> Option<&mut Chunk> {
+        self.chunks.get_mut(&pos)
+    }
+
+    /// Every loaded chunk and its position
+    pub fn chunks(&self) -> impl Iterator<Item = (ChunkPos, &Chunk)> {
+        self.chunks.iter().map(|(pos, chunk)| (*pos, chunk))
+    }
+
+    pub fn is_loaded(&self, pos: BlockPos) -> bool {
+        self.chunks.contains_key(&chunk_pos(pos))
+    }
+
+    /// The id of a block, or the grid's air id if its chunk isn't loaded
+    pub fn get(&self, pos: BlockPos) -> usize {
+        self.chunk(chunk_pos(pos))
+            .map(|chunk| chunk.get(local_pos(pos)))
+            .unwrap_or(self.air)
+    }
+
+    pub fn get_id<I: AsId>(&self, pos: BlockPos) -> I {
+        I::from_id(self.get(pos))
+    }
+
+    /// Sets the id of a block, loading a chunk of air if needed,
+    /// and returns the id that was replaced
+    pub fn set(&mut self, pos: BlockPos, id: usize) -> usize {
+        self.chunk_or_air(chunk_pos(pos)).set(local_pos(pos), id)
+    }
+
+    pub fn set_id<I: AsId>(&mut self, pos: BlockPos, block: &I) -> usize {
+        self.set(pos, block.to_id())
+    }
+
+    /// Sets a block, loading a chunk of air if needed, and creates its
+    /// block entity, see [`Chunk::place`]
+    pub fn place<I: HasBlockEntity>(&mut self, pos: BlockPos, block: &I) -> usize {
+        self.chunk_or_air(chunk_pos(pos)).place(local_pos(pos), block)
+    }
+
+    pub fn block_entity(&self, pos: BlockPos) -> Option<&dyn BlockEntity> {
+        self.chunk(chunk_pos(pos))?.block_entity(local_pos(pos))
+    }
+
+    pub fn block_entity_mut(&mut self, pos: BlockPos) -> Option<&mut dyn BlockEntity> {
+        self.chunk_mut(chunk_pos(pos))?.block_entity_mut(local_pos(pos))
+    }
+
+    fn chunk_or_air(&mut self, pos: ChunkPos) -> &mut Chunk {
+        let air = self.air;
+        self.chunks.entry(pos).or_insert_with(|| Chunk::filled(air))
+    }
+}
@@ -0,0 +1,292 @@
+//! A command line tool for inspecting and checking a block table.
+//!
+//! The table lives in your own crate, so the tool is built as a small
+//! binary of that crate, which registers its table and id enum with
+//! [`cli_main!`](crate::cli_main):
+//!
+//! ```rust,ignore
+//! // src/bin/blocks.rs
+//! opentale_blocks::cli_main!(BlockId, BLOCKS);
+//! ```
+//!
+//! `examples/cli.rs` is such a binary for a small demo table.  The
+//! `opentale-blocks` binary bundled with this crate has no table, so it
+//! only offers the commands that don't need one, `diff` and `region`.
+//!
+//! ```text
+//! USAGE:
+//!     opentale-blocks dump [--format json|csv]
+//!     opentale-blocks diff <old.json> <new.json>
+//!     opentale-blocks validate <asset directory> [--deny-warnings]
+//!     opentale-blocks region scan|repair <region file>...
+//! ```
+//!
+//! `dump` prints every block with its ids, texture indices and states.
+//! `diff` compares two JSON dumps and fails if any state changed id,
+//! so CI can catch blocks being renumbered by accident.
+//! `region scan` lists the corrupt chunks of [region files](crate::region),
+//! and `region repair` removes them.
+//!
+//! The tool exits with 1 when it finds problems and with 2 when it's used wrong.
+
+use std::{collections::HashMap, path::Path, process::ExitCode};
+
+use serde_json::{json, Value};
+
+use crate::{blocks::block_types::BlockType, region, table::Table, AsId};
+
+const USAGE: &str = "\
+USAGE:
+    opentale-blocks dump [--format json|csv]
+    opentale-blocks diff <old.json> <new.json>
+    opentale-blocks validate <asset directory> [--deny-warnings]
+    opentale-blocks region scan|repair <region file>...
+
+COMMANDS:
+    dump        Prints every block with its ids, texture indices and states
+    diff        Shows the states whose ids changed between two JSON dumps
+    validate    Checks the textures and models referenced by every block
+    region      Finds corrupt chunks in region files, and removes them with `repair`";
+
+/// Defines a `main` function running the tool over a table.
+///
+/// Takes the id enum and the table generated by [`table!`](crate::table).
+#[macro_export]
+macro_rules! cli_main {
+    ($enum_name:ty, $table:expr) => {
+        fn main() -> std::process::ExitCode {
+            $crate::cli::run::<$enum_name, _>(&$table, std::env::args())
+        }
+    };
+}
+
+/// Runs the command given by `args` without a table, where the first argument
+/// is the program's name.
+///
+/// Only `diff` and `region` can be run, the other commands fail with a usage error.
+pub fn run_without_table(args: impl IntoIterator<Item = String>) -> ExitCode {
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["dump" | "validate", ..] => usage_error("this command needs a table, build the tool with `cli_main!`"),
+        args => run_table_independent(args),
+    }
+}
+
+/// Runs the command given by `args`, where the first argument is the program's name
+pub fn run<I: AsId, T: 'static + ?Sized + BlockType>(
+    table: &Table<T>,
+    args: impl IntoIterator<Item = String>
+) -> ExitCode {
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["dump"] | ["dump", "--format", "json"] => {
+            println!("{:#}", dump::<I, T>(table));
+            ExitCode::SUCCESS
+        }
+        ["dump", "--format", "csv"] => {
+            print!("{}", dump_csv::<I, T>(table));
+            ExitCode::SUCCESS
+        }
+        ["dump", ..] => usage_error("expected `--format json` or `--format csv`"),
+        ["validate", rest @ ..] => validate(table, rest),
+        args => run_table_independent(args),
+    }
+}
+
+/// Runs the commands that don't need a table
+fn run_table_independent(args: &[&str]) -> ExitCode {
+    match args {
+        ["diff", old, new] => diff(Path::new(old), Path::new(new)),
+        ["diff", ..] => usage_error("expected an old and a new dump"),
+        ["region", "scan", paths @ ..] if !paths.is_empty() => region(paths, false),
+        ["region", "repair", paths @ ..] if !paths.is_empty() => region(paths, true),
+        ["region", ..] => usage_error("expected `scan` or `repair` and region files"),
+        ["help" | "--help" | "-h"] => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        _ => usage_error("expected a command"),
+    }
+}
+
+/// Every block with its ids, texture indices and state names, as JSON.
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, slab::Slab, BlockType}}, cli, AsId};
+///
+/// table!(BlockType, enum BlockId, static BLOCKS = {
+///     let Dirt: Basic = Block::new_basic("dirt");
+///     let StoneSlab: Slab = Block::new_slab("stone_slab");
+/// });
+///
+/// let dump = cli::dump::<BlockId, _>(&BLOCKS);
+/// assert_eq!(dump["blocks"][1]["first_id"], 2);
+/// assert_eq!(dump["blocks"][1]["states"][1], "StoneSlab[type: top]");
+/// ```
+pub fn dump<I: AsId, T: 'static + ?Sized + BlockType>(table: &Table<T>) -> Value {
+    let blocks: Vec<Value> = table.iter()
+        .map(|block| json!({
+            "name": block.name(),
+            "first_id": block.id(),
+            "id_span": block.id_span(),
+            "texture_index": block.index(),
+            "texture_span": block.texture_span(),
+            "states": (block.id()..block.id() + block.id_span())
+                .map(|id| I::from_id(id).to_string())
+                .collect::<Vec<_>>(),
+        }))
+        .collect();
+    json!({
+        "id_span": table.id_span(),
+        "blocks": blocks,
+    })
+}
+
+/// Every state of every block, one per row, as CSV
+pub fn dump_csv<I: AsId, T: 'static + ?Sized + BlockType>(table: &Table<T>) -> String {
+    let mut csv = "id,block,state,texture_index,texture_span\n".to_string();
+    for block in table {
+        for id in block.id()..block.id() + block.id_span() {
+            csv += &format!(
+                "{id},{},{},{},{}\n",
+                csv_field(block.name()),
+                csv_field(&I::from_id(id).to_string()),
+                block.index(),
+                block.texture_span(),
+            );
+        }
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The id of every state in a JSON dump
+fn state_ids(dump: &Value) -> Result<Vec<(String, u64)>, String> {
+    let blocks = dump["blocks"].as_array().ok_or("expected a dump made by `dump`")?;
+    let mut states = vec![];
+    for block in blocks {
+        let first_id = block["first_id"].as_u64().ok_or("a block is missing its `first_id`")?;
+        let names = block["states"].as_array().ok_or("a block is missing its `states`")?;
+        for (offset, name) in names.iter().enumerate() {
+            let name = name.as_str().ok_or("state names must be strings")?;
+            states.push((name.to_string(), first_id + offset as u64));
+        }
+    }
+    Ok(states)
+}
+
+fn diff(old: &Path, new: &Path) -> ExitCode {
+    let read = |path: &Path| -> Result<Vec<(String, u64)>, String> {
+        let text = std::fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+        let dump = serde_json::from_str(&text).map_err(|error| format!("{}: {error}", path.display()))?;
+        state_ids(&dump).map_err(|error| format!("{}: {error}", path.display()))
+    };
+    let (old, new) = match (read(old), read(new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(error), _) | (_, Err(error)) => {
+            eprintln!("error: {error}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let new_ids: HashMap<&str, u64> = new.iter().map(|(name, id)| (name.as_str(), *id)).collect();
+    let old_ids: HashMap<&str, u64> = old.iter().map(|(name, id)| (name.as_str(), *id)).collect();
+    let (mut moved, mut removed) = (0, 0);
+    for (name, old_id) in &old {
+        match new_ids.get(name.as_str()) {
+            Some(new_id) if new_id != old_id => {
+                println!("moved   {name}: {old_id} -> {new_id}");
+                moved += 1;
+            }
+            Some(_) => {}
+            None => {
+                println!("removed {name}: {old_id}");
+                removed += 1;
+            }
+        }
+    }
+    let mut added = 0;
+    for (name, new_id) in &new {
+        if !old_ids.contains_key(name.as_str()) {
+            println!("added   {name}: {new_id}");
+            added += 1;
+        }
+    }
+    println!("{moved} moved, {removed} removed, {added} added");
+    if moved > 0 || removed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn validate<T: 'static + ?Sized + BlockType>(table: &Table<T>, args: &[&str]) -> ExitCode {
+    let mut root = None;
+    let mut deny_warnings = false;
+    for arg in args {
+        match *arg {
+            "--deny-warnings" => deny_warnings = true,
+            flag if flag.starts_with("--") => return usage_error(&format!("unknown flag `{flag}`")),
+            path if root.is_none() => root = Some(path),
+            _ => return usage_error("expected a single asset directory"),
+        }
+    }
+    let Some(root) = root else {
+        return usage_error("expected an asset directory");
+    };
+
+    let report = table.validate_assets(Path::new(root));
+    print!("{report}");
+    let errors = report.errors().count();
+    let warnings = report.warnings().count();
+    println!("{errors} errors, {warnings} warnings");
+    if errors > 0 || (deny_warnings && warnings > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn region(paths: &[&str], repair: bool) -> ExitCode {
+    let mut failed = false;
+    for path in paths {
+        let report = if repair { region::repair(path) } else { region::scan(path) };
+        match report {
+            Ok(report) => {
+                for problem in &report.problems {
+                    println!("{path}: {problem}");
+                }
+                let action = if repair { "removed" } else { "corrupt" };
+                println!("{path}: {} chunks, {} {action}", report.chunks, report.problems.len());
+                failed |= !repair && !report.problems.is_empty();
+            }
+            Err(error) => {
+                eprintln!("error: {path}: {error}");
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("error: {message}\n\n{USAGE}");
+    ExitCode::from(2)
+}
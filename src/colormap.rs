@@ -0,0 +1,159 @@
+//! Tinting faces by biome colour.
+//!
+//! Grass tops, leaves and water are drawn in greyscale and coloured by
+//! where they are.  Each texture slot has a [`TintSource`], which meshes
+//! carry in every vertex, and the colour comes from looking up the biome's
+//! temperature and humidity in a [`Colormap`].  [`Colormaps`] holds every
+//! colormap, so a server rendering maps and a client drawing chunks
+//! colour blocks the same way.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # use opentale_blocks::colormap::{Colormap, Colormaps, TintSource};
+//!
+//! // a 2x2 colormap, hot is on the left and humid is at the top
+//! let grass = Colormap::from_rgb([2, 2], vec![
+//!     [0, 200, 0], [0, 100, 0],
+//!     [200, 200, 0], [100, 100, 100],
+//! ]);
+//! let colormaps = Colormaps::new(grass.clone(), grass, [40, 80, 200]);
+//!
+//! assert_eq!(colormaps.color(TintSource::Grass, 1.0, 1.0), [0, 200, 0]);
+//! // humidity is scaled by temperature, cold places are always dry
+//! assert_eq!(colormaps.color(TintSource::Grass, 0.0, 1.0), [100, 100, 100]);
+//! assert_eq!(colormaps.color(TintSource::Water, 0.5, 0.5), [40, 80, 200]);
+//! assert_eq!(colormaps.color(TintSource::None, 0.5, 0.5), [255, 255, 255]);
+//!
+//! let tint = TintSource::Constant([10, 20, 30]);
+//! assert_eq!(TintSource::from_bits(tint.to_bits()), tint);
+//! ```
+
+#[cfg(feature = "textures")]
+use std::path::Path;
+
+/// Where the colour of a face comes from
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TintSource {
+    /// The face isn't tinted
+    #[default]
+    None,
+    /// The face is always tinted the same colour
+    Constant([u8; 3]),
+    /// The grass colormap, for grass tops and plants
+    Grass,
+    /// The foliage colormap, for leaves and vines
+    Foliage,
+    /// The water colour
+    Water,
+    /// One of the game's own colormaps, by its index in [`Colormaps::custom`]
+    Custom(u32),
+}
+
+impl TintSource {
+    /// Packs the source into a vertex attribute: the kind in the top
+    /// 8 bits, and the colour as `0xRRGGBB` or the custom index below
+    pub const fn to_bits(self) -> u32 {
+        match self {
+            TintSource::None => 0,
+            TintSource::Constant([r, g, b]) => 1 << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32,
+            TintSource::Grass => 2 << 24,
+            TintSource::Foliage => 3 << 24,
+            TintSource::Water => 4 << 24,
+            TintSource::Custom(index) => 5 << 24 | (index & 0xFF_FFFF),
+        }
+    }
+
+    /// Unpacks a source packed by [`TintSource::to_bits`]
+    pub const fn from_bits(bits: u32) -> TintSource {
+        let low = bits & 0xFF_FFFF;
+        match bits >> 24 {
+            1 => TintSource::Constant([(low >> 16) as u8, (low >> 8) as u8, low as u8]),
+            2 => TintSource::Grass,
+            3 => TintSource::Foliage,
+            4 => TintSource::Water,
+            5 => TintSource::Custom(low),
+            _ => TintSource::None,
+        }
+    }
+}
+
+/// An image of colours by temperature and humidity.
+///
+/// Temperature goes from hot on the left to cold on the right, and humidity
+/// from humid at the top to dry at the bottom.  Humidity is scaled by
+/// temperature first, so only the lower left triangle is ever sampled.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Colormap {
+    size: [u32; 2],
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Colormap {
+    /// A colormap from its pixels, row by row from the top.
+    ///
+    /// Panics if the number of pixels doesn't match the size.
+    pub fn from_rgb(size: [u32; 2], pixels: Vec<[u8; 3]>) -> Colormap {
+        assert_eq!(pixels.len(), size[0] as usize * size[1] as usize, "the colormap's pixels don't match its size");
+        assert!(!pixels.is_empty(), "a colormap needs at least one pixel");
+        Colormap { size, pixels }
+    }
+
+    /// Loads a colormap from a PNG image
+    #[cfg(feature = "textures")]
+    pub fn load(path: &Path) -> Result<Colormap, crate::textures::TextureError> {
+        let image = crate::textures::read_png(path)
+            .map_err(|kind| crate::textures::TextureError { path: path.to_path_buf(), kind })?;
+        let pixels = image.pixels.chunks_exact(4).map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+        Ok(Colormap::from_rgb([image.width, image.height], pixels))
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// The colour for a temperature and humidity, each from 0 to 1
+    pub fn sample(&self, temperature: f32, humidity: f32) -> [u8; 3] {
+        let temperature = temperature.clamp(0.0, 1.0);
+        let humidity = humidity.clamp(0.0, 1.0) * temperature;
+        let [width, height] = self.size.map(|size| size as f32 - 1.0);
+        let x = ((1.0 - temperature) * width).round() as usize;
+        let y = ((1.0 - humidity) * height).round() as usize;
+        self.pixels[y * self.size[0] as usize + x]
+    }
+}
+
+/// Every colormap a [`TintSource`] can use
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Colormaps {
+    pub grass: Colormap,
+    pub foliage: Colormap,
+    pub water: [u8; 3],
+    /// The colormaps of [`TintSource::Custom`], by index
+    pub custom: Vec<Colormap>,
+}
+
+impl Colormaps {
+    pub fn new(grass: Colormap, foliage: Colormap, water: [u8; 3]) -> Colormaps {
+        Colormaps { grass, foliage, water, custom: vec![] }
+    }
+
+    pub fn with_custom(mut self, colormap: Colormap) -> Self {
+        self.custom.push(colormap);
+        self
+    }
+
+    /// The colour to multiply a face's texture by, white for faces that
+    /// aren't tinted or use a custom colormap that doesn't exist
+    pub fn color(&self, source: TintSource, temperature: f32, humidity: f32) -> [u8; 3] {
+        match source {
+            TintSource::None => [255; 3],
+            TintSource::Constant(color) => color,
+            TintSource::Grass => self.grass.sample(temperature, humidity),
+            TintSource::Foliage => self.foliage.sample(temperature, humidity),
+            TintSource::Water => self.water,
+            TintSource::Custom(index) => self.custom.get(index as usize)
+                .map_or([255; 3], |colormap| colormap.sample(temperature, humidity)),
+        }
+    }
+}
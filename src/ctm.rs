@@ -0,0 +1,212 @@
+//! Connected textures, which join the faces of neighbouring blocks.
+//!
+//! A connected texture is a sheet of tiles, and each face picks the tile
+//! matching which of the 8 blocks around it in the plane of the face
+//! connect to it.  Every tile takes its own layers of the texture array,
+//! so a face's layer is its texture's `first_layer` plus
+//! `tile * frame_count`, see [`AnimatedTexture::tile`](crate::animation::AnimatedTexture::tile).
+//!
+//! The mesher picks the tile of every cube face, connecting it to the
+//! cubes around it that have the same texture on the same face.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, chunk::Chunk, ctm::CtmLayout, meshing::{MeshTable, Mesher, PaddedChunk}, AsId};
+//!
+//! table!(BlockType, enum BlockId, static BLOCKS = {
+//!     let Bookshelf: Basic = Block::new_basic("bookshelf")
+//!         .with_texture("bookshelf.png")
+//!         .with_connected_texture(CtmLayout::Simple);
+//! });
+//!
+//! let mesher = Mesher::new(MeshTable::new(&BLOCKS));
+//! let air = BlockId::Air(()).to_id();
+//! let mut chunk = Chunk::filled(air);
+//! for x in 0..3 {
+//!     chunk.set_id([x, 0, 0], &BlockId::Bookshelf(()));
+//! }
+//! let mesh = mesher.mesh(&PaddedChunk::new(&chunk, air));
+//!
+//! // looking at the south faces, the row has a left end, a middle, and a right end
+//! let mut south: Vec<(f32, u8)> = mesh.vertices.iter()
+//!     .filter(|vertex| vertex.normal == [0.0, 0.0, 1.0] && vertex.position[1] == 0.0)
+//!     .map(|vertex| (vertex.position[0], vertex.tile))
+//!     .collect();
+//! south.sort_by(|a, b| a.partial_cmp(b).unwrap());
+//! assert_eq!(south, [(0.0, 1), (1.0, 1), (1.0, 2), (2.0, 2), (2.0, 3), (3.0, 3)]);
+//! ```
+
+use crate::blocks::block::FaceDir;
+
+/// How the tiles of a connected texture are picked and laid out in its sheet
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CtmLayout {
+    /// 47 tiles, one for every way the 8 neighbours can connect once corners
+    /// without both their edges connected are ignored.  Tiles are in order of
+    /// their [`Neighbours`] bits, in an 8x6 sheet with the last cell unused.
+    ///
+    /// Used for glass and other blocks that join into seamless walls.
+    Blob,
+    /// 4 tiles joining faces into rows, by their left and right neighbours:
+    /// alone, the left end, the middle, then the right end, in a 4x1 sheet.
+    ///
+    /// Used for bookshelves and other blocks that join along a shelf.
+    Simple,
+}
+
+impl CtmLayout {
+    /// The number of tiles, and so the number of layers each frame takes
+    pub const fn tile_count(self) -> usize {
+        match self {
+            CtmLayout::Blob => 47,
+            CtmLayout::Simple => 4,
+        }
+    }
+
+    /// The columns and rows of tiles in a sheet
+    pub const fn grid(self) -> [u32; 2] {
+        match self {
+            CtmLayout::Blob => [8, 6],
+            CtmLayout::Simple => [4, 1],
+        }
+    }
+
+    /// The tile to use for a face with the given neighbours.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # use opentale_blocks::ctm::{CtmLayout, Neighbours};
+    ///
+    /// assert_eq!(CtmLayout::Blob.tile(Neighbours::NONE), 0);
+    /// assert_eq!(CtmLayout::Blob.tile(Neighbours::ALL), 46);
+    /// // corners only count when both of their edges connect
+    /// assert_eq!(CtmLayout::Blob.tile(Neighbours(Neighbours::UP_RIGHT)), 0);
+    ///
+    /// assert_eq!(CtmLayout::Simple.tile(Neighbours(Neighbours::RIGHT)), 1);
+    /// assert_eq!(CtmLayout::Simple.tile(Neighbours(Neighbours::LEFT | Neighbours::RIGHT | Neighbours::UP)), 2);
+    /// ```
+    pub const fn tile(self, neighbours: Neighbours) -> usize {
+        match self {
+            CtmLayout::Blob => BLOB_TILES[neighbours.reduced().0 as usize] as usize,
+            CtmLayout::Simple => {
+                let left = neighbours.0 & Neighbours::LEFT != 0;
+                let right = neighbours.0 & Neighbours::RIGHT != 0;
+                match (left, right) {
+                    (false, false) => 0,
+                    (false, true) => 1,
+                    (true, true) => 2,
+                    (true, false) => 3,
+                }
+            }
+        }
+    }
+}
+
+/// Which of the 8 blocks around a face in its plane connect to it,
+/// one bit each going clockwise from up, as seen looking at the face.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Neighbours(pub u8);
+
+impl Neighbours {
+    pub const UP: u8 = 1;
+    pub const UP_RIGHT: u8 = 2;
+    pub const RIGHT: u8 = 4;
+    pub const DOWN_RIGHT: u8 = 8;
+    pub const DOWN: u8 = 16;
+    pub const DOWN_LEFT: u8 = 32;
+    pub const LEFT: u8 = 64;
+    pub const UP_LEFT: u8 = 128;
+
+    pub const NONE: Neighbours = Neighbours(0);
+    pub const ALL: Neighbours = Neighbours(255);
+
+    /// The offset of each neighbour from the block, in bit order
+    pub const fn offsets(face: FaceDir) -> [[i32; 3]; 8] {
+        let (right, up) = face_axes(face);
+        let mut offsets = [[0; 3]; 8];
+        let steps = [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+        let mut i = 0;
+        while i < 8 {
+            let (x, y) = steps[i];
+            offsets[i] = [
+                right[0] * x + up[0] * y,
+                right[1] * x + up[1] * y,
+                right[2] * x + up[2] * y,
+            ];
+            i += 1;
+        }
+        offsets
+    }
+
+    /// Finds the neighbours of a face, given whether the block
+    /// at an offset from the face's block connects to it
+    pub fn new(face: FaceDir, mut connects: impl FnMut([i32; 3]) -> bool) -> Neighbours {
+        let mut bits = 0;
+        for (bit, offset) in Self::offsets(face).into_iter().enumerate() {
+            if connects(offset) {
+                bits |= 1 << bit;
+            }
+        }
+        Neighbours(bits)
+    }
+
+    /// Drops the corners whose two edges don't both connect
+    pub const fn reduced(self) -> Neighbours {
+        let mut bits = self.0 & (Self::UP | Self::RIGHT | Self::DOWN | Self::LEFT);
+        let corners = [
+            (Self::UP_RIGHT, Self::UP | Self::RIGHT),
+            (Self::DOWN_RIGHT, Self::DOWN | Self::RIGHT),
+            (Self::DOWN_LEFT, Self::DOWN | Self::LEFT),
+            (Self::UP_LEFT, Self::UP | Self::LEFT),
+        ];
+        let mut i = 0;
+        while i < 4 {
+            let (corner, edges) = corners[i];
+            if self.0 & corner != 0 && self.0 & edges == edges {
+                bits |= corner;
+            }
+            i += 1;
+        }
+        Neighbours(bits)
+    }
+}
+
+/// The directions pointing right and up across a face, as seen looking at it.
+///
+/// These follow the texture coordinates of [`Quad::cuboid_face`](crate::blocks::geometry::Quad::cuboid_face),
+/// so side faces are upright, the top face has north up, and the bottom face has south up.
+pub const fn face_axes(face: FaceDir) -> ([i32; 3], [i32; 3]) {
+    match face {
+        FaceDir::Up => ([1, 0, 0], [0, 0, -1]),
+        FaceDir::North => ([-1, 0, 0], [0, 1, 0]),
+        FaceDir::West => ([0, 0, 1], [0, 1, 0]),
+        FaceDir::East => ([0, 0, -1], [0, 1, 0]),
+        FaceDir::South => ([1, 0, 0], [0, 1, 0]),
+        FaceDir::Down => ([1, 0, 0], [0, 0, 1]),
+    }
+}
+
+/// The blob tile of every set of neighbours, by their bits
+const BLOB_TILES: [u8; 256] = {
+    let mut tiles = [0; 256];
+    let mut count = 0;
+    let mut bits = 0;
+    while bits < 256 {
+        if Neighbours(bits as u8).reduced().0 as usize == bits {
+            tiles[bits] = count;
+            count += 1;
+        }
+        bits += 1;
+    }
+    assert!(count as usize == CtmLayout::Blob.tile_count());
+    let mut bits = 0;
+    while bits < 256 {
+        tiles[bits] = tiles[Neighbours(bits as u8).reduced().0 as usize];
+        bits += 1;
+    }
+    tiles
+};
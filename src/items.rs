@@ -0,0 +1,103 @@
+//! Inventory items generated alongside the blocks of a table.
+//!
+//! Giving [`table!`](crate::table) an item enum and table name generates
+//! an item for every block, linked both ways with `ItemId::from_block`
+//! and `BlockId::item`.  Blocks that can't be held, like fire, opt out
+//! with `#[item(none)]`, and `#[item(stack_size = 16)]` changes how
+//! many fit in a stack.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, custom::Custom, slab::Slab, BlockType}}, items::ItemIcon, AsId};
+//!
+//! table!(BlockType, enum BlockId, items enum ItemId, static ITEMS, static BLOCKS = {
+//!     let Dirt: Basic = Block::new_basic("dirt");
+//!     #[item(none)]
+//!     let Fire: Custom = Block::new_custom("fire");
+//!     #[item(stack_size = 16)]
+//!     let Sign: Custom = Block::new_custom("sign");
+//!     let StoneSlab: Slab = Block::new_slab("stone_slab");
+//! });
+//!
+//! assert_eq!(ItemId::ALL, [ItemId::Dirt, ItemId::Sign, ItemId::StoneSlab]);
+//! assert_eq!(BlockId::Dirt(()).item(), Some(ItemId::Dirt));
+//! assert_eq!(BlockId::Fire(()).item(), None);
+//! assert_eq!(ItemId::from_block(&BlockId::Air(())), None);
+//!
+//! let sign = &ITEMS[ItemId::Sign];
+//! assert_eq!((sign.name(), sign.stack_size), ("sign", 16));
+//! assert_eq!(ItemId::Dirt.stack_size(), 64);
+//! assert_eq!(ItemId::StoneSlab.block().to_string(), "StoneSlab[type: bottom]");
+//!
+//! assert_eq!(ITEMS[ItemId::Dirt].icon(), ItemIcon::Cube { up: 1, left: 1, right: 1 });
+//! assert_eq!(ITEMS[ItemId::Sign].icon(), ItemIcon::Sprite { texture: 3 });
+//! assert_eq!(ITEMS[ItemId::StoneSlab].icon(), ItemIcon::Model);
+//! ```
+
+use crate::blocks::{block::FaceDir, block_types::BlockType};
+
+/// How many items fit in a stack unless the table says otherwise
+pub const DEFAULT_STACK_SIZE: u32 = 64;
+
+/// The inventory item that places a block
+pub struct Item<T: 'static + ?Sized> {
+    /// The block the item places, in its first state
+    pub block: &'static T,
+    /// The most items that fit in one inventory slot
+    pub stack_size: u32,
+}
+
+impl<T: 'static + ?Sized + BlockType> Item<T> {
+    /// The item's technical name, the same as its block's
+    pub fn name(&self) -> &'static str {
+        self.block.name()
+    }
+
+    /// How the item should be drawn in inventories, from its block's textures
+    pub fn icon(&self) -> ItemIcon {
+        let block = self.block;
+        let texture = |face: FaceDir| block.index() + block.texture_slot(0, face);
+        if block.is_cube(0) {
+            ItemIcon::Cube {
+                up: texture(FaceDir::Up),
+                left: texture(FaceDir::South),
+                right: texture(FaceDir::East),
+            }
+        } else if block.quads(0).is_some() {
+            ItemIcon::Model
+        } else {
+            ItemIcon::Sprite { texture: texture(FaceDir::North) }
+        }
+    }
+}
+
+/// A hint for rendering an item's inventory icon, using texture indices
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemIcon {
+    /// An isometric cube seen from above its south east corner
+    Cube { up: usize, left: usize, right: usize },
+    /// The block's first state drawn from its quads, like slabs and stairs
+    Model,
+    /// A flat image of one texture, for blocks with custom models
+    Sprite { texture: usize },
+}
+
+/// Every item of a table, indexed by the generated item enum
+pub struct ItemTable<T: 'static + ?Sized>(pub &'static [Item<T>]);
+
+impl<T: 'static + ?Sized> ItemTable<T> {
+    pub fn iter(&self) -> impl Iterator<Item = &'static Item<T>> + '_ {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
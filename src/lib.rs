@@ -1,22 +1,56 @@
 #![feature(adt_const_params)]
 #![feature(const_trait_impl)]
 
+use blocks::properties::BlockProperties;
+
+pub mod animation;
+pub mod assets;
+pub mod behavior;
+pub mod block_entity;
 pub mod blocks;
+pub mod chunk;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod colormap;
+pub mod ctm;
+pub mod items;
+pub mod lighting;
+pub mod meshing;
+pub mod model;
+pub mod region;
+#[cfg(feature = "schematic")]
+pub mod schematic;
+pub mod simulation;
 pub mod table;
+pub mod tags;
+#[cfg(feature = "textures")]
+pub mod textures;
+pub mod variants;
+
+#[doc(hidden)]
+pub use paste;
 
+/// Anything that can be defined in a [`table!`].
+///
+/// Entries without textures, like sounds or biomes, can leave out
+/// [`get_texture_size`](Buildable::get_texture_size), [`with_index`](Buildable::with_index)
+/// and [`set_index`](Buildable::set_index), and take no texture layers.
+/// Likewise [`set_properties`](Buildable::set_properties) only matters to blocks.
 #[const_trait]
 pub trait Buildable: HasBuildVariants {
     fn new_with_name(name: &'static str) -> Self;
-    fn get_texture_size() -> usize;
+    /// The number of texture layers the entry takes, 0 for entries without textures
+    fn get_texture_size() -> usize { 0usize }
     /// This is useful for blocks with multiple blockstates
     /// such as stairs, slabs, waterloggable blocks, rotateable blocks,
     /// connecting blocks, etc.  Most blocks don't have blockstates,
     /// so the default is 1.
     fn get_id_span() -> usize { 1usize }
-    fn with_index(self, idx: usize) -> Self;
-    fn set_index(&mut self, idx: usize);
+    fn with_index(self, _idx: usize) -> Self where Self: Sized { self }
+    fn set_index(&mut self, _idx: usize) {}
     fn with_id(self, id: usize) -> Self;
     fn set_id(&mut self, id: usize);
+    fn set_properties(&mut self, _properties: BlockProperties) {}
 }
 
 pub trait HasBuildVariants {
@@ -90,13 +124,173 @@ impl<T: AsId, U: AsId> AsId for (T, U) {
     }
 }
 
+/// Defines a static table of entries, along with an enum of their ids.
+///
+/// Every entry is a static of a type implementing [`Buildable`] and the
+/// `$slice` trait, which must be `Sync`, and the table holds them all as
+/// `&dyn $slice`.  Ids are given out in order, with each entry taking as
+/// many as it has states.
+///
+/// The enum starts with a null variant with id 0, `Air` unless another name is
+/// given in parentheses, like `enum BiomeId(Void)`.  Tables of things that
+/// have no "nothing", like sounds, leave it out with `enum SoundId()`, and
+/// their first entry gets id 0.
+///
+/// Entries with per block data declare its type after theirs, like
+/// `let Furnace: Rotateable<Full, { RotDir::Y }> with FurnaceData = ...;`,
+/// see [`block_entity`].  Entries that do something attach a behaviour with
+/// `#[behavior(Value)]`, see [`behavior`].
+///
+/// Attributes and a visibility can go before `enum`, like
+/// `#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)] pub(crate) enum BlockId`.
+/// The attributes are put on the enum, and the visibility on everything the
+/// table defines, its statics included, which are `pub` if none is given.
+///
+/// Instead of its entries, a table can list the parts it's made of, declared
+/// with [`table_part!`] in other modules or crates, like
+/// `static BLOCKS = [nature, machines::machines]`.  Entries get their ids and
+/// texture indices in order of the parts, then of the entries in each part.
+///
+/// Block tables can also generate items with `items enum ItemId, static ITEMS,`
+/// (see [`items`]), and tags with `tags Tag { log, ... },` (see [`tags`]).
+///
+/// ### Example
+///
+/// A registry of sound events, without a null variant or textures:
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, AsId, Buildable, HasBuildVariants};
+///
+/// pub trait SoundEvent: Sync {
+///     fn name(&self) -> &'static str;
+///     fn id(&self) -> usize;
+///     fn volume(&self) -> f32;
+/// }
+///
+/// pub struct Sound {
+///     name: &'static str,
+///     volume: f32,
+///     id: usize,
+/// }
+///
+/// impl Sound {
+///     const fn new(name: &'static str) -> Sound {
+///         Sound::new_with_name(name)
+///     }
+///
+///     const fn with_volume(mut self, volume: f32) -> Sound {
+///         self.volume = volume;
+///         self
+///     }
+/// }
+///
+/// impl const Buildable for Sound {
+///     fn new_with_name(name: &'static str) -> Self {
+///         Sound { name, volume: 1.0, id: 0 }
+///     }
+///     fn with_id(mut self, id: usize) -> Self {
+///         self.id = id;
+///         self
+///     }
+///     fn set_id(&mut self, id: usize) {
+///         self.id = id;
+///     }
+/// }
+///
+/// impl HasBuildVariants for Sound {
+///     type Variants = ();
+/// }
+///
+/// impl SoundEvent for Sound {
+///     fn name(&self) -> &'static str { self.name }
+///     fn id(&self) -> usize { self.id }
+///     fn volume(&self) -> f32 { self.volume }
+/// }
+///
+/// table!(SoundEvent, enum SoundId(), static SOUNDS = {
+///     let Click: Sound = Sound::new("ui.click");
+///     let Thunder: Sound = Sound::new("weather.thunder").with_volume(4.0);
+/// });
+///
+/// assert_eq!(SoundId::from_id(0).to_string(), "Click");
+/// assert_eq!(SOUNDS[SoundId::Thunder(())].id(), 1);
+/// assert_eq!(SOUNDS[SoundId::Thunder(())].volume(), 4.0);
+/// assert_eq!(SoundId::get_id_span(), 2);
+/// assert_eq!(SOUNDS.iter().map(|sound| sound.name()).collect::<Vec<_>>(), ["ui.click", "weather.thunder"]);
+/// ```
+///
+/// Deriving traits on the enum, so ids can be compared, hashed and printed:
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use std::collections::HashMap;
+/// # use opentale_blocks::{table, id, blocks::{block::Block, block_types::{basic::Basic, slab::{Slab, SlabType}, stair::Stair, BlockType}}, AsId};
+///
+/// table!(BlockType, #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)] pub(crate) enum BlockId, static BLOCKS = {
+///     let Stone: Basic = Block::new_basic("stone");
+///     let StoneSlab: Slab = Block::new_slab("stone_slab");
+///     let StoneStair: Stair = Block::new_stair("stone_stair");
+/// });
+///
+/// let mut counts = HashMap::new();
+/// for id in [BlockId::Stone(()), id!(BlockId::StoneSlab { ty: SlabType::Top }), BlockId::Stone(())] {
+///     *counts.entry(id).or_insert(0) += 1;
+/// }
+/// assert_eq!(counts[&BlockId::Stone(())], 2);
+/// assert_eq!(format!("{:?}", BlockId::StoneSlab((SlabType::Top, ()))), "StoneSlab((Top, ()))");
+///
+/// let stair = BlockId::from_id(7);
+/// assert_eq!(BlockId::from_id(stair.to_id()), stair);
+/// assert_ne!(stair, BlockId::Air(()));
+/// ```
+///
+/// Renaming the null variant:
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, AsId};
+///
+/// table!(BlockType, enum FluidId(Empty), static FLUIDS = {
+///     let Water: Basic = Block::new_basic("water");
+/// });
+///
+/// assert_eq!(FluidId::from_id(0).to_string(), "Empty");
+/// assert_eq!(FLUIDS[FluidId::Water(())].id(), 1);
+/// assert_eq!(FluidId::Water(()).index(), 1);
+/// ```
 #[macro_export]
 macro_rules! table {
     (
+        $slice:path,
+        $(#[$enum_attr:meta])*
+        enum $($rest:tt)*
+    ) => {
+        $crate::table!($slice, $(#[$enum_attr])* pub enum $($rest)*);
+    };
+    (
+        $slice:path,
+        $(#[$enum_attr:meta])*
+        $vis:vis enum $enum:ident,
+        $($rest:tt)*
+    ) => {
+        $crate::table!($slice, $(#[$enum_attr])* $vis enum $enum(Air), $($rest)*);
+    };
+    (
+        @entries
         $slice:path, 
-        enum $enum:ident,
+        $(#[$enum_attr:meta])*
+        $vis:vis enum $enum:ident($($null:ident)?),
+        $(items enum $item_enum:ident, static $item_table:ident,)?
+        $(tags $tag_enum:ident { $($tag:ident),* $(,)? },)?
         static $table:ident = {
-            $(let $block_name:ident : $block_type:ty = $block_expr:expr ;)*
+            $(
+                $(#[$($attr:tt)*])*
+                let $block_name:ident : $block_type:ty [$($data:ty)?] = $block_expr:expr ;
+            )*
         }
     ) => {
         $crate::assert_items_define!(
@@ -104,19 +298,552 @@ macro_rules! table {
             $($block_name : $block_type),*
         );
         $crate::items_define!(
-            1, 1,
+            $vis, 0 $(+ $crate::for_null!($null, 1))?, 1,
             $($block_name : $block_type = $block_expr),*
         );
 
         $crate::table_define!(
-            $table, $slice, 
+            $vis $table, $slice, 
             $($block_name),*
         );
 
         $crate::enum_define!(
-            $enum, $slice,
+            $(#[$enum_attr])* $vis $enum, [$($null)?], $slice,
             $($block_name : $block_type),*
         );
+
+        $crate::item_enum_define!(
+            [$($vis $item_enum, $item_table)?], $slice, $enum;
+            [];
+            $($block_name [$([$($attr)*])*]),*
+        );
+
+        $crate::block_entity_define!(
+            $enum;
+            $($block_name [$($data)?]),*
+        );
+
+        $crate::behavior_define!(
+            $enum;
+            [];
+            $($block_name [$([$($attr)*])*]),*
+        );
+
+        $crate::tags_define!(
+            [$($vis $tag_enum { $($tag),* })?], $enum, [$($null)?],
+            $($block_name [$([$($attr)*])*]),*
+        );
+    };
+    (
+        $slice:path, 
+        $(#[$enum_attr:meta])*
+        $vis:vis enum $enum:ident($($null:ident)?),
+        $(items enum $item_enum:ident, static $item_table:ident,)?
+        $(tags $tag_enum:ident { $($tag:ident),* $(,)? },)?
+        static $table:ident = { $($entries:tt)* }
+    ) => {
+        $crate::table_entries!(
+            $crate::table;
+            {
+                $slice,
+                $(#[$enum_attr])*
+                $vis enum $enum($($null)?),
+                $(items enum $item_enum, static $item_table,)?
+                $(tags $tag_enum { $($tag),* },)?
+                static $table =
+            }
+            [];
+            $($entries)*
+        );
+    };
+    (
+        $slice:path, 
+        $(#[$enum_attr:meta])*
+        $vis:vis enum $enum:ident($($null:ident)?),
+        $(items enum $item_enum:ident, static $item_table:ident,)?
+        $(tags $tag_enum:ident { $($tag:ident),* $(,)? },)?
+        static $table:ident = [$($($part:ident)::+),* $(,)?]
+    ) => {
+        $crate::table_parts!(
+            {
+                $slice,
+                $(#[$enum_attr])*
+                $vis enum $enum($($null)?),
+                $(items enum $item_enum, static $item_table,)?
+                $(tags $tag_enum { $($tag),* },)?
+                static $table =
+            }
+            []
+            $([$($part)::+])*
+        );
+    };
+}
+
+/// Declares some of the entries of a table in a module of their own, so
+/// a big table can be split across files and crates and put together by
+/// listing its parts in [`table!`].
+///
+/// `table_part!(nature, { ... })` defines a `pub` module called `nature`,
+/// or one with the visibility given before the name.  It holds an unnumbered
+/// constant and a type alias for every entry, so entries can use whatever
+/// is in scope where the part is declared.  Parts in the same crate can share
+/// a name, unless they also have the same entries.
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # fn main() {
+/// # use opentale_blocks::{table, blocks::block_types::BlockType, AsId};
+/// table!(BlockType, enum BlockId, tags Tag { log }, static BLOCKS = [nature_blocks::nature, machines]);
+///
+/// assert_eq!(BlockId::from_id(1).to_string(), "Dirt");
+/// assert_eq!(BlockId::from_id(4).to_string(), "Furnace[facing: north]");
+/// assert_eq!(BLOCKS[BlockId::Pipe(())].id(), 8);
+///
+/// // texture indices are given out in the same order, starting after the debug texture
+/// let indices: Vec<usize> = BLOCKS.iter().map(|block| block.index()).collect();
+/// assert_eq!(indices, [1, 2, 3, 6, 12]);
+/// assert!(BlockId::Log(()).has_tag(Tag::Log));
+/// # }
+///
+/// mod nature_blocks {
+///     use opentale_blocks::{table_part, blocks::{block::Block, block_types::{basic::Basic, pillar::Pillar}}};
+///
+///     table_part!(nature, {
+///         let Dirt: Basic = Block::new_basic("dirt");
+///         let Grass: Basic = Block::new_basic("grass");
+///         #[tags(log)]
+///         let Log: Pillar = Block::new_pillar("log");
+///     });
+/// }
+///
+/// use opentale_blocks::{table_part, blocks::{block::Block, block_types::{basic::Basic, full::Full, rotateable::{RotDir, Rotateable}}}};
+///
+/// table_part!(pub(crate) machines, {
+///     let Furnace: Rotateable<Full, { RotDir::Y }> = Block::new_rotateable(Block::new_full("furnace"));
+///     let Pipe: Basic = Block::new_basic("pipe");
+/// });
+/// ```
+///
+/// Parts with the same name in different modules, put in different tables:
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::block_types::BlockType, AsId};
+/// table!(BlockType, enum BlockId, static BLOCKS = [overworld::terrain]);
+/// table!(BlockType, enum NetherBlockId, static NETHER_BLOCKS = [nether::terrain]);
+///
+/// assert_eq!(BLOCKS[BlockId::Stone(())].id(), 1);
+/// assert_eq!(NETHER_BLOCKS[NetherBlockId::Netherrack(())].id(), 1);
+///
+/// mod overworld {
+///     use opentale_blocks::{table_part, blocks::{block::Block, block_types::basic::Basic}};
+///
+///     table_part!(terrain, {
+///         let Stone: Basic = Block::new_basic("stone");
+///     });
+/// }
+///
+/// mod nether {
+///     use opentale_blocks::{table_part, blocks::{block::Block, block_types::basic::Basic}};
+///
+///     table_part!(terrain, {
+///         let Netherrack: Basic = Block::new_basic("netherrack");
+///     });
+/// }
+/// ```
+#[macro_export]
+macro_rules! table_part {
+    ($name:ident, { $($entries:tt)* }) => {
+        $crate::table_part!(pub $name, { $($entries)* });
+    };
+    ($vis:vis $name:ident, { $($entries:tt)* }) => {
+        $crate::table_entries!($crate::table_part; { $vis $name, } []; $($entries)*);
+    };
+    (
+        @entries $vis:vis $name:ident, {
+            $(
+                $(#[$($attr:tt)*])*
+                let $block_name:ident : $block_type:ty [$($data:ty)?] = $block_expr:expr ;
+            )*
+        }
+    ) => {
+        $crate::table_part!(
+            @define ($) $vis $name,
+            $($block_name : $block_type [$($data)?] = $block_expr, [$([$($attr)*])*]);*
+        );
+    };
+    (
+        @define ($d:tt) $vis:vis $name:ident,
+        $($block_name:ident : $block_type:ty [$($data:ty)?] = $block_expr:expr, [$($attrs:tt)*]);*
+    ) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $(
+                #[allow(non_upper_case_globals)]
+                pub const $block_name: $block_type = $block_expr;
+                #[allow(non_camel_case_types)]
+                pub type $block_name = $block_type;
+            )*
+
+            $crate::paste::paste! {
+                $($(
+                    #[doc(hidden)]
+                    pub type [<$block_name BlockEntity>] = $data;
+                )?)*
+
+                /// Adds the entries of this part to a table being put together
+                #[doc(hidden)]
+                #[macro_export]
+                macro_rules! [<__table_part_ $name $(_ $block_name)*>] {
+                    ($d callback:path; $d path:tt $d($d args:tt)*) => {
+                        $d callback! {
+                            @part [$(
+                                $(#$attrs)*
+                                let $block_name: $crate::table_part_path!($d path $block_name)
+                                    [$($crate::table_part_path!($d path [<$block_name BlockEntity>], $data))?] =
+                                    $crate::table_part_path!($d path $block_name);
+                            )*]
+                            $d($d args)*
+                        }
+                    };
+                }
+
+                #[doc(hidden)]
+                pub use [<__table_part_ $name $(_ $block_name)*>] as entries;
+            }
+        }
+    };
+}
+
+/// Puts together the entries of a table's parts, one part at a time,
+/// then defines the table with all of them
+#[macro_export]
+macro_rules! table_parts {
+    (@part [$($new:tt)*] {$($header:tt)*} [$($entries:tt)*] $($parts:tt)*) => {
+        $crate::table_parts!({$($header)*} [$($entries)* $($new)*] $($parts)*);
+    };
+    ({$($header:tt)*} [$($entries:tt)*]) => {
+        $crate::table!(@entries $($header)* { $($entries)* });
+    };
+    ({$($header:tt)*} $entries:tt [$($part:ident)::+] $($parts:tt)*) => {
+        $($part::)+entries!($crate::table_parts; [$($part)::+] {$($header)*} $entries $($parts)*);
+    };
+}
+
+/// Puts the block entity type of every entry of a table or table part in
+/// brackets after its type, like `let Chest: Basic [ChestData] = ...;`, with
+/// empty brackets for entries without one, then passes them all to the macro
+/// given, after its header.
+///
+/// Works through the entries one at a time, going through the type of
+/// entries with a block entity a token at a time to find the `with`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! table_entries {
+    ($callback:path; {$($header:tt)*} [$($entries:tt)*];) => {
+        $callback!(@entries $($header)* { $($entries)* });
+    };
+    (
+        $callback:path; $header:tt [$($entries:tt)*];
+        $(#[$($attr:tt)*])*
+        let $block_name:ident : $block_type:ty = $block_expr:expr ;
+        $($rest:tt)*
+    ) => {
+        $crate::table_entries!(
+            $callback; $header
+            [$($entries)* $(#[$($attr)*])* let $block_name: $block_type [] = $block_expr;];
+            $($rest)*
+        );
+    };
+    (
+        $callback:path; $header:tt $entries:tt;
+        $(#[$($attr:tt)*])*
+        let $block_name:ident : $($rest:tt)*
+    ) => {
+        $crate::table_entries!(
+            @with $callback; $header $entries;
+            [$([$($attr)*])*] $block_name [];
+            $($rest)*
+        );
+    };
+    (
+        @with $callback:path; $header:tt [$($entries:tt)*];
+        [$($attrs:tt)*] $block_name:ident [$($block_type:tt)*];
+        with $data:ty = $block_expr:expr ;
+        $($rest:tt)*
+    ) => {
+        $crate::table_entries!(
+            $callback; $header
+            [$($entries)* $(#$attrs)* let $block_name: $($block_type)* [$data] = $block_expr;];
+            $($rest)*
+        );
+    };
+    (
+        @with $callback:path; $header:tt $entries:tt;
+        $attrs:tt $block_name:ident [$($block_type:tt)*];
+        $next:tt $($rest:tt)*
+    ) => {
+        $crate::table_entries!(
+            @with $callback; $header $entries;
+            $attrs $block_name [$($block_type)* $next];
+            $($rest)*
+        );
+    };
+}
+
+/// The path to an entry of a table part, from the part's path, or to
+/// the alias of its block entity type, given along with the type
+#[macro_export]
+macro_rules! table_part_path {
+    ([$($part:ident)::+] $name:ident) => {
+        $($part::)+$name
+    };
+    ($path:tt $name:ident, $data:ty) => {
+        $crate::table_part_path!($path $name)
+    };
+}
+
+/// Generates the item enum and item table of a table, one item per block
+/// unless the block has `#[item(none)]`.
+///
+/// Works through the blocks one at a time, collecting
+/// each block with an item along with its stack size.
+#[macro_export]
+macro_rules! item_enum_define {
+    ([], $($rest:tt)*) => {
+
+    };
+    (
+        [$vis:vis $item_enum:ident, $item_table:ident], $slice:path, $enum_name:ident;
+        [$(($item:ident, $stack_size:expr))*];
+    ) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        $vis enum $item_enum {
+            $($item),*
+        }
+
+        $vis static $item_table: $crate::items::ItemTable<dyn $slice> = $crate::items::ItemTable(&[
+            $($crate::items::Item { block: &$item, stack_size: $stack_size }),*
+        ]);
+
+        impl $item_enum {
+            pub const ALL: &'static [$item_enum] = &[$($item_enum::$item),*];
+
+            /// The item placing a block, or `None` if the block has no item
+            pub fn from_block(block: &$enum_name) -> Option<$item_enum> {
+                match block {
+                    $($enum_name::$item(_) => Some($item_enum::$item),)*
+                    _ => None,
+                }
+            }
+
+            /// The first state of the block this item places
+            pub fn block(&self) -> $enum_name {
+                <$enum_name as $crate::AsId>::from_id($item_table.0[*self as usize].block.id())
+            }
+
+            pub fn stack_size(&self) -> u32 {
+                $item_table.0[*self as usize].stack_size
+            }
+        }
+
+        impl $enum_name {
+            /// The item that places this block, or `None` if the block has no item
+            pub fn item(&self) -> Option<$item_enum> {
+                $item_enum::from_block(self)
+            }
+        }
+
+        impl std::ops::Index<$item_enum> for $crate::items::ItemTable<dyn ($slice)> {
+            type Output = $crate::items::Item<dyn $slice>;
+
+            fn index(&self, index: $item_enum) -> &Self::Output {
+                &self.0[index as usize]
+            }
+        }
+    };
+    (
+        $head:tt, $slice:path, $enum_name:ident;
+        $items:tt;
+        $block_name:ident [$($attrs:tt)*] $(, $($rest:tt)*)?
+    ) => {
+        $crate::item_enum_define!(
+            @attrs $head, $slice, $enum_name;
+            $items;
+            $block_name, $crate::items::DEFAULT_STACK_SIZE, [$($attrs)*];
+            $($($rest)*)?
+        );
+    };
+    (
+        @attrs $head:tt, $slice:path, $enum_name:ident;
+        [$($items:tt)*];
+        $block_name:ident, $stack_size:expr, [];
+        $($rest:tt)*
+    ) => {
+        $crate::item_enum_define!(
+            $head, $slice, $enum_name;
+            [$($items)* ($block_name, $stack_size)];
+            $($rest)*
+        );
+    };
+    (
+        @attrs $head:tt, $slice:path, $enum_name:ident;
+        $items:tt;
+        $block_name:ident, $stack_size:expr, [[item(none)] $($attrs:tt)*];
+        $($rest:tt)*
+    ) => {
+        $crate::item_enum_define!(
+            $head, $slice, $enum_name;
+            $items;
+            $($rest)*
+        );
+    };
+    (
+        @attrs $head:tt, $slice:path, $enum_name:ident;
+        $items:tt;
+        $block_name:ident, $stack_size:expr, [[item(stack_size = $size:expr)] $($attrs:tt)*];
+        $($rest:tt)*
+    ) => {
+        $crate::item_enum_define!(
+            @attrs $head, $slice, $enum_name;
+            $items;
+            $block_name, $size, [$($attrs)*];
+            $($rest)*
+        );
+    };
+    (
+        @attrs $head:tt, $slice:path, $enum_name:ident;
+        $items:tt;
+        $block_name:ident, $stack_size:expr, [[$attr:ident $($args:tt)*] $($attrs:tt)*];
+        $($rest:tt)*
+    ) => {
+        $crate::entry_attr!($attr);
+        $crate::item_enum_define!(
+            @attrs $head, $slice, $enum_name;
+            $items;
+            $block_name, $stack_size, [$($attrs)*];
+            $($rest)*
+        );
+    };
+}
+
+/// Implements [`HasBlockEntity`](crate::block_entity::HasBlockEntity) for the
+/// enum of a table, from the blocks declared `with` a block entity type.
+#[macro_export]
+macro_rules! block_entity_define {
+    ($enum_name:ident; $($block_name:ident [$($data:ty)?]),*) => {
+        impl $crate::block_entity::HasBlockEntity for $enum_name {
+            fn has_block_entity(&self) -> bool {
+                match self {
+                    $($enum_name::$block_name(_) => $crate::block_entity_define!(@has $($data)?),)*
+                    #[allow(unreachable_patterns)]
+                    _ => false,
+                }
+            }
+
+            fn new_block_entity(&self) -> Option<Box<dyn $crate::block_entity::BlockEntity>> {
+                match self {
+                    $($enum_name::$block_name(_) => $crate::block_entity_define!(@new $($data)?),)*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+
+            fn state_ids(&self) -> std::ops::Range<usize> {
+                $enum_name::state_ids(self)
+            }
+        }
+    };
+    (@has $data:ty) => {
+        true
+    };
+    (@has) => {
+        false
+    };
+    (@new $data:ty) => {
+        Some(Box::new(<$data as Default>::default()))
+    };
+    (@new) => {
+        None
+    };
+}
+
+/// Implements [`HasBehavior`](crate::behavior::HasBehavior) for the
+/// enum of a table, from the blocks with `#[behavior(Value)]`.
+///
+/// Works through the blocks one at a time, collecting
+/// each block with a behaviour along with its value.
+#[macro_export]
+macro_rules! behavior_define {
+    (
+        $enum_name:ident;
+        [$(($block:ident, $behavior:expr))*];
+    ) => {
+        impl $crate::behavior::HasBehavior for $enum_name {
+            fn behavior(&self) -> &'static dyn $crate::behavior::BlockBehavior<Self> {
+                match self {
+                    $($enum_name::$block(_) => &$behavior,)*
+                    #[allow(unreachable_patterns)]
+                    _ => &$crate::behavior::NoBehavior,
+                }
+            }
+        }
+    };
+    (
+        $enum_name:ident;
+        $behaviors:tt;
+        $block_name:ident [$($attrs:tt)*] $(, $($rest:tt)*)?
+    ) => {
+        $crate::behavior_define!(
+            @attrs $enum_name;
+            $behaviors;
+            $block_name, [$($attrs)*];
+            $($($rest)*)?
+        );
+    };
+    (
+        @attrs $enum_name:ident;
+        $behaviors:tt;
+        $block_name:ident, [];
+        $($rest:tt)*
+    ) => {
+        $crate::behavior_define!(
+            $enum_name;
+            $behaviors;
+            $($rest)*
+        );
+    };
+    (
+        @attrs $enum_name:ident;
+        [$($behaviors:tt)*];
+        $block_name:ident, [[behavior($behavior:expr)] $($attrs:tt)*];
+        $($rest:tt)*
+    ) => {
+        $crate::behavior_define!(
+            $enum_name;
+            [$($behaviors)* ($block_name, $behavior)];
+            $($rest)*
+        );
+    };
+    (
+        @attrs $enum_name:ident;
+        $behaviors:tt;
+        $block_name:ident, [[$attr:ident $($args:tt)*] $($attrs:tt)*];
+        $($rest:tt)*
+    ) => {
+        $crate::entry_attr!($attr);
+        $crate::behavior_define!(
+            @attrs $enum_name;
+            $behaviors;
+            $block_name, [$($attrs)*];
+            $($rest)*
+        );
     };
 }
 
@@ -141,12 +868,12 @@ macro_rules! assert_items_define {
 #[macro_export]
 macro_rules! items_define {
     (
-        $id:expr, $idx:expr,
+        $vis:vis, $id:expr, $idx:expr,
         $first_name:ident : $first_type:ty = $first_expr:expr 
         $(, $rest_name:ident : $rest_type:ty = $rest_expr:expr)*
     ) => {
         #[allow(non_upper_case_globals)]
-        static $first_name: $first_type = 
+        $vis static $first_name: $first_type = 
             <$first_type as $crate::Buildable>::with_id(
                 <$first_type as $crate::Buildable>::with_index(
                     $first_expr, 
@@ -155,12 +882,12 @@ macro_rules! items_define {
                 $id
             );
         $crate::items_define!(
-            $id + <$first_type as $crate::Buildable>::get_id_span(),
+            $vis, $id + <$first_type as $crate::Buildable>::get_id_span(),
             $idx + <$first_type as $crate::Buildable>::get_texture_size(),
             $($rest_name : $rest_type = $rest_expr),*
         );
     };
-    ($id:expr, $idx:expr,) => {
+    ($vis:vis, $id:expr, $idx:expr,) => {
         
     };
 }
@@ -168,10 +895,10 @@ macro_rules! items_define {
 #[macro_export]
 macro_rules! table_define {
     (
-        $table_name:ident, $slice:path, 
+        $vis:vis $table_name:ident, $slice:path, 
         $($block_name:ident),*
     ) => {
-        pub static $table_name : $crate::table::Table<dyn $slice> = $crate::table::Table(&[
+        $vis static $table_name : $crate::table::Table<dyn $slice> = $crate::table::Table(&[
             $(& $block_name),*
         ]);
     }
@@ -179,27 +906,54 @@ macro_rules! table_define {
 
 #[macro_export]
 macro_rules! enum_define {
-    ($enum_name:ident, $slice:path, $($block_name:ident : $block_type:ty),*) => {
-        pub enum $enum_name {
-            Air(()),
+    (
+        $(#[$enum_attr:meta])* $vis:vis $enum_name:ident, [$($null:ident)?], $slice:path,
+        $($block_name:ident : $block_type:ty),*
+    ) => {
+        $(#[$enum_attr])*
+        $vis enum $enum_name {
+            $($null(()),)?
             $($block_name(<$block_type as $crate::HasBuildVariants>::Variants)),*
         }
 
         impl $enum_name {
             pub fn inner_id(&self) -> usize {
                 match self {
-                    $enum_name::Air(()) => 0,
+                    $($enum_name::$null(()) => 0,)?
                     $(
                         $enum_name::$block_name(inner) => inner.to_id()
                     ),*
                 }
             }
 
+            /// The first state of the entry at `index`, where the null variant has index 0
+            pub fn from_index(index: usize) -> Self {
+                let spans: &[usize] = &[
+                    $($crate::for_null!($null, 1),)?
+                    $(<<$block_type as $crate::HasBuildVariants>::Variants as $crate::AsId>::get_id_span()),*
+                ];
+                <Self as $crate::AsId>::from_id(spans[..index].iter().sum())
+            }
+
+            /// The ids of every state of this entry
+            pub fn state_ids(&self) -> std::ops::Range<usize> {
+                let first = <Self as $crate::AsId>::to_id(self) - self.inner_id();
+                let span = match self {
+                    $($enum_name::$null(()) => 1,)?
+                    $(
+                        $enum_name::$block_name(_) => <<$block_type as $crate::HasBuildVariants>::Variants as $crate::AsId>::get_id_span()
+                    ),*
+                };
+                first..first + span
+            }
+
             pub fn index(&self) -> usize {
-                if let $enum_name::Air(_) = self {
-                    return 0;
-                }
-                let mut index = 1;
+                $(
+                    if let $enum_name::$null(_) = self {
+                        return 0;
+                    }
+                )?
+                let mut index = 0 $(+ $crate::for_null!($null, 1))?;
                 $(
                     if let $enum_name::$block_name(_) = self {
                         return index;
@@ -215,20 +969,20 @@ macro_rules! enum_define {
             const NAME: Self::Name = stringify!($enum_name);
 
             fn from_id(id: usize) -> Self {
-                $crate::from_id_inner!($enum_name, id, $($block_name : $block_type),*);
+                $crate::from_id_inner!($enum_name, [$($null)?], id, $($block_name : $block_type),*);
             }
 
             fn to_id(&self) -> usize {
-                $crate::to_id_inner!($enum_name, self, $($block_name : $block_type),*);
+                $crate::to_id_inner!($enum_name, [$($null)?], self, $($block_name : $block_type),*);
             }
 
             fn get_id_span() -> usize {
-                1 + $crate::get_id_span_inner!($($block_type),*)
+                0 $(+ $crate::for_null!($null, 1))? + $crate::get_id_span_inner!($($block_type),*)
             }
 
             fn to_string(&self) -> String {
                 match self {
-                    $enum_name::Air(()) => "Air".to_string(),
+                    $($enum_name::$null(()) => stringify!($null).to_string(),)?
                     $(
                         $enum_name::$block_name(inner) => if <<$block_type as $crate::HasBuildVariants>::Variants as $crate::AsId>::get_id_span() == 1 {
                             stringify!($block_name).to_string()
@@ -244,7 +998,7 @@ macro_rules! enum_define {
             type Output = dyn $slice;
         
             fn index(&self, index: $enum_name) -> &Self::Output {
-                let idx = index.index() - 1;
+                let idx = index.index() $(- $crate::for_null!($null, 1))?;
                 // use index.inner_id() to grab modified versions of blocks
                 // such as rotated or reflected blocks
                 self.0[idx]
@@ -254,13 +1008,112 @@ macro_rules! enum_define {
 }
 
 #[macro_export]
-macro_rules! from_id_inner {
-    ($enum_name:ident, $id:expr, $($block_name:ident : $block_type:ty),*) => {
-        if $id == 0 {
-            return $enum_name::Air(());
+macro_rules! tags_define {
+    ([], $($rest:tt)*) => {
+
+    };
+    (
+        [$vis:vis $tag_enum:ident { $($tag:ident),* }], $enum_name:ident, [$($null:ident)?],
+        $($block_name:ident [$($attrs:tt)*]),*
+    ) => {
+        $crate::paste::paste! {
+            #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+            $vis enum $tag_enum {
+                $([<$tag:camel>]),*
+            }
+
+            impl $crate::tags::BlockTag for $tag_enum {
+                const ALL: &'static [Self] = &[$($tag_enum::[<$tag:camel>]),*];
+
+                fn name(&self) -> &'static str {
+                    match self {
+                        $($tag_enum::[<$tag:camel>] => stringify!($tag)),*
+                    }
+                }
+
+                fn index(&self) -> usize {
+                    *self as usize
+                }
+
+                fn members(&self) -> $crate::tags::BlockSet {
+                    $crate::tags::BlockSet::from_words($tag_enum::MEMBERS[*self as usize])
+                }
+            }
+
+            impl $tag_enum {
+                /// The tags of every block, by block index
+                const BLOCK_TAGS: &'static [&'static [usize]] = &[
+                    $($crate::for_null!($null, &[]),)?
+                    $(&$crate::block_tags!($tag_enum; []; $($attrs)*)),*
+                ];
+                const WORDS: usize = $tag_enum::BLOCK_TAGS.len() / 64 + 1;
+                /// One bit per block index for every tag
+                const MEMBERS: &'static [&'static [u64]] = &[
+                    $(&$crate::tags::tag_words::<{ $tag_enum::WORDS }>(
+                        $tag_enum::[<$tag:camel>] as usize,
+                        $tag_enum::BLOCK_TAGS
+                    )),*
+                ];
+
+                /// Every block given this tag in the table
+                pub fn blocks(&self) -> impl Iterator<Item = $enum_name> {
+                    let words = $tag_enum::MEMBERS[*self as usize];
+                    (0..words.len() * 64)
+                        .filter(move |index| words[index / 64] >> (index % 64) & 1 == 1)
+                        .map($enum_name::from_index)
+                }
+            }
         }
+
+        impl $enum_name {
+            /// Whether this block was given a tag in the table
+            pub fn has_tag(&self, tag: $tag_enum) -> bool {
+                let index = self.index();
+                $tag_enum::MEMBERS[tag as usize][index / 64] >> (index % 64) & 1 == 1
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! block_tags {
+    ($tag_enum:ident; [$($tags:tt)*];) => {
+        $crate::paste::paste!([$($tags)*])
+    };
+    ($tag_enum:ident; [$($tags:tt)*]; [tags($($tag:ident),* $(,)?)] $($rest:tt)*) => {
+        $crate::block_tags!($tag_enum; [$($tags)* $($tag_enum::[<$tag:camel>] as usize,)*]; $($rest)*)
+    };
+    ($tag_enum:ident; $tags:tt; [$attr:ident $($args:tt)*] $($rest:tt)*) => {{
+        $crate::entry_attr!($attr);
+        $crate::block_tags!($tag_enum; $tags; $($rest)*)
+    }};
+}
+
+/// Checks the name of an attribute on a table entry, failing on any that no
+/// part of the table reads.  Doc comments and `cfg` are let through.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! entry_attr {
+    (doc) => {};
+    (cfg) => {};
+    (tags) => {};
+    (item) => {};
+    (behavior) => {};
+    ($other:ident) => {
+        compile_error!(concat!("unknown attribute `#[", stringify!($other), "]` on a table entry"));
+    };
+}
+
+#[macro_export]
+macro_rules! from_id_inner {
+    ($enum_name:ident, [$($null:ident)?], $id:expr, $($block_name:ident : $block_type:ty),*) => {
+        $(
+            if $id == 0 {
+                return $enum_name::$null(());
+            }
+        )?
         
-        let mut offset = 1;
+        let mut offset = 0 $(+ $crate::for_null!($null, 1))?;
 
         $(
             let size = <<$block_type as $crate::HasBuildVariants>::Variants as $crate::AsId>::get_id_span();
@@ -279,12 +1132,13 @@ macro_rules! from_id_inner {
 
 #[macro_export]
 macro_rules! to_id_inner {
-    ($enum_name:ident, $self:ident, $($block_name:ident : $block_type:ty),*) => {
-        let mut offset = 0;
-        if let $enum_name::Air(()) = $self {
-            return offset;
-        }
-        offset += 1;
+    ($enum_name:ident, [$($null:ident)?], $self:ident, $($block_name:ident : $block_type:ty),*) => {
+        $(
+            if let $enum_name::$null(()) = $self {
+                return 0;
+            }
+        )?
+        let mut offset = 0 $(+ $crate::for_null!($null, 1))?;
         $(
             if let $enum_name::$block_name(inner) = $self {
                 return offset + inner.to_id();
@@ -295,6 +1149,15 @@ macro_rules! to_id_inner {
     };
 }
 
+/// Expands to its tokens, used to write them once
+/// when a table's enum has a null variant
+#[macro_export]
+macro_rules! for_null {
+    ($null:ident, $($tokens:tt)*) => {
+        $($tokens)*
+    };
+}
+
 #[macro_export]
 macro_rules! get_id_span_inner {
     ($first_type:ty, $($rest_type:ty),+) => {
@@ -0,0 +1,282 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{blocks::{block::FaceDir, block_types::BlockType}, chunk::{chunk_pos, local_pos, offset, world_pos, BlockPos, Chunk, ChunkGrid, ChunkPos, CHUNK_VOLUME}, table::Table};
+
+/// The brightest a block can be lit
+pub const MAX_LIGHT: u8 = 15;
+
+/// The light given off and absorbed by every block state, indexed by id.
+///
+/// Built once from a [`Table`] so propagating light
+/// never has to look up blocks through the table.
+pub struct LightTable {
+    emission: Vec<u8>,
+    opacity: Vec<u8>,
+}
+
+impl LightTable {
+    pub fn new<T: 'static + ?Sized + BlockType>(table: &Table<T>) -> LightTable {
+        // air gives off no light and lets all light through
+        let mut emission = vec![0; table.id_span()];
+        let mut opacity = vec![0; table.id_span()];
+        for block in table {
+            for state in 0..block.id_span() {
+                emission[block.id() + state] = block.light_emission(state);
+                opacity[block.id() + state] = block.light_opacity(state);
+            }
+        }
+        LightTable { emission, opacity }
+    }
+
+    pub fn emission(&self, id: usize) -> u8 {
+        self.emission.get(id).copied().unwrap_or(0)
+    }
+
+    pub fn opacity(&self, id: usize) -> u8 {
+        self.opacity.get(id).copied().unwrap_or(0)
+    }
+}
+
+/// The two kinds of light, which are stored and propagated separately
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LightChannel {
+    /// Light given off by blocks
+    Block,
+    /// Light coming from the sky, which travels straight down without dimming
+    Sky,
+}
+
+/// The block and sky light of every block in a chunk
+#[derive(Clone)]
+pub struct LightChunk {
+    block: Box<[u8]>,
+    sky: Box<[u8]>,
+}
+
+impl LightChunk {
+    fn new() -> LightChunk {
+        LightChunk {
+            block: vec![0; CHUNK_VOLUME].into_boxed_slice(),
+            sky: vec![0; CHUNK_VOLUME].into_boxed_slice(),
+        }
+    }
+
+    /// The light of a block, relative to the chunk
+    pub fn get(&self, channel: LightChannel, local: [usize; 3]) -> u8 {
+        self.channel(channel)[Chunk::index(local)]
+    }
+
+    fn channel(&self, channel: LightChannel) -> &[u8] {
+        match channel {
+            LightChannel::Block => &self.block,
+            LightChannel::Sky => &self.sky,
+        }
+    }
+
+    fn channel_mut(&mut self, channel: LightChannel) -> &mut [u8] {
+        match channel {
+            LightChannel::Block => &mut self.block,
+            LightChannel::Sky => &mut self.sky,
+        }
+    }
+}
+
+/// Block and sky light for every loaded chunk of a [`ChunkGrid`].
+///
+/// Light is computed once with [`Lighting::compute`], then kept up to date
+/// with [`Lighting::update`] every time a block changes.  Sky light enters
+/// the grid from above any chunk that doesn't have a loaded chunk on top of it.
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}, properties::BlockProperties}, chunk::{Chunk, ChunkGrid}, lighting::{LightTable, Lighting}, AsId};
+///
+/// table!(BlockType, enum BlockId, static BLOCKS = {
+///     let Stone: Basic = Block::new_basic("stone");
+///     let Lamp: Basic = Block::new_basic("lamp")
+///         .with_properties(BlockProperties::DEFAULT.with_light_emission(14));
+/// });
+///
+/// let air = BlockId::Air(()).to_id();
+/// let mut grid = ChunkGrid::new(air);
+/// // a cave with a stone roof, so no sky light gets in
+/// grid.insert_chunk([0, 0, 0], Chunk::filled(air));
+/// grid.insert_chunk([0, 1, 0], Chunk::filled(BlockId::Stone(()).to_id()));
+///
+/// let mut lighting = Lighting::new(LightTable::new(&BLOCKS));
+/// lighting.compute(&grid);
+/// assert_eq!(lighting.block_light([4, 4, 4]), 0);
+///
+/// grid.set_id([4, 4, 4], &BlockId::Lamp(()));
+/// lighting.update(&grid, [4, 4, 4]);
+/// assert_eq!(lighting.block_light([4, 4, 4]), 14);
+/// assert_eq!(lighting.block_light([6, 4, 4]), 12);
+///
+/// grid.set_id([4, 4, 4], &BlockId::Air(()));
+/// lighting.update(&grid, [4, 4, 4]);
+/// assert_eq!(lighting.block_light([6, 4, 4]), 0);
+/// ```
+pub struct Lighting {
+    table: LightTable,
+    chunks: HashMap<ChunkPos, LightChunk>,
+}
+
+impl Lighting {
+    pub fn new(table: LightTable) -> Lighting {
+        Lighting {
+            table,
+            chunks: HashMap::new(),
+        }
+    }
+
+    pub fn table(&self) -> &LightTable {
+        &self.table
+    }
+
+    /// The light of a chunk, if it has been computed
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&LightChunk> {
+        self.chunks.get(&pos)
+    }
+
+    /// The light of a block, or 0 if its chunk hasn't been lit
+    pub fn light(&self, channel: LightChannel, pos: BlockPos) -> u8 {
+        self.get(channel, pos).unwrap_or(0)
+    }
+
+    pub fn block_light(&self, pos: BlockPos) -> u8 {
+        self.light(LightChannel::Block, pos)
+    }
+
+    pub fn sky_light(&self, pos: BlockPos) -> u8 {
+        self.light(LightChannel::Sky, pos)
+    }
+
+    /// Lights every loaded chunk of the grid from scratch,
+    /// forgetting light for chunks that are no longer loaded.
+    pub fn compute(&mut self, grid: &ChunkGrid) {
+        self.chunks = grid.chunks().map(|(pos, _)| (pos, LightChunk::new())).collect();
+        let positions: Vec<ChunkPos> = self.chunks.keys().copied().collect();
+        for channel in [LightChannel::Block, LightChannel::Sky] {
+            let mut queue = VecDeque::new();
+            for chunk in &positions {
+                for index in 0..CHUNK_VOLUME {
+                    let pos = world_pos(*chunk, Chunk::position(index));
+                    if let Some(source) = self.source(grid, channel, pos) {
+                        self.set(channel, pos, source);
+                        queue.push_back(pos);
+                    }
+                }
+            }
+            self.propagate(grid, channel, queue);
+        }
+    }
+
+    /// Updates light after the block at `pos` changed.
+    ///
+    /// If the block is in a chunk that hasn't been lit yet,
+    /// the whole grid is lit again.
+    pub fn update(&mut self, grid: &ChunkGrid, pos: BlockPos) {
+        if !self.chunks.contains_key(&chunk_pos(pos)) {
+            self.compute(grid);
+            return;
+        }
+        for channel in [LightChannel::Block, LightChannel::Sky] {
+            let old = self.light(channel, pos);
+            self.set(channel, pos, 0);
+            let mut removal = VecDeque::from([(pos, old)]);
+            let mut relight = VecDeque::new();
+            // darken everything that was lit through this block, remembering
+            // the brighter light around it that needs to spread back in
+            while let Some((current, level)) = removal.pop_front() {
+                if let Some(source) = self.source(grid, channel, current) {
+                    self.set(channel, current, source);
+                    relight.push_back(current);
+                }
+                for face in FaceDir::ALL {
+                    let next = offset(current, face);
+                    let Some(next_level) = self.get(channel, next) else {
+                        continue;
+                    };
+                    if next_level == 0 {
+                        continue;
+                    }
+                    let fed_by_sky = channel == LightChannel::Sky
+                        && face == FaceDir::Down
+                        && level == MAX_LIGHT
+                        && next_level == MAX_LIGHT;
+                    if next_level < level || fed_by_sky {
+                        self.set(channel, next, 0);
+                        removal.push_back((next, next_level));
+                    } else {
+                        relight.push_back(next);
+                    }
+                }
+            }
+            self.propagate(grid, channel, relight);
+        }
+    }
+
+    /// The light a block gives itself, before any light spreads to it
+    fn source(&self, grid: &ChunkGrid, channel: LightChannel, pos: BlockPos) -> Option<u8> {
+        let id = grid.get(pos);
+        match channel {
+            LightChannel::Block => {
+                let emission = self.table.emission(id);
+                (emission > 0).then_some(emission)
+            }
+            LightChannel::Sky => {
+                let above = offset(pos, FaceDir::Up);
+                if grid.is_loaded(above) {
+                    return None;
+                }
+                let level = self.spread(channel, MAX_LIGHT, FaceDir::Down, id);
+                (level > 0).then_some(level)
+            }
+        }
+    }
+
+    /// The light reaching a block with id `id` when light
+    /// of level `level` travels into it towards `face`
+    fn spread(&self, channel: LightChannel, level: u8, face: FaceDir, id: usize) -> u8 {
+        let opacity = self.table.opacity(id);
+        if channel == LightChannel::Sky && face == FaceDir::Down && level == MAX_LIGHT && opacity == 0 {
+            return MAX_LIGHT;
+        }
+        level.saturating_sub(opacity.max(1))
+    }
+
+    /// Spreads light outwards from every queued block
+    fn propagate(&mut self, grid: &ChunkGrid, channel: LightChannel, mut queue: VecDeque<BlockPos>) {
+        while let Some(current) = queue.pop_front() {
+            let level = self.light(channel, current);
+            if level <= 1 {
+                continue;
+            }
+            for face in FaceDir::ALL {
+                let next = offset(current, face);
+                let Some(next_level) = self.get(channel, next) else {
+                    continue;
+                };
+                let spread = self.spread(channel, level, face, grid.get(next));
+                if spread > next_level {
+                    self.set(channel, next, spread);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    fn get(&self, channel: LightChannel, pos: BlockPos) -> Option<u8> {
+        let chunk = self.chunks.get(&chunk_pos(pos))?;
+        Some(chunk.get(channel, local_pos(pos)))
+    }
+
+    fn set(&mut self, channel: LightChannel, pos: BlockPos, level: u8) {
+        if let Some(chunk) = self.chunks.get_mut(&chunk_pos(pos)) {
+            chunk.channel_mut(channel)[Chunk::index(local_pos(pos))] = level;
+        }
+    }
+}
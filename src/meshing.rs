@@ -0,0 +1,762 @@
+use crate::{blocks::{block::{CullingFlag, FaceDir}, block_types::BlockType, geometry::Quad}, chunk::{world_pos, BlockPos, Chunk, ChunkGrid, ChunkPos, CHUNK_SIZE}, ctm::{CtmLayout, Neighbours}, model::Models, table::Table, variants::{position_hash, TextureVariants}};
+
+/// The length of each side of a chunk with its one block border
+pub const PADDED_SIZE: usize = CHUNK_SIZE + 2;
+/// The number of blocks in a chunk with its one block border
+pub const PADDED_VOLUME: usize = PADDED_SIZE * PADDED_SIZE * PADDED_SIZE;
+
+/// The ids of a chunk along with a one block border of its neighbours,
+/// which is everything needed to mesh the chunk.
+///
+/// Borders that aren't filled in have the air id the chunk was padded with.
+///
+/// ### Example
+///
+/// A table without a null variant, where empty space is water drawn by
+/// something else:
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, custom::Custom, BlockType}}, chunk::{Chunk, ChunkGrid}, meshing::{MeshTable, Mesher, PaddedChunk}, AsId};
+///
+/// table!(BlockType, enum BlockId(), static BLOCKS = {
+///     let Stone: Basic = Block::new_basic("stone");
+///     let Water: Custom = Block::new_custom("water");
+/// });
+///
+/// let water = BlockId::Water(()).to_id();
+/// let mesher = Mesher::new(MeshTable::new(&BLOCKS));
+///
+/// // stone in the corner of a chunk is surrounded by water, past the chunk's border too
+/// let mut chunk = Chunk::filled(water);
+/// chunk.set_id([0, 0, 0], &BlockId::Stone(()));
+/// assert!(!chunk.is_filled_with(water));
+/// assert_eq!(mesher.mesh(&PaddedChunk::new(&chunk, water)).quad_count(), 6);
+///
+/// let mut grid = ChunkGrid::new(water);
+/// grid.set_id([0, 0, 0], &BlockId::Stone(()));
+/// assert_eq!(grid.get_id::<BlockId>([-1, 0, 0]).to_string(), "Water");
+/// assert_eq!(mesher.mesh(&PaddedChunk::from_grid(&grid, [0, 0, 0])).quad_count(), 6);
+/// ```
+#[derive(Clone)]
+pub struct PaddedChunk {
+    ids: Vec<usize>,
+    /// The world position of the chunk's first block, which picks texture variants
+    origin: BlockPos,
+}
+
+impl PaddedChunk {
+    /// A chunk surrounded by blocks with the id `air`, usually the id of the
+    /// null variant of a table, placed at the origin of the world
+    pub fn new(chunk: &Chunk, air: usize) -> PaddedChunk {
+        let mut padded = PaddedChunk {
+            ids: vec![air; PADDED_VOLUME],
+            origin: [0; 3],
+        };
+        for (index, id) in chunk.ids().enumerate() {
+            let local = Chunk::position(index);
+            padded.set(local.map(|c| c as i32), id);
+        }
+        padded
+    }
+
+    /// A chunk of a grid along with the border of every chunk around it,
+    /// including the edges and corners of diagonal neighbours.
+    /// Chunks that aren't loaded are filled with the grid's air id.
+    pub fn from_grid(grid: &ChunkGrid, pos: ChunkPos) -> PaddedChunk {
+        let origin = world_pos(pos, [0; 3]);
+        let ids = (0..PADDED_VOLUME)
+            .map(|index| {
+                let local = Self::position(index);
+                grid.get([0, 1, 2].map(|axis| origin[axis] + local[axis]))
+            })
+            .collect();
+        PaddedChunk { ids, origin }
+    }
+
+    /// Places the chunk in the world, so texture variants
+    /// are picked by the world position of each block
+    pub fn with_position(mut self, pos: ChunkPos) -> Self {
+        self.origin = world_pos(pos, [0; 3]);
+        self
+    }
+
+    /// Copies the layer of a neighbouring chunk touching `face` of this chunk into the border
+    pub fn with_border(mut self, face: FaceDir, neighbour: &Chunk) -> Self {
+        let normal = face.normal();
+        let axis = normal.iter().position(|c| *c != 0).unwrap();
+        let (border, layer) = if normal[axis] > 0 {
+            (CHUNK_SIZE as i32, 0)
+        } else {
+            (-1, CHUNK_SIZE - 1)
+        };
+        for a in 0..CHUNK_SIZE {
+            for b in 0..CHUNK_SIZE {
+                let mut local = [0; 3];
+                local[axis] = layer;
+                local[(axis + 1) % 3] = a;
+                local[(axis + 2) % 3] = b;
+                let mut pos = local.map(|c| c as i32);
+                pos[axis] = border;
+                self.set(pos, neighbour.get(local));
+            }
+        }
+        self
+    }
+
+    /// The world position of a block relative to the chunk
+    pub fn world_pos(&self, local: [i32; 3]) -> BlockPos {
+        [0, 1, 2].map(|axis| self.origin[axis] + local[axis])
+    }
+
+    /// The id of a block relative to the chunk, from -1 to [`CHUNK_SIZE`] on each axis
+    pub fn get(&self, local: [i32; 3]) -> usize {
+        self.ids[Self::index(local)]
+    }
+
+    pub fn set(&mut self, local: [i32; 3], id: usize) {
+        self.ids[Self::index(local)] = id;
+    }
+
+    fn index(local: [i32; 3]) -> usize {
+        let [x, y, z] = local.map(|c| (c + 1) as usize);
+        x + z * PADDED_SIZE + y * PADDED_SIZE * PADDED_SIZE
+    }
+
+    fn position(index: usize) -> [i32; 3] {
+        [
+            (index % PADDED_SIZE) as i32 - 1,
+            (index / (PADDED_SIZE * PADDED_SIZE)) as i32 - 1,
+            ((index / PADDED_SIZE) % PADDED_SIZE) as i32 - 1,
+        ]
+    }
+}
+
+/// A vertex of a chunk mesh
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Vertex {
+    /// The vertex's position relative to the chunk
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    /// Texture coordinates, which go past 1 on merged faces
+    /// so textures repeat across them
+    pub uv: [f32; 2],
+    /// The layer of the texture array this vertex samples from
+    pub layer: u32,
+    /// The tile of a connected texture or the texture variant,
+    /// 0 for other textures
+    pub tile: u8,
+    /// Where the vertex's colour comes from, packed by [`TintSource::to_bits`](crate::colormap::TintSource::to_bits)
+    pub tint: u32,
+    /// How much ambient light reaches this vertex,
+    /// from 0 when fully occluded to 3 when nothing is in the way
+    pub ao: u8,
+}
+
+/// Triangles ready to be uploaded to the GPU, wound counter clockwise
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// The number of quads in the mesh
+    pub fn quad_count(&self) -> usize {
+        self.indices.len() / 6
+    }
+
+    /// Adds a quad, splitting it into triangles along the diagonal
+    /// that keeps ambient occlusion from smearing across the quad
+    fn push_quad(&mut self, quad: &Quad, offset: [f32; 3], layer: u32, tile: u8, tint: u32, ao: [u8; 4]) {
+        let start = self.vertices.len() as u32;
+        for (corner, ao) in ao.into_iter().enumerate() {
+            self.vertices.push(Vertex {
+                position: [0, 1, 2].map(|axis| quad.positions[corner][axis] + offset[axis]),
+                normal: quad.normal,
+                uv: quad.uvs[corner],
+                layer,
+                tile,
+                tint,
+                ao,
+            });
+        }
+        let triangles = if ao[1] + ao[3] > ao[0] + ao[2] {
+            [0, 1, 3, 1, 2, 3]
+        } else {
+            [0, 1, 2, 0, 2, 3]
+        };
+        self.indices.extend(triangles.map(|i| start + i));
+    }
+}
+
+/// How the mesher draws one block state
+struct MeshEntry {
+    /// Whether the state is drawn as a textured cube
+    cube: bool,
+    /// Whether the state is a solid cube, which casts ambient occlusion
+    occludes: bool,
+    /// Whether each face hides the faces of neighbours touching it
+    culls: [bool; 6],
+    /// Whether each face can be hidden by a neighbour
+    cullable: [bool; 6],
+    /// The texture array layer of each face
+    layers: [u32; 6],
+    /// The layout of each face's connected texture
+    connected: [Option<CtmLayout>; 6],
+    /// The texture variants of each face
+    variants: [Option<TextureVariants>; 6],
+    /// The packed tint of each face
+    tints: [u32; 6],
+    /// Whether each face of a cube is drawn from a model instead
+    modelled: [bool; 6],
+    /// Extra geometry with its texture array layer and packed tint
+    quads: Vec<(Quad, u32, u32)>,
+}
+
+impl MeshEntry {
+    const AIR: MeshEntry = MeshEntry {
+        cube: false,
+        occludes: false,
+        culls: [false; 6],
+        cullable: [false; 6],
+        layers: [0; 6],
+        connected: [None; 6],
+        variants: [None; 6],
+        tints: [0; 6],
+        modelled: [false; 6],
+        quads: vec![],
+    };
+}
+
+/// What a cube face looks like, faces looking the same can be merged
+#[derive(Clone, Copy, PartialEq)]
+struct FaceKey {
+    layer: u32,
+    tile: u8,
+    tint: u32,
+    /// Quarter turns of the face's texture
+    rotation: u8,
+    ao: [u8; 4],
+}
+
+/// Everything the mesher needs to know about every block state, indexed by id.
+///
+/// Built once from a [`Table`] so meshing never has to look up blocks through the table.
+pub struct MeshTable {
+    entries: Vec<MeshEntry>,
+}
+
+impl MeshTable {
+    /// A table that draws blocks without their models.
+    ///
+    /// Blocks drawn only from models, like custom blocks, are skipped,
+    /// and faces with a model are drawn as plain cube faces.
+    /// Use [`MeshTable::with_models`] to draw them.
+    pub fn new<T: 'static + ?Sized + BlockType>(table: &Table<T>) -> MeshTable {
+        MeshTable::with_models(table, &Models::default())
+    }
+
+    /// A table that draws blocks with their models, as loaded by [`Models::load`].
+    ///
+    /// A face model is drawn in place of its cube face, textured by the face's
+    /// texture and the model's own texture slots counted from there.  Models
+    /// that aren't loaded are skipped.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, custom::Custom, BlockType}}, chunk::Chunk, meshing::{MeshTable, Mesher, PaddedChunk}, model::Models, AsId};
+    ///
+    /// table!(BlockType, enum BlockId, static BLOCKS = {
+    ///     let Rock: Custom = Block::new_custom("rock")
+    ///         .with_model("rock.bbno$");
+    ///     let Panel: Basic = Block::new_basic("panel")
+    ///         .with_model("panel.bbno$");
+    /// });
+    ///
+    /// let root = std::env::temp_dir().join("opentale_blocks_mesh_models_example");
+    /// std::fs::create_dir_all(&root).unwrap();
+    /// std::fs::write(root.join("rock.bbno$"), "cube 4 0 4 12 6 12").unwrap();
+    /// // a face with a raised plate in the middle
+    /// std::fs::write(root.join("panel.bbno$"), "cube 0 0 0 16 16 0\nface north\ncube 4 4 -1 12 12 0").unwrap();
+    /// let models = Models::load(&BLOCKS, &root).unwrap();
+    ///
+    /// let air = BlockId::Air(()).to_id();
+    /// let mut chunk = Chunk::filled(air);
+    /// chunk.set_id([0, 0, 0], &BlockId::Rock(()));
+    ///
+    /// // without its model, a custom block isn't drawn at all
+    /// let mesh = Mesher::new(MeshTable::new(&BLOCKS)).mesh(&PaddedChunk::new(&chunk, air));
+    /// assert!(mesh.is_empty());
+    ///
+    /// let mesher = Mesher::new(MeshTable::with_models(&BLOCKS, &models));
+    /// assert_eq!(mesher.mesh(&PaddedChunk::new(&chunk, air)).quad_count(), 6);
+    ///
+    /// // every face of the panel is drawn from the model, turned to face it
+    /// chunk.set_id([0, 0, 0], &BlockId::Panel(()));
+    /// let mesh = mesher.mesh(&PaddedChunk::new(&chunk, air));
+    /// assert_eq!(mesh.quad_count(), 6 * 7);
+    /// assert!(mesh.vertices.iter().any(|vertex| vertex.position[1] < 0.0));
+    /// ```
+    pub fn with_models<T: 'static + ?Sized + BlockType>(table: &Table<T>, models: &Models) -> MeshTable {
+        let mut entries: Vec<MeshEntry> = (0..table.id_span()).map(|_| MeshEntry::AIR).collect();
+        for block in table {
+            for state in 0..block.id_span() {
+                let flags = FaceDir::ALL.map(|face| block.culling(state, face));
+                let cube = block.is_cube(state);
+                let mut modelled = [false; 6];
+                let mut quads = vec![];
+                if !cube {
+                    quads.extend(block.quads(state).unwrap_or_default());
+                }
+                for (face, path, transform) in block.state_models(state) {
+                    let Some(model) = models.get(path) else {
+                        continue;
+                    };
+                    // face models count their texture slots from the texture of their face
+                    let first_slot = match face {
+                        Some(face) => {
+                            modelled[face.to_index()] = true;
+                            block.texture_slot(state, face)
+                        }
+                        None => 0,
+                    };
+                    quads.extend(model.quads().into_iter().map(|mut quad| {
+                        quad.texture += first_slot;
+                        quad.transformed(transform)
+                    }));
+                }
+                entries[block.id() + state] = MeshEntry {
+                    cube,
+                    occludes: flags.iter().all(|flag| matches!(flag, CullingFlag::Both)),
+                    culls: flags.each_ref().map(|flag| matches!(flag, CullingFlag::Cullable | CullingFlag::Both)),
+                    cullable: flags.each_ref().map(|flag| matches!(flag, CullingFlag::Culling | CullingFlag::Both)),
+                    layers: FaceDir::ALL.map(|face| (block.index() + block.texture_slot(state, face)) as u32),
+                    connected: FaceDir::ALL.map(|face| block.connected_texture(block.texture_slot(state, face))),
+                    variants: FaceDir::ALL.map(|face| block.texture_variants(block.texture_slot(state, face)).copied()),
+                    tints: FaceDir::ALL.map(|face| block.tint_source(block.texture_slot(state, face)).to_bits()),
+                    modelled: if cube { modelled } else { [false; 6] },
+                    quads: quads
+                        .into_iter()
+                        .map(|quad| {
+                            let layer = (block.index() + quad.texture) as u32;
+                            let tint = block.tint_source(quad.texture).to_bits();
+                            (quad, layer, tint)
+                        })
+                        .collect(),
+                };
+            }
+        }
+        MeshTable { entries }
+    }
+
+    fn entry(&self, id: usize) -> &MeshEntry {
+        static AIR: MeshEntry = MeshEntry::AIR;
+        // ids past the end of the table are drawn as air
+        self.entries.get(id).unwrap_or(&AIR)
+    }
+}
+
+/// Builds meshes for chunks, culling hidden faces and merging
+/// neighbouring cube faces that share a texture into larger quads.
+///
+/// Meshing only depends on the ids it's given, so the same chunk
+/// always produces the same mesh.
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, chunk::Chunk, meshing::{MeshTable, Mesher, PaddedChunk}, AsId};
+///
+/// table!(BlockType, enum BlockId, static BLOCKS = {
+///     let Stone: Basic = Block::new_basic("stone");
+///     let Dirt: Basic = Block::new_basic("dirt");
+/// });
+///
+/// let mesher = Mesher::new(MeshTable::new(&BLOCKS));
+/// let air = BlockId::Air(()).to_id();
+/// let mut chunk = Chunk::filled(air);
+///
+/// // a lone block has all six of its faces
+/// chunk.set_id([0, 0, 0], &BlockId::Stone(()));
+/// let mesh = mesher.mesh(&PaddedChunk::new(&chunk, air));
+/// assert_eq!(mesh.quad_count(), 6);
+/// assert_eq!(mesh.vertices.len(), 24);
+///
+/// // a row of blocks is merged into one long box
+/// chunk.set_id([1, 0, 0], &BlockId::Stone(()));
+/// chunk.set_id([2, 0, 0], &BlockId::Stone(()));
+/// assert_eq!(mesher.mesh(&PaddedChunk::new(&chunk, air)).quad_count(), 6);
+///
+/// // but faces with different textures aren't merged
+/// chunk.set_id([1, 0, 0], &BlockId::Dirt(()));
+/// assert_eq!(mesher.mesh(&PaddedChunk::new(&chunk, air)).quad_count(), 14);
+/// ```
+pub struct Mesher {
+    table: MeshTable,
+    ambient_occlusion: bool,
+}
+
+impl Mesher {
+    pub fn new(table: MeshTable) -> Mesher {
+        Mesher {
+            table,
+            ambient_occlusion: false,
+        }
+    }
+
+    /// Darkens the corners of faces next to solid cubes.
+    ///
+    /// Each vertex is occluded by the blocks touching it in front of its face:
+    /// the two along the face's edges and the one across its corner.
+    /// Faces are only merged with neighbours that have the same occlusion.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, chunk::Chunk, meshing::{MeshTable, Mesher, PaddedChunk}, AsId};
+    ///
+    /// table!(BlockType, enum BlockId, static BLOCKS = {
+    ///     let Stone: Basic = Block::new_basic("stone");
+    /// });
+    ///
+    /// let mesher = Mesher::new(MeshTable::new(&BLOCKS)).with_ambient_occlusion(true);
+    ///
+    /// // a 3x3 floor with one block on top of its corner
+    /// let air = BlockId::Air(()).to_id();
+    /// let mut chunk = Chunk::filled(air);
+    /// for x in 0..3 {
+    ///     for z in 0..3 {
+    ///         chunk.set_id([x, 0, z], &BlockId::Stone(()));
+    ///     }
+    /// }
+    /// chunk.set_id([0, 1, 0], &BlockId::Stone(()));
+    /// let mesh = mesher.mesh(&PaddedChunk::new(&chunk, air));
+    ///
+    /// let mut floor: Vec<([f32; 3], u8)> = mesh.vertices.iter()
+    ///     .filter(|vertex| vertex.normal == [0.0, 1.0, 0.0] && vertex.position[1] == 1.0)
+    ///     .map(|vertex| (vertex.position, vertex.ao))
+    ///     .collect();
+    /// floor.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// floor.dedup();
+    /// assert_eq!(floor, [
+    ///     ([0.0, 1.0, 1.0], 2), ([0.0, 1.0, 2.0], 3), ([0.0, 1.0, 3.0], 3),
+    ///     ([1.0, 1.0, 0.0], 2), ([1.0, 1.0, 1.0], 2), ([1.0, 1.0, 2.0], 3),
+    ///     ([2.0, 1.0, 0.0], 3), ([2.0, 1.0, 1.0], 3), ([2.0, 1.0, 2.0], 3),
+    ///     ([3.0, 1.0, 0.0], 3), ([3.0, 1.0, 2.0], 3), ([3.0, 1.0, 3.0], 3),
+    /// ]);
+    ///
+    /// // a quad with one darkened corner is split so only one triangle touches it
+    /// for quad in mesh.indices.chunks(6) {
+    ///     let mut dark: Vec<u32> = quad.iter().copied()
+    ///         .filter(|i| mesh.vertices[*i as usize].ao < 3)
+    ///         .collect();
+    ///     let uses = dark.len();
+    ///     dark.dedup();
+    ///     if dark.len() == 1 {
+    ///         assert_eq!(uses, 1);
+    ///     }
+    /// }
+    /// ```
+    pub fn with_ambient_occlusion(mut self, enabled: bool) -> Self {
+        self.ambient_occlusion = enabled;
+        self
+    }
+
+    pub fn table(&self) -> &MeshTable {
+        &self.table
+    }
+
+    pub fn mesh(&self, chunk: &PaddedChunk) -> Mesh {
+        let mut mesh = Mesh::default();
+        for face in FaceDir::ALL {
+            self.mesh_cube_faces(chunk, face, &mut mesh);
+        }
+        self.mesh_quads(chunk, &mut mesh);
+        mesh
+    }
+
+    /// Whether a face of the block at `local` is hidden by its neighbour
+    fn is_hidden(&self, chunk: &PaddedChunk, local: [i32; 3], face: FaceDir) -> bool {
+        if !self.table.entry(chunk.get(local)).cullable[face.to_index()] {
+            return false;
+        }
+        let normal = face.normal();
+        let neighbour = [0, 1, 2].map(|axis| local[axis] + normal[axis]);
+        self.table.entry(chunk.get(neighbour)).culls[face.opposite().to_index()]
+    }
+
+    /// The connected texture tile or texture variant of a face of the block at `local`,
+    /// along with the quarter turns to rotate its texture by.
+    ///
+    /// Neighbours in the plane of the face connect when
+    /// they're cubes with the same texture on that face.
+    /// Connected textures ignore variants.
+    fn face_tile(&self, chunk: &PaddedChunk, local: [i32; 3], face: FaceDir) -> (u8, u8) {
+        let entry = self.table.entry(chunk.get(local));
+        let Some(layout) = entry.connected[face.to_index()] else {
+            return match entry.variants[face.to_index()] {
+                Some(variants) => {
+                    let hash = position_hash(chunk.world_pos(local));
+                    (variants.pick(hash) as u8, variants.rotation(hash))
+                }
+                None => (0, 0),
+            };
+        };
+        let layer = entry.layers[face.to_index()];
+        let neighbours = Neighbours::new(face, |offset| {
+            let neighbour = self.table.entry(chunk.get([0, 1, 2].map(|axis| local[axis] + offset[axis])));
+            neighbour.cube && neighbour.layers[face.to_index()] == layer
+        });
+        (layout.tile(neighbours) as u8, 0)
+    }
+
+    /// The ambient occlusion of each corner of a face of the block at `local`,
+    /// indexed by whether the corner is on the far side of the face's two other axes
+    fn face_ao(&self, chunk: &PaddedChunk, local: [i32; 3], face: FaceDir) -> [u8; 4] {
+        if !self.ambient_occlusion {
+            return [3; 4];
+        }
+        let normal = face.normal();
+        let axis = normal.iter().position(|c| *c != 0).unwrap();
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        let occludes = |du: i32, dv: i32| {
+            let mut pos = [0, 1, 2].map(|i| local[i] + normal[i]);
+            pos[u] += du;
+            pos[v] += dv;
+            self.table.entry(chunk.get(pos)).occludes as u8
+        };
+        [(-1, -1), (1, -1), (-1, 1), (1, 1)].map(|(du, dv)| {
+            let (side_u, side_v, corner) = (occludes(du, 0), occludes(0, dv), occludes(du, dv));
+            if side_u == 1 && side_v == 1 {
+                0
+            } else {
+                3 - side_u - side_v - corner
+            }
+        })
+    }
+
+    /// Picks the occlusion of each vertex of a quad from the occlusion of its face's corners
+    fn vertex_ao(quad: &Quad, face: FaceDir, center: [f32; 3], face_ao: [u8; 4]) -> [u8; 4] {
+        let axis = face.normal().iter().position(|c| *c != 0).unwrap();
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        quad.positions.map(|position| {
+            let far_u = (position[u] > center[u]) as usize;
+            let far_v = (position[v] > center[v]) as usize;
+            face_ao[far_u + 2 * far_v]
+        })
+    }
+
+    /// Greedily merges the visible cube faces pointing towards `face`, one slice at a time
+    fn mesh_cube_faces(&self, chunk: &PaddedChunk, face: FaceDir, mesh: &mut Mesh) {
+        let axis = face.normal().iter().position(|c| *c != 0).unwrap();
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        let local = |slice: usize, a: usize, b: usize| {
+            let mut local = [0; 3];
+            local[axis] = slice as i32;
+            local[u] = a as i32;
+            local[v] = b as i32;
+            local
+        };
+        // faces are only merged when their texture, tile, rotation, and occlusion all match,
+        // and their texture isn't turned
+        let mut mask: Vec<Option<FaceKey>> = vec![None; CHUNK_SIZE * CHUNK_SIZE];
+        for slice in 0..CHUNK_SIZE {
+            for b in 0..CHUNK_SIZE {
+                for a in 0..CHUNK_SIZE {
+                    let pos = local(slice, a, b);
+                    let entry = self.table.entry(chunk.get(pos));
+                    let drawn = entry.cube && !entry.modelled[face.to_index()];
+                    mask[a + b * CHUNK_SIZE] = (drawn && !self.is_hidden(chunk, pos, face))
+                        .then(|| {
+                            let (tile, rotation) = self.face_tile(chunk, pos, face);
+                            FaceKey {
+                                layer: entry.layers[face.to_index()],
+                                tile,
+                                tint: entry.tints[face.to_index()],
+                                rotation,
+                                ao: self.face_ao(chunk, pos, face),
+                            }
+                        });
+                }
+            }
+            for b in 0..CHUNK_SIZE {
+                let mut a = 0;
+                while a < CHUNK_SIZE {
+                    let Some(key) = mask[a + b * CHUNK_SIZE] else {
+                        a += 1;
+                        continue;
+                    };
+                    // a turned texture would be stretched across a merged face,
+                    // so turned faces are drawn one block at a time
+                    let (width, height) = if key.rotation != 0 {
+                        (1, 1)
+                    } else {
+                        let width = (a..CHUNK_SIZE)
+                            .take_while(|a| mask[a + b * CHUNK_SIZE] == Some(key))
+                            .count();
+                        let height = (b..CHUNK_SIZE)
+                            .take_while(|b| (a..a + width).all(|a| mask[a + b * CHUNK_SIZE] == Some(key)))
+                            .count();
+                        (width, height)
+                    };
+                    for row in b..b + height {
+                        mask[a + row * CHUNK_SIZE..a + width + row * CHUNK_SIZE].fill(None);
+                    }
+                    let FaceKey { layer, tile, tint, rotation, ao: face_ao } = key;
+                    let min = local(slice, a, b).map(|c| c as f32);
+                    let max = local(slice + 1, a + width, b + height).map(|c| c as f32);
+                    let mut quad = Quad::cuboid_face(face, min, max, 0);
+                    quad.uvs.rotate_left(rotation as usize);
+                    let ao = Self::vertex_ao(&quad, face, min, face_ao);
+                    mesh.push_quad(&quad, [0.0; 3], layer, tile, tint, ao);
+                    a += width;
+                }
+            }
+        }
+    }
+
+    /// Adds the geometry of blocks that aren't cubes, and of models
+    fn mesh_quads(&self, chunk: &PaddedChunk, mesh: &mut Mesh) {
+        for index in 0..CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE {
+            let pos = Chunk::position(index).map(|c| c as i32);
+            let entry = self.table.entry(chunk.get(pos));
+            for (quad, layer, tint) in &entry.quads {
+                // only quads on the edge of the block are occluded by its neighbours
+                let ao = match quad.cull_face {
+                    Some(face) if self.is_hidden(chunk, pos, face) => continue,
+                    Some(face) => Self::vertex_ao(quad, face, [0.5; 3], self.face_ao(chunk, pos, face)),
+                    None => [3; 4],
+                };
+                mesh.push_quad(quad, pos.map(|c| c as f32), *layer, 0, *tint, ao);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+// lints look inside `table!` when it's used in this crate, and
+// the entries are only used through their ids
+#[allow(dead_code, unused_parens, unused_assignments)]
+mod tests {
+    use crate::{blocks::{block::{Block, FaceDir}, block_types::{basic::Basic, BlockType}}, chunk::Chunk, table, AsId};
+
+    use super::{Mesh, MeshTable, Mesher, PaddedChunk};
+
+    table!(BlockType, enum BlockId, static BLOCKS = {
+        let Stone: Basic = Block::new_basic("stone");
+    });
+
+    /// Meshes stone at every position, with or without ambient occlusion
+    fn mesh_with(blocks: &[[usize; 3]], ambient_occlusion: bool) -> Mesh {
+        let air = BlockId::Air(()).to_id();
+        let mut chunk = Chunk::filled(air);
+        for pos in blocks {
+            chunk.set_id(*pos, &BlockId::Stone(()));
+        }
+        Mesher::new(MeshTable::new(&BLOCKS))
+            .with_ambient_occlusion(ambient_occlusion)
+            .mesh(&PaddedChunk::new(&chunk, air))
+    }
+
+    fn mesh(blocks: &[[usize; 3]]) -> Mesh {
+        mesh_with(blocks, true)
+    }
+
+    /// The corners of a quad with their occlusion, in the order they
+    /// were added, and the order its triangles use them in
+    type QuadCorners = ([([f32; 3], u8); 4], [u32; 6]);
+
+    /// Every quad facing `face`
+    fn quads(mesh: &Mesh, face: FaceDir) -> Vec<QuadCorners> {
+        let normal = face.normal().map(|c| c as f32);
+        mesh.indices
+            .chunks(6)
+            .filter(|indices| mesh.vertices[indices[0] as usize].normal == normal)
+            .map(|indices| {
+                let start = *indices.iter().min().unwrap();
+                let corners = [0, 1, 2, 3].map(|i| {
+                    let vertex = &mesh.vertices[(start + i) as usize];
+                    (vertex.position, vertex.ao)
+                });
+                (corners, [0, 1, 2, 3, 4, 5].map(|i| indices[i] - start))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn corner() {
+        // a block with walls on two sides of its top
+        let mesh = mesh(&[[0, 0, 0], [1, 1, 0], [0, 1, 1]]);
+        assert_eq!(quads(&mesh, FaceDir::Up)[0], (
+            [([0.0, 1.0, 1.0], 2), ([1.0, 1.0, 1.0], 0), ([1.0, 1.0, 0.0], 2), ([0.0, 1.0, 0.0], 3)],
+            [0, 1, 2, 0, 2, 3],
+        ));
+    }
+
+    #[test]
+    fn step() {
+        // a block with another on top of the block next to it
+        let mesh = mesh(&[[0, 0, 0], [1, 0, 0], [1, 1, 0]]);
+        assert_eq!(quads(&mesh, FaceDir::Up), [
+            (
+                [([0.0, 1.0, 1.0], 3), ([1.0, 1.0, 1.0], 2), ([1.0, 1.0, 0.0], 2), ([0.0, 1.0, 0.0], 3)],
+                [0, 1, 2, 0, 2, 3],
+            ),
+            (
+                [([1.0, 2.0, 1.0], 3), ([2.0, 2.0, 1.0], 3), ([2.0, 2.0, 0.0], 3), ([1.0, 2.0, 0.0], 3)],
+                [0, 1, 2, 0, 2, 3],
+            ),
+        ]);
+        // the riser is darkened along the floor in front of it
+        assert_eq!(quads(&mesh, FaceDir::West)[1], (
+            [([1.0, 1.0, 0.0], 2), ([1.0, 1.0, 1.0], 2), ([1.0, 2.0, 1.0], 3), ([1.0, 2.0, 0.0], 3)],
+            [0, 1, 2, 0, 2, 3],
+        ));
+    }
+
+    #[test]
+    fn occlusion_splits_merged_faces() {
+        // a row of four blocks with one on top of the last
+        let blocks = [[0, 0, 0], [1, 0, 0], [2, 0, 0], [3, 0, 0], [3, 1, 0]];
+        assert_eq!(quads(&mesh_with(&blocks, false), FaceDir::Up).len(), 2);
+        assert_eq!(quads(&mesh(&blocks), FaceDir::Up), [
+            (
+                [([0.0, 1.0, 1.0], 3), ([2.0, 1.0, 1.0], 3), ([2.0, 1.0, 0.0], 3), ([0.0, 1.0, 0.0], 3)],
+                [0, 1, 2, 0, 2, 3],
+            ),
+            (
+                [([2.0, 1.0, 1.0], 3), ([3.0, 1.0, 1.0], 2), ([3.0, 1.0, 0.0], 2), ([2.0, 1.0, 0.0], 3)],
+                [0, 1, 2, 0, 2, 3],
+            ),
+            (
+                [([3.0, 2.0, 1.0], 3), ([4.0, 2.0, 1.0], 3), ([4.0, 2.0, 0.0], 3), ([3.0, 2.0, 0.0], 3)],
+                [0, 1, 2, 0, 2, 3],
+            ),
+        ]);
+    }
+
+    #[test]
+    fn flips_quads_darkened_on_their_first_diagonal() {
+        // like the corner, but darkening the quad's first corner, so the
+        // quad is split along the other diagonal to keep the dark corner
+        // in one triangle
+        let mesh = mesh(&[[1, 0, 0], [0, 1, 0], [1, 1, 1]]);
+        assert_eq!(quads(&mesh, FaceDir::Up)[0], (
+            [([1.0, 1.0, 1.0], 0), ([2.0, 1.0, 1.0], 2), ([2.0, 1.0, 0.0], 3), ([1.0, 1.0, 0.0], 2)],
+            [0, 1, 3, 1, 2, 3],
+        ));
+    }
+}
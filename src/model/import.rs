@@ -0,0 +1,430 @@
+//! Importing models made in Blockbench, from `.bbmodel` projects
+//! or from glTF files, into [`Model`]s.
+//!
+//! Blockbench's Java block models use the same coordinates as `.bbno$`
+//! files.  Other Blockbench formats and glTF files are centered on the block
+//! horizontally, with their base at the bottom of the block, as Blockbench
+//! exports them.  glTF files use one unit per block.
+//!
+//! Imported models can be written out with [`Model`]'s `Display` impl, and
+//! split with [`Model::split`] into the face models and custom model of a
+//! [`FullCustom`](crate::blocks::block_types::full_custom::FullCustom) block.
+
+use std::{fmt, io, path::Path};
+
+use base64::Engine;
+use serde_json::Value;
+
+use crate::{blocks::block::FaceDir, model::{face_name, Axis, Cube, CubeFace, Cull, Element, Model, ModelQuad, Rotation}};
+
+/// Imports a `.bbmodel`, `.gltf` or `.glb` file, depending on its extension
+pub fn load(path: impl AsRef<Path>) -> Result<Model, ImportError> {
+    let path = path.as_ref();
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("bbmodel") => from_bbmodel(&std::fs::read_to_string(path).map_err(ImportError::Io)?),
+        Some("gltf" | "glb") => from_gltf(&std::fs::read(path).map_err(ImportError::Io)?, path.parent()),
+        _ => Err(ImportError::UnknownFormat),
+    }
+}
+
+/// Imports the cubes and meshes of a Blockbench project.
+///
+/// Faces without a texture are left out, and the texture slot of every
+/// other face is the position of its texture in the project.
+///
+/// ### Example
+///
+/// ```rust
+/// # use opentale_blocks::{blocks::block::FaceDir, model::import};
+///
+/// let project = r#"{
+///     "meta": { "model_format": "java_block" },
+///     "resolution": { "width": 16, "height": 16 },
+///     "elements": [
+///         { "type": "cube", "from": [0, 0, 0], "to": [16, 16, 2], "faces": {
+///             "north": { "uv": [0, 0, 16, 16], "texture": 0 },
+///             "south": { "uv": [0, 0, 16, 16], "texture": 0 }
+///         } },
+///         { "type": "cube", "from": [6, 6, 2], "to": [10, 10, 6], "faces": {
+///             "south": { "uv": [6, 6, 10, 10], "texture": 1 }
+///         } }
+///     ]
+/// }"#;
+///
+/// let split = import::from_bbmodel(project).unwrap().split();
+/// assert_eq!(split.face(FaceDir::North).quads().len(), 1);
+/// assert_eq!(split.custom.quads().len(), 2);
+/// assert!(split.face(FaceDir::North).lies_on(FaceDir::North));
+/// ```
+pub fn from_bbmodel(json: &str) -> Result<Model, ImportError> {
+    let project: Value = serde_json::from_str(json).map_err(ImportError::Json)?;
+    let offset = match project["meta"]["model_format"].as_str() {
+        Some("java_block") => [0.0; 3],
+        _ => [8.0, 0.0, 8.0],
+    };
+    let resolution = [
+        project["resolution"]["width"].as_f64().unwrap_or(16.0) as f32,
+        project["resolution"]["height"].as_f64().unwrap_or(16.0) as f32,
+    ];
+    let textures: Vec<&str> = project["textures"].as_array()
+        .map(|textures| textures.iter().map(|texture| texture["uuid"].as_str().unwrap_or("")).collect())
+        .unwrap_or_default();
+    let importer = BbImporter { offset, resolution, textures };
+
+    let mut model = Model::default();
+    let elements = project["elements"].as_array()
+        .ok_or_else(|| ImportError::Invalid("the project has no `elements`".to_string()))?;
+    for (index, element) in elements.iter().enumerate() {
+        if element["export"] == Value::Bool(false) {
+            continue;
+        }
+        let invalid = |message: String| ImportError::Invalid(format!("element {index}: {message}"));
+        match element["type"].as_str().unwrap_or("cube") {
+            "cube" => importer.cube(element, &mut model).map_err(invalid)?,
+            "mesh" => importer.mesh(element, &mut model).map_err(invalid)?,
+            // groups, locators and such have no geometry
+            _ => {}
+        }
+    }
+    Ok(model)
+}
+
+struct BbImporter<'a> {
+    offset: [f32; 3],
+    resolution: [f32; 2],
+    textures: Vec<&'a str>,
+}
+
+impl BbImporter<'_> {
+    fn cube(&self, element: &Value, model: &mut Model) -> Result<(), String> {
+        let inflate = element["inflate"].as_f64().unwrap_or(0.0) as f32;
+        let from = vector::<3>(&element["from"], "from")?;
+        let to = vector::<3>(&element["to"], "to")?;
+        let from = [0, 1, 2].map(|axis| from[axis] - inflate + self.offset[axis]);
+        let to = [0, 1, 2].map(|axis| to[axis] + inflate + self.offset[axis]);
+        let rotations = self.rotations(element)?;
+
+        let mut faces = vec![];
+        let mut uv_rotations = vec![];
+        for face in FaceDir::ALL {
+            let face_json = &element["faces"][face_name(face)];
+            if face_json.is_null() {
+                continue;
+            }
+            let Some(texture) = self.texture(&face_json["texture"])? else {
+                continue;
+            };
+            let [u0, v0, u1, v1] = vector::<4>(&face_json["uv"], "uv")?;
+            let [u0, u1] = [u0, u1].map(|u| u * 16.0 / self.resolution[0]);
+            let [v0, v1] = [v0, v1].map(|v| v * 16.0 / self.resolution[1]);
+            faces.push((face, CubeFace { uv: Some([u0, v0, u1, v1]), texture, cull: Cull::Auto }));
+            uv_rotations.push(face_json["rotation"].as_f64().unwrap_or(0.0) as i32);
+        }
+
+        if rotations.len() <= 1 && uv_rotations.iter().all(|rotation| *rotation == 0) {
+            model.elements.push(Element::Cube(Cube {
+                from,
+                to,
+                faces,
+                rotation: rotations.first().copied(),
+            }));
+            return Ok(());
+        }
+        // rotations the format can't express are baked into separate quads
+        for ((face, cube_face), uv_rotation) in faces.into_iter().zip(uv_rotations) {
+            let texture = cube_face.texture;
+            let cube = Model {
+                elements: vec![Element::Cube(Cube { from, to, faces: vec![(face, cube_face)], rotation: None })],
+            };
+            let quad = &cube.quads()[0];
+            let mut uvs = quad.uvs.map(|uv| uv.map(|c| c * 16.0));
+            uvs.rotate_left((uv_rotation.rem_euclid(360) / 90) as usize);
+            model.elements.push(Element::Quad(ModelQuad {
+                positions: quad.positions.map(|p| rotate_all(&rotations, p.map(|c| c * 16.0))),
+                uvs,
+                texture,
+                cull: Cull::Auto,
+                rotation: None,
+            }));
+        }
+        Ok(())
+    }
+
+    fn mesh(&self, element: &Value, model: &mut Model) -> Result<(), String> {
+        let origin = vector::<3>(&element["origin"], "origin").unwrap_or([0.0; 3]);
+        let rotations = self.rotations(element)?;
+        let vertices = element["vertices"].as_object().ok_or("a mesh needs `vertices`")?;
+        let faces = element["faces"].as_object().ok_or("a mesh needs `faces`")?;
+        for face in faces.values() {
+            let Some(texture) = self.texture(&face["texture"])? else {
+                continue;
+            };
+            let keys = face["vertices"].as_array()
+                .filter(|keys| keys.len() == 3 || keys.len() == 4)
+                .ok_or("mesh faces need 3 or 4 vertices")?;
+            let mut corners = vec![];
+            for key in keys {
+                let key = key.as_str().ok_or("mesh face vertices must be names")?;
+                let position = vector::<3>(vertices.get(key).unwrap_or(&Value::Null), key)?;
+                let position = [0, 1, 2].map(|axis| position[axis] + origin[axis] + self.offset[axis]);
+                let uv = vector::<2>(&face["uv"][key], "uv").unwrap_or([0.0; 2]);
+                corners.push((
+                    rotate_all(&rotations, position),
+                    [uv[0] * 16.0 / self.resolution[0], uv[1] * 16.0 / self.resolution[1]],
+                ));
+            }
+            if corners.len() == 4 {
+                sort_around_center(&mut corners);
+            } else {
+                corners.push(corners[2]);
+            }
+            model.elements.push(Element::Quad(ModelQuad {
+                positions: [0, 1, 2, 3].map(|i| corners[i].0),
+                uvs: [0, 1, 2, 3].map(|i| corners[i].1),
+                texture,
+                cull: Cull::Auto,
+                rotation: None,
+            }));
+        }
+        Ok(())
+    }
+
+    /// The rotations of an element, in the order they're applied
+    fn rotations(&self, element: &Value) -> Result<Vec<Rotation>, String> {
+        let origin = vector::<3>(&element["origin"], "origin").unwrap_or([8.0, 8.0, 8.0]);
+        let origin = [0, 1, 2].map(|axis| origin[axis] + self.offset[axis]);
+        let Ok(angles) = vector::<3>(&element["rotation"], "rotation") else {
+            return Ok(vec![]);
+        };
+        Ok([Axis::X, Axis::Y, Axis::Z].into_iter()
+            .zip(angles)
+            .filter(|(_, degrees)| *degrees != 0.0)
+            .map(|(axis, degrees)| Rotation { axis, degrees, origin })
+            .collect())
+    }
+
+    /// The texture slot of a face, or `None` if the face has no texture
+    fn texture(&self, texture: &Value) -> Result<Option<usize>, String> {
+        match texture {
+            Value::Number(number) => number.as_u64()
+                .map(|slot| Some(slot as usize))
+                .ok_or_else(|| format!("invalid texture `{number}`")),
+            Value::String(uuid) => self.textures.iter()
+                .position(|texture| texture == uuid)
+                .map(Some)
+                .ok_or_else(|| format!("unknown texture `{uuid}`")),
+            Value::Null | Value::Bool(false) => Ok(None),
+            _ => Err("invalid texture".to_string()),
+        }
+    }
+}
+
+fn vector<const N: usize>(value: &Value, name: &str) -> Result<[f32; N], String> {
+    let invalid = || format!("`{name}` must be a list of {N} numbers");
+    let array = value.as_array().filter(|array| array.len() == N).ok_or_else(invalid)?;
+    let mut vector = [0.0; N];
+    for (c, value) in vector.iter_mut().zip(array) {
+        *c = value.as_f64().ok_or_else(invalid)? as f32;
+    }
+    Ok(vector)
+}
+
+fn rotate_all(rotations: &[Rotation], point: [f32; 3]) -> [f32; 3] {
+    rotations.iter().fold(point, |point, rotation| rotation.apply(point))
+}
+
+/// Puts the corners of a quad in order around its center, keeping the
+/// facing given by the first three corners
+fn sort_around_center(corners: &mut [([f32; 3], [f32; 2])]) {
+    let normal = triangle_normal(corners[0].0, corners[1].0, corners[2].0);
+    let center = [0, 1, 2].map(|axis| corners.iter().map(|(p, _)| p[axis]).sum::<f32>() / 4.0);
+    let first = sub(corners[0].0, center);
+    let side = cross(normal, first);
+    corners.sort_by(|(a, _), (b, _)| {
+        let angle = |p: [f32; 3]| {
+            let p = sub(p, center);
+            dot(p, side).atan2(dot(p, first)).rem_euclid(std::f32::consts::TAU)
+        };
+        angle(*a).total_cmp(&angle(*b))
+    });
+}
+
+/// Imports the triangles of a glTF or binary glTF file.
+///
+/// Every triangle becomes a quad, with pairs of triangles making up
+/// a flat quad merged back together.  The texture slot of each quad
+/// is the index of its material.  `base` is the directory that
+/// buffers stored in separate files are relative to.
+pub fn from_gltf(bytes: &[u8], base: Option<&Path>) -> Result<Model, ImportError> {
+    let gltf = gltf::Gltf::from_slice(bytes).map_err(ImportError::Gltf)?;
+    let mut buffers = vec![];
+    for buffer in gltf.buffers() {
+        let data = match buffer.source() {
+            gltf::buffer::Source::Bin => gltf.blob.clone()
+                .ok_or_else(|| ImportError::Invalid("the binary chunk is missing".to_string()))?,
+            gltf::buffer::Source::Uri(uri) => match uri.strip_prefix("data:") {
+                Some(data) => {
+                    let (_, encoded) = data.split_once(";base64,")
+                        .ok_or_else(|| ImportError::Invalid("only base64 data uris are supported".to_string()))?;
+                    base64::engine::general_purpose::STANDARD.decode(encoded)
+                        .map_err(|error| ImportError::Invalid(format!("invalid data uri: {error}")))?
+                }
+                None => {
+                    let base = base.ok_or_else(|| ImportError::Invalid(format!("can't find buffer `{uri}`")))?;
+                    std::fs::read(base.join(uri)).map_err(ImportError::Io)?
+                }
+            },
+        };
+        buffers.push(data);
+    }
+
+    let mut model = Model::default();
+    let scene = gltf.default_scene().or_else(|| gltf.scenes().next());
+    let mut nodes: Vec<_> = scene.iter()
+        .flat_map(|scene| scene.nodes())
+        .map(|node| (node, IDENTITY))
+        .collect();
+    while let Some((node, parent)) = nodes.pop() {
+        let transform = multiply(parent, node.transform().matrix());
+        nodes.extend(node.children().map(|child| (child, transform)));
+        let Some(mesh) = node.mesh() else {
+            continue;
+        };
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+            let Some(positions) = reader.read_positions() else {
+                continue;
+            };
+            let positions: Vec<[f32; 3]> = positions
+                .map(|p| {
+                    let p = apply(transform, p);
+                    [(p[0] + 0.5) * 16.0, p[1] * 16.0, (p[2] + 0.5) * 16.0]
+                })
+                .collect();
+            let uvs: Vec<[f32; 2]> = match reader.read_tex_coords(0) {
+                Some(uvs) => uvs.into_f32().map(|uv| uv.map(|c| c * 16.0)).collect(),
+                None => vec![[0.0; 2]; positions.len()],
+            };
+            let indices: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+            let mut triangles = indices.chunks_exact(3)
+                .map(|triangle| [0, 1, 2].map(|i| triangle[i] as usize))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .peekable();
+            let texture = primitive.material().index().unwrap_or(0);
+            while let Some(triangle) = triangles.next() {
+                if triangle.iter().any(|index| *index >= positions.len()) {
+                    return Err(ImportError::Invalid("a triangle refers to a missing vertex".to_string()));
+                }
+                let merged = triangles.peek()
+                    .and_then(|next| merge_triangles(triangle, *next, &positions));
+                let corners = match merged {
+                    Some(quad) => {
+                        triangles.next();
+                        quad
+                    }
+                    None => [triangle[0], triangle[1], triangle[2], triangle[2]],
+                };
+                model.elements.push(Element::Quad(ModelQuad {
+                    positions: corners.map(|index| positions[index]),
+                    uvs: corners.map(|index| uvs.get(index).copied().unwrap_or_default()),
+                    texture,
+                    cull: Cull::Auto,
+                    rotation: None,
+                }));
+            }
+        }
+    }
+    Ok(model)
+}
+
+/// Joins two triangles sharing an edge into a quad,
+/// if they face the same way and make a convex quad
+fn merge_triangles(a: [usize; 3], b: [usize; 3], positions: &[[f32; 3]]) -> Option<[usize; 4]> {
+    let quad = (0..3).find_map(|edge| {
+        let (start, end) = (a[edge], a[(edge + 1) % 3]);
+        let shared = (0..3).position(|i| b[i] == end && b[(i + 1) % 3] == start)?;
+        let other = b[(shared + 2) % 3];
+        Some([start, other, end, a[(edge + 2) % 3]])
+    })?;
+    let corners = quad.map(|index| positions.get(index).copied());
+    let [Some(p0), Some(p1), Some(p2), Some(p3)] = corners else {
+        return None;
+    };
+    let corners = [p0, p1, p2, p3];
+    let normal = triangle_normal(p0, p1, p2);
+    let convex = (0..4).all(|i| {
+        let turn = triangle_normal(corners[i], corners[(i + 1) % 4], corners[(i + 2) % 4]);
+        dot(turn, normal) > 1.0 - 1e-3
+    });
+    convex.then_some(quad)
+}
+
+type Matrix = [[f32; 4]; 4];
+
+const IDENTITY: Matrix = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+
+/// Multiplies two column major matrices
+fn multiply(a: Matrix, b: Matrix) -> Matrix {
+    [0, 1, 2, 3].map(|column| [0, 1, 2, 3].map(|row| {
+        (0..4).map(|i| a[i][row] * b[column][i]).sum()
+    }))
+}
+
+fn apply(matrix: Matrix, p: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|row| matrix[0][row] * p[0] + matrix[1][row] * p[1] + matrix[2][row] * p[2] + matrix[3][row])
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// The unit normal of a counter clockwise triangle, or zero if it has no area
+fn triangle_normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    let normal = cross(sub(b, a), sub(c, a));
+    let length = dot(normal, normal).sqrt();
+    if length <= f32::EPSILON {
+        return [0.0; 3];
+    }
+    normal.map(|c| c / length)
+}
+
+/// Why a model couldn't be imported
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    /// The file isn't a `.bbmodel`, `.gltf` or `.glb` file
+    UnknownFormat,
+    Json(serde_json::Error),
+    Gltf(gltf::Error),
+    /// The file is valid JSON or glTF, but isn't a model that can be imported
+    Invalid(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(error) => write!(f, "couldn't read model: {error}"),
+            ImportError::UnknownFormat => write!(f, "expected a .bbmodel, .gltf or .glb file"),
+            ImportError::Json(error) => write!(f, "invalid Blockbench project: {error}"),
+            ImportError::Gltf(error) => write!(f, "invalid glTF file: {error}"),
+            ImportError::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
@@ -0,0 +1,724 @@
+//! Models in the `.bbno$` format.
+//!
+//! A model is a plain text file with one statement per line.  Positions and
+//! uvs are measured in sixteenths of a block, so a full block spans 0 to 16.
+//! Everything after a `#` is a comment.
+//!
+//! ```text
+//! # a raised plate on the north face of a block
+//! cube 2 2 0  14 14 1
+//! face north uv 2 2 14 14
+//! face south cull none
+//! # a single quad, with its corners listed counter clockwise from the outside
+//! quad 0 0 0  0 16 0  16 16 0  16 0 0  uv 16 16  16 0  0 0  0 16  texture 1
+//! ```
+//!
+//! - `cube x0 y0 z0 x1 y1 z1` adds a box.  Without any `face` lines it has
+//!   all six faces, with uvs locked to block space.
+//! - `face <direction>` adds a face to the last cube.  Once a cube has
+//!   one `face` line, only the listed faces are kept.
+//! - `quad` followed by the four corners of a quad adds a single quad.
+//!
+//! Each statement can be followed by properties:
+//!
+//! - `uv u0 v0 u1 v1` on faces, or four `u v` pairs on quads, sets the texture coordinates.
+//! - `texture <slot>` picks a texture relative to the block's index, 0 by default.
+//! - `cull <direction>` culls the face when the neighbour in that direction culls,
+//!   and `cull none` never culls it.  Faces and quads lying on the edge of
+//!   the block cull towards the edge they're on by default.
+//! - `rotate <x|y|z> <degrees> <ox> <oy> <oz>` on cubes and quads rotates them
+//!   around an axis through the given origin.
+
+use std::{collections::HashMap, fmt, io, path::{Path, PathBuf}};
+
+use crate::{blocks::{block::FaceDir, block_types::BlockType, geometry::Quad}, table::Table};
+
+#[cfg(feature = "import")]
+pub mod import;
+
+/// A parsed `.bbno$` model
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Model {
+    pub elements: Vec<Element>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Element {
+    Cube(Cube),
+    Quad(ModelQuad),
+}
+
+/// A box, in sixteenths of a block
+#[derive(Clone, PartialEq, Debug)]
+pub struct Cube {
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    /// The faces of the cube that are drawn, in the order they were listed
+    pub faces: Vec<(FaceDir, CubeFace)>,
+    pub rotation: Option<Rotation>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CubeFace {
+    /// `[u0, v0, u1, v1]` in sixteenths of the texture,
+    /// if unset the uvs are locked to block space
+    pub uv: Option<[f32; 4]>,
+    /// The texture used, relative to the block's index
+    pub texture: usize,
+    pub cull: Cull,
+}
+
+/// A single quad, in sixteenths of a block
+#[derive(Clone, PartialEq, Debug)]
+pub struct ModelQuad {
+    /// Corners wound counter clockwise when seen from the front
+    pub positions: [[f32; 3]; 4],
+    /// The uv of each corner, in sixteenths of the texture
+    pub uvs: [[f32; 2]; 4],
+    /// The texture used, relative to the block's index
+    pub texture: usize,
+    pub cull: Cull,
+    pub rotation: Option<Rotation>,
+}
+
+/// When a face of a model is removed because its neighbour culls it
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cull {
+    /// Cull towards the edge of the block the face lies flat on, if any
+    Auto,
+    /// Never cull
+    Never,
+    /// Cull when the neighbour in this direction culls
+    Face(FaceDir),
+}
+
+/// A rotation around an axis through `origin`, in sixteenths of a block
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rotation {
+    pub axis: Axis,
+    /// Counter clockwise when looking from the positive end of the axis
+    pub degrees: f32,
+    pub origin: [f32; 3],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+    X, Y, Z
+}
+
+impl Rotation {
+    fn apply(&self, point: [f32; 3]) -> [f32; 3] {
+        let (sin, cos) = self.degrees.to_radians().sin_cos();
+        let p = [0, 1, 2].map(|axis| point[axis] - self.origin[axis]);
+        let rotated = match self.axis {
+            Axis::X => [p[0], p[1] * cos - p[2] * sin, p[1] * sin + p[2] * cos],
+            Axis::Y => [p[0] * cos + p[2] * sin, p[1], -p[0] * sin + p[2] * cos],
+            Axis::Z => [p[0] * cos - p[1] * sin, p[0] * sin + p[1] * cos, p[2]],
+        };
+        [0, 1, 2].map(|axis| rotated[axis] + self.origin[axis])
+    }
+
+    fn apply_vector(&self, vector: [f32; 3]) -> [f32; 3] {
+        let origin = self.apply([0.0; 3]);
+        let moved = self.apply(vector);
+        [0, 1, 2].map(|axis| moved[axis] - origin[axis])
+    }
+}
+
+impl Model {
+    /// Parses the text of a `.bbno$` file
+    pub fn parse(text: &str) -> Result<Model, ParseError> {
+        let mut model = Model::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap();
+            let mut tokens = Tokens::new(line, number + 1);
+            let Some(statement) = tokens.next() else {
+                continue;
+            };
+            match statement.text {
+                "cube" => {
+                    let from = tokens.numbers::<3>()?;
+                    let to = tokens.numbers::<3>()?;
+                    if (0..3).any(|axis| from[axis] > to[axis]) {
+                        return Err(statement.error("cube ends before it starts"));
+                    }
+                    let mut cube = Cube { from, to, faces: vec![], rotation: None };
+                    while let Some(property) = tokens.next() {
+                        match property.text {
+                            "rotate" => cube.rotation = Some(tokens.rotation()?),
+                            _ => return Err(property.unexpected("property of a cube")),
+                        }
+                    }
+                    model.elements.push(Element::Cube(cube));
+                }
+                "face" => {
+                    let face = tokens.face()?;
+                    let mut cube_face = CubeFace { uv: None, texture: 0, cull: Cull::Auto };
+                    while let Some(property) = tokens.next() {
+                        match property.text {
+                            "uv" => cube_face.uv = Some(tokens.numbers::<4>()?),
+                            "texture" => cube_face.texture = tokens.integer()?,
+                            "cull" => cube_face.cull = tokens.cull()?,
+                            _ => return Err(property.unexpected("property of a face")),
+                        }
+                    }
+                    let Some(Element::Cube(cube)) = model.elements.last_mut() else {
+                        return Err(statement.error("`face` must follow a `cube`"));
+                    };
+                    if cube.faces.iter().any(|(existing, _)| *existing == face) {
+                        return Err(statement.error("this face was already listed for the cube"));
+                    }
+                    cube.faces.push((face, cube_face));
+                }
+                "quad" => {
+                    let corners = tokens.numbers::<12>()?;
+                    let mut quad = ModelQuad {
+                        positions: [0, 1, 2, 3].map(|i| [corners[i * 3], corners[i * 3 + 1], corners[i * 3 + 2]]),
+                        uvs: [[0.0, 16.0], [16.0, 16.0], [16.0, 0.0], [0.0, 0.0]],
+                        texture: 0,
+                        cull: Cull::Auto,
+                        rotation: None,
+                    };
+                    while let Some(property) = tokens.next() {
+                        match property.text {
+                            "uv" => {
+                                let uvs = tokens.numbers::<8>()?;
+                                quad.uvs = [0, 1, 2, 3].map(|i| [uvs[i * 2], uvs[i * 2 + 1]]);
+                            }
+                            "texture" => quad.texture = tokens.integer()?,
+                            "cull" => quad.cull = tokens.cull()?,
+                            "rotate" => quad.rotation = Some(tokens.rotation()?),
+                            _ => return Err(property.unexpected("property of a quad")),
+                        }
+                    }
+                    model.elements.push(Element::Quad(quad));
+                }
+                _ => return Err(statement.unexpected("`cube`, `face` or `quad`")),
+            }
+        }
+        Ok(model)
+    }
+
+    /// Reads and parses a `.bbno$` file
+    pub fn load(path: impl AsRef<Path>) -> Result<Model, ModelErrorKind> {
+        let text = std::fs::read_to_string(path).map_err(|error| match error.kind() {
+            io::ErrorKind::NotFound => ModelErrorKind::Missing,
+            _ => ModelErrorKind::Io(error),
+        })?;
+        Model::parse(&text).map_err(ModelErrorKind::Malformed)
+    }
+
+    /// The geometry of the model in block space, from 0 to 1 on each axis
+    pub fn quads(&self) -> Vec<Quad> {
+        let mut quads = vec![];
+        for element in &self.elements {
+            match element {
+                Element::Cube(cube) => {
+                    let from = cube.from.map(|c| c / 16.0);
+                    let to = cube.to.map(|c| c / 16.0);
+                    for (face, cube_face) in cube.all_faces() {
+                        let mut quad = Self::rotated(
+                            Quad::cuboid_face(face, from, to, cube_face.texture),
+                            cube.rotation
+                        );
+                        if let Some([u0, v0, u1, v1]) = cube_face.uv {
+                            quad.uvs = [[u0, v1], [u1, v1], [u1, v0], [u0, v0]]
+                                .map(|uv| uv.map(|c| c / 16.0));
+                        }
+                        quad.cull_face = match cube_face.cull {
+                            Cull::Auto => boundary_face(&quad),
+                            Cull::Never => None,
+                            Cull::Face(face) => Some(face),
+                        };
+                        quads.push(quad);
+                    }
+                }
+                Element::Quad(model_quad) => {
+                    let positions = model_quad.positions.map(|p| p.map(|c| c / 16.0));
+                    let edge = |to: usize| [0, 1, 2].map(|axis| positions[to][axis] - positions[0][axis]);
+                    let (a, b) = (edge(1), edge(2));
+                    let normal = [
+                        a[1] * b[2] - a[2] * b[1],
+                        a[2] * b[0] - a[0] * b[2],
+                        a[0] * b[1] - a[1] * b[0],
+                    ];
+                    let length = normal.iter().map(|c| c * c).sum::<f32>().sqrt().max(f32::EPSILON);
+                    let mut quad = Self::rotated(Quad {
+                        positions,
+                        uvs: model_quad.uvs.map(|uv| uv.map(|c| c / 16.0)),
+                        normal: normal.map(|c| c / length),
+                        texture: model_quad.texture,
+                        cull_face: None,
+                    }, model_quad.rotation);
+                    quad.cull_face = match model_quad.cull {
+                        Cull::Auto => boundary_face(&quad),
+                        Cull::Never => None,
+                        Cull::Face(face) => Some(face),
+                    };
+                    quads.push(quad);
+                }
+            }
+        }
+        quads
+    }
+
+    fn rotated(mut quad: Quad, rotation: Option<Rotation>) -> Quad {
+        let Some(rotation) = rotation else {
+            return quad;
+        };
+        let rotation = Rotation {
+            origin: rotation.origin.map(|c| c / 16.0),
+            ..rotation
+        };
+        quad.positions = quad.positions.map(|p| rotation.apply(p));
+        quad.normal = rotation.apply_vector(quad.normal);
+        quad
+    }
+
+    /// Whether the model is made for one face of a block: all of its geometry
+    /// stays in the half of the block next to the face, and some of it
+    /// lies flat on the face, pointing out of the block.
+    pub fn lies_on(&self, face: FaceDir) -> bool {
+        let normal = face.normal();
+        let axis = normal.iter().position(|c| *c != 0).unwrap();
+        let plane = if normal[axis] > 0 { 1.0 } else { 0.0 };
+        let quads = self.quads();
+        let near_face = quads.iter()
+            .flat_map(|quad| quad.positions)
+            .all(|p| (p[axis] - plane).abs() <= 0.5 + EPSILON);
+        let on_face = quads.iter().any(|quad| boundary_face(quad) == Some(face));
+        near_face && on_face
+    }
+
+    /// Splits the model into the parts lying flat on each face of the block,
+    /// which cull towards their face, and everything else, which never culls.
+    ///
+    /// This matches how [`FullCustom`](crate::blocks::block_types::full_custom::FullCustom)
+    /// blocks separate their six face models from their custom model.
+    pub fn split(&self) -> SplitModel {
+        let mut split = SplitModel::default();
+        for element in &self.elements {
+            match element {
+                Element::Cube(cube) if cube.rotation.is_none() => {
+                    let mut custom = Cube { faces: vec![], ..cube.clone() };
+                    for (face, cube_face) in cube.all_faces() {
+                        let quad = Quad::cuboid_face(face, cube.from.map(|c| c / 16.0), cube.to.map(|c| c / 16.0), 0);
+                        let culls = matches!(cube_face.cull, Cull::Auto) || cube_face.cull == Cull::Face(face);
+                        if culls && boundary_face(&quad) == Some(face) {
+                            split.faces[face.to_index()].elements.push(Element::Cube(Cube {
+                                faces: vec![(face, CubeFace { cull: Cull::Auto, ..cube_face })],
+                                ..cube.clone()
+                            }));
+                        } else {
+                            custom.faces.push((face, CubeFace { cull: Cull::Never, ..cube_face }));
+                        }
+                    }
+                    if !custom.faces.is_empty() {
+                        split.custom.elements.push(Element::Cube(custom));
+                    }
+                }
+                Element::Cube(cube) => {
+                    let faces = cube.all_faces()
+                        .map(|(face, cube_face)| (face, CubeFace { cull: Cull::Never, ..cube_face }))
+                        .collect();
+                    split.custom.elements.push(Element::Cube(Cube { faces, ..cube.clone() }));
+                }
+                Element::Quad(model_quad) => {
+                    let quad = &Model { elements: vec![element.clone()] }.quads()[0];
+                    match boundary_face(quad) {
+                        Some(face) if matches!(model_quad.cull, Cull::Auto) || model_quad.cull == Cull::Face(face) => {
+                            split.faces[face.to_index()].elements.push(Element::Quad(ModelQuad {
+                                cull: Cull::Auto,
+                                ..model_quad.clone()
+                            }));
+                        }
+                        _ => split.custom.elements.push(Element::Quad(ModelQuad {
+                            cull: Cull::Never,
+                            ..model_quad.clone()
+                        })),
+                    }
+                }
+            }
+        }
+        split
+    }
+}
+
+impl Cube {
+    /// The faces of the cube that are drawn, all six if none were listed
+    fn all_faces(&self) -> impl Iterator<Item = (FaceDir, CubeFace)> + '_ {
+        let default_faces = self.faces.is_empty().then(|| {
+            FaceDir::ALL.map(|face| (face, CubeFace { uv: None, texture: 0, cull: Cull::Auto }))
+        });
+        default_faces.into_iter().flatten().chain(self.faces.iter().cloned())
+    }
+}
+
+const EPSILON: f32 = 1e-4;
+
+/// The face of the block a quad lies flat on, pointing out of the block
+fn boundary_face(quad: &Quad) -> Option<FaceDir> {
+    FaceDir::ALL.into_iter().find(|face| {
+        let normal = face.normal();
+        let axis = normal.iter().position(|c| *c != 0).unwrap();
+        let plane = if normal[axis] > 0 { 1.0 } else { 0.0 };
+        quad.positions.iter().all(|p| (p[axis] - plane).abs() <= EPSILON)
+            && quad.normal[axis] * normal[axis] as f32 > 0.0
+    })
+}
+
+/// A model split by [`Model::split`]
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SplitModel {
+    /// The part lying on each face, indexed by [`FaceDir::to_index`]
+    pub faces: [Model; 6],
+    /// The part that doesn't lie on any face
+    pub custom: Model,
+}
+
+impl SplitModel {
+    pub fn face(&self, face: FaceDir) -> &Model {
+        &self.faces[face.to_index()]
+    }
+}
+
+/// Writes the model back out in the `.bbno$` format
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn numbers(f: &mut fmt::Formatter<'_>, numbers: &[f32]) -> fmt::Result {
+            numbers.iter().try_for_each(|number| write!(f, " {number}"))
+        }
+        fn rotation(f: &mut fmt::Formatter<'_>, rotation: &Option<Rotation>) -> fmt::Result {
+            let Some(rotation) = rotation else {
+                return Ok(());
+            };
+            let axis = match rotation.axis {
+                Axis::X => "x",
+                Axis::Y => "y",
+                Axis::Z => "z",
+            };
+            write!(f, " rotate {axis} {}", rotation.degrees)?;
+            numbers(f, &rotation.origin)
+        }
+        fn cull(f: &mut fmt::Formatter<'_>, cull: Cull) -> fmt::Result {
+            match cull {
+                Cull::Auto => Ok(()),
+                Cull::Never => write!(f, " cull none"),
+                Cull::Face(face) => write!(f, " cull {}", face_name(face)),
+            }
+        }
+
+        for element in &self.elements {
+            match element {
+                Element::Cube(cube) => {
+                    write!(f, "cube")?;
+                    numbers(f, &cube.from)?;
+                    numbers(f, &cube.to)?;
+                    rotation(f, &cube.rotation)?;
+                    writeln!(f)?;
+                    for (face, cube_face) in &cube.faces {
+                        write!(f, "face {}", face_name(*face))?;
+                        if let Some(uv) = cube_face.uv {
+                            write!(f, " uv")?;
+                            numbers(f, &uv)?;
+                        }
+                        if cube_face.texture != 0 {
+                            write!(f, " texture {}", cube_face.texture)?;
+                        }
+                        cull(f, cube_face.cull)?;
+                        writeln!(f)?;
+                    }
+                }
+                Element::Quad(quad) => {
+                    write!(f, "quad")?;
+                    numbers(f, quad.positions.as_flattened())?;
+                    write!(f, " uv")?;
+                    numbers(f, quad.uvs.as_flattened())?;
+                    if quad.texture != 0 {
+                        write!(f, " texture {}", quad.texture)?;
+                    }
+                    cull(f, quad.cull)?;
+                    rotation(f, &quad.rotation)?;
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a model file couldn't be parsed, pointing at the offending token
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+    /// The line of the error, starting from 1
+    pub line: usize,
+    /// The column of the error in characters, starting from 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl Token<'_> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        self.error(format!("expected {expected}, found `{}`", self.text))
+    }
+}
+
+/// The whitespace separated tokens of one line
+struct Tokens<'a> {
+    line: &'a str,
+    number: usize,
+    offset: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str, number: usize) -> Tokens<'a> {
+        Tokens { line, number, offset: 0 }
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let rest = &self.line[self.offset..];
+        let start = self.offset + rest.find(|c: char| !c.is_whitespace())?;
+        let end = self.line[start..]
+            .find(char::is_whitespace)
+            .map_or(self.line.len(), |end| start + end);
+        self.offset = end;
+        Some(Token {
+            text: &self.line[start..end],
+            line: self.number,
+            column: self.line[..start].chars().count() + 1,
+        })
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<Token<'a>, ParseError> {
+        self.next().ok_or_else(|| ParseError {
+            line: self.number,
+            column: self.line.chars().count() + 1,
+            message: format!("expected {expected}, found the end of the line"),
+        })
+    }
+
+    fn numbers<const N: usize>(&mut self) -> Result<[f32; N], ParseError> {
+        let mut numbers = [0.0; N];
+        for number in &mut numbers {
+            let token = self.expect("a number")?;
+            *number = token.text.parse().map_err(|_| token.unexpected("a number"))?;
+        }
+        Ok(numbers)
+    }
+
+    fn integer(&mut self) -> Result<usize, ParseError> {
+        let token = self.expect("a texture slot")?;
+        token.text.parse().map_err(|_| token.unexpected("a texture slot"))
+    }
+
+    fn face(&mut self) -> Result<FaceDir, ParseError> {
+        let token = self.expect("a direction")?;
+        match token.text {
+            "up" => Ok(FaceDir::Up),
+            "north" => Ok(FaceDir::North),
+            "west" => Ok(FaceDir::West),
+            "east" => Ok(FaceDir::East),
+            "south" => Ok(FaceDir::South),
+            "down" => Ok(FaceDir::Down),
+            _ => Err(token.unexpected("`up`, `north`, `west`, `east`, `south` or `down`")),
+        }
+    }
+
+    fn cull(&mut self) -> Result<Cull, ParseError> {
+        let offset = self.offset;
+        if self.next().is_some_and(|token| token.text == "none") {
+            return Ok(Cull::Never);
+        }
+        self.offset = offset;
+        Ok(Cull::Face(self.face()?))
+    }
+
+    fn rotation(&mut self) -> Result<Rotation, ParseError> {
+        let token = self.expect("an axis")?;
+        let axis = match token.text {
+            "x" => Axis::X,
+            "y" => Axis::Y,
+            "z" => Axis::Z,
+            _ => return Err(token.unexpected("`x`, `y` or `z`")),
+        };
+        let [degrees] = self.numbers::<1>()?;
+        let origin = self.numbers::<3>()?;
+        Ok(Rotation { axis, degrees, origin })
+    }
+}
+
+/// Every model used by a [`Table`], loaded from files relative to a root directory.
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{custom::Custom, full::Full, BlockType}}, model::{ModelErrorKind, Models}, with_full_paths, AsId};
+///
+/// table!(BlockType, enum BlockId, static BLOCKS = {
+///     let Rock: Custom = Block::new_custom("rock")
+///         .with_model("rock.bbno$");
+///     let Crate: Full = Block::new_full("crate")
+///         .with_models(with_full_paths! {
+///             north: "crate_north.bbno$",
+///             up: "crate_lid.bbno$"
+///         });
+/// });
+///
+/// let root = std::env::temp_dir().join("opentale_blocks_models_example");
+/// std::fs::create_dir_all(&root).unwrap();
+/// std::fs::write(root.join("rock.bbno$"), "cube 4 0 4 12 6 12").unwrap();
+/// std::fs::write(root.join("crate_north.bbno$"), "cube 0 0 0 16 16 1").unwrap();
+/// // a lid model that is actually on the bottom of the block
+/// std::fs::write(root.join("crate_lid.bbno$"), "cube 0 0 0 16 1 16").unwrap();
+///
+/// let errors = Models::load(&BLOCKS, &root).unwrap_err();
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].block, "crate");
+/// assert!(matches!(errors[0].kind, ModelErrorKind::WrongFace(_)));
+///
+/// std::fs::write(root.join("crate_lid.bbno$"), "cube 0 15 0 16 16 16\nface up").unwrap();
+/// let models = Models::load(&BLOCKS, &root).unwrap();
+/// assert_eq!(models.get("rock.bbno$").unwrap().quads().len(), 6);
+/// assert_eq!(models.get("crate_lid.bbno$").unwrap().quads().len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Models {
+    models: HashMap<&'static str, Model>,
+}
+
+impl Models {
+    /// Loads every model referenced by the table, checking that face models lie on their face.
+    ///
+    /// Every problem found is returned, not just the first one.
+    pub fn load<T: 'static + ?Sized + BlockType>(
+        table: &Table<T>,
+        root: impl AsRef<Path>
+    ) -> Result<Models, Vec<ModelError>> {
+        let root = root.as_ref();
+        let mut models = Models::default();
+        let mut failed: HashMap<&'static str, ModelErrorKind> = HashMap::new();
+        let mut errors = vec![];
+        for block in table {
+            for (face, path) in block.model_paths() {
+                let error = |kind| ModelError {
+                    block: block.name(),
+                    path: root.join(path),
+                    kind,
+                };
+                if let Some(kind) = failed.get(path) {
+                    errors.push(error(kind.clone()));
+                    continue;
+                }
+                if !models.models.contains_key(path) {
+                    match Model::load(root.join(path)) {
+                        Ok(model) => {
+                            models.models.insert(path, model);
+                        }
+                        Err(kind) => {
+                            failed.insert(path, kind.clone());
+                            errors.push(error(kind));
+                            continue;
+                        }
+                    }
+                }
+                if let Some(face) = face && !models.models[path].lies_on(face) {
+                    errors.push(error(ModelErrorKind::WrongFace(face)));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(models)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// A model by the path it's referenced by in the table
+    pub fn get(&self, path: &str) -> Option<&Model> {
+        self.models.get(path)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &Model)> {
+        self.models.iter().map(|(path, model)| (*path, model))
+    }
+}
+
+/// A problem with a model referenced by a block
+#[derive(Debug)]
+pub struct ModelError {
+    /// The technical name of the block referencing the model
+    pub block: &'static str,
+    pub path: PathBuf,
+    pub kind: ModelErrorKind,
+}
+
+#[derive(Debug)]
+pub enum ModelErrorKind {
+    /// The file doesn't exist
+    Missing,
+    /// The file couldn't be read
+    Io(io::Error),
+    Malformed(ParseError),
+    /// The model is used for a face of the block but doesn't lie on it
+    WrongFace(FaceDir),
+}
+
+impl Clone for ModelErrorKind {
+    fn clone(&self) -> Self {
+        match self {
+            ModelErrorKind::Missing => ModelErrorKind::Missing,
+            ModelErrorKind::Io(error) => ModelErrorKind::Io(io::Error::new(error.kind(), error.to_string())),
+            ModelErrorKind::Malformed(error) => ModelErrorKind::Malformed(error.clone()),
+            ModelErrorKind::WrongFace(face) => ModelErrorKind::WrongFace(*face),
+        }
+    }
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.display();
+        match &self.kind {
+            ModelErrorKind::Missing => write!(f, "{}: model {path} doesn't exist", self.block),
+            ModelErrorKind::Io(error) => write!(f, "{}: couldn't read model {path}: {error}", self.block),
+            ModelErrorKind::Malformed(error) => write!(f, "{}: {path}:{error}", self.block),
+            ModelErrorKind::WrongFace(face) => write!(f, "{}: model {path} doesn't lie on the {} face", self.block, face_name(*face)),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+pub(crate) fn face_name(face: FaceDir) -> &'static str {
+    match face {
+        FaceDir::Up => "up",
+        FaceDir::North => "north",
+        FaceDir::West => "west",
+        FaceDir::East => "east",
+        FaceDir::South => "south",
+        FaceDir::Down => "down",
+    }
+}
@@ -0,0 +1,885 @@
+//! A compressed on-disk format for the chunks of a world.
+//!
+//! Chunks are grouped into region files of [`REGION_SIZE`] chunks on each
+//! side, which a [`RegionStore`] keeps in a directory as `r.x.y.z.otr`.
+//! A region file is split into [`SECTOR_SIZE`] byte sectors:
+//!
+//! - The header, taking up the first sectors, holds the magic `OTRG`, the
+//!   format version, then an index entry for the id map and one for every
+//!   chunk, so any chunk can be found without reading the others.  Each entry
+//!   is the first sector, the number of sectors, the length in bytes and a
+//!   CRC-32 of what it points to, all little endian `u32`s.
+//! - The id map lists the state of every id the file uses, like
+//!   `Furnace[facing: north]`, so loading a chunk maps its ids to the
+//!   current table even if blocks were added, removed or reordered since
+//!   it was saved.  Loading a chunk with a state the table no longer has
+//!   fails, unless a block to load such states as is chosen with
+//!   [`RegionStore::with_missing_state`].
+//! - Every chunk is stored as its palette of ids followed by its packed
+//!   palette indices, the same way [`Chunk`] keeps them in memory.
+//!
+//! The id map and every chunk start with a byte saying how they're
+//! compressed.  `deflate` and `zstd` compression each need their feature,
+//! and files can mix chunks compressed in different ways.  Block entities
+//! aren't saved.
+//!
+//! [`scan`] checks every entry of a file against its checksum, and
+//! [`repair`] removes the chunks whose sectors are corrupt, so the rest of
+//! the region can still be loaded.  The command line tool runs both.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, full::Full, rotateable::{Rotateable, RotDir, YRotFacing}, BlockType}}, chunk::Chunk, region::{self, CorruptionKind, RegionStore}, AsId};
+//!
+//! mod old {
+//! #   use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, full::Full, rotateable::{Rotateable, RotDir}, BlockType}}, AsId};
+//!     table!(BlockType, enum BlockId, static BLOCKS = {
+//!         let Stone: Basic = Block::new_basic("stone");
+//!         let Furnace: Rotateable<Full, { RotDir::Y }> = Block::new_rotateable(Block::new_full("furnace"));
+//!     });
+//! }
+//!
+//! // a later version of the game added dirt before the furnace
+//! mod new {
+//! #   use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, full::Full, rotateable::{Rotateable, RotDir}, BlockType}}, AsId};
+//!     table!(BlockType, enum BlockId, static BLOCKS = {
+//!         let Stone: Basic = Block::new_basic("stone");
+//!         let Dirt: Basic = Block::new_basic("dirt");
+//!         let Furnace: Rotateable<Full, { RotDir::Y }> = Block::new_rotateable(Block::new_full("furnace"));
+//!     });
+//! }
+//!
+//! let dir = std::env::temp_dir().join("opentale_blocks_region_example");
+//! let _ = std::fs::remove_dir_all(&dir);
+//!
+//! let mut chunk = Chunk::filled(old::BlockId::Air(()).to_id());
+//! chunk.set_id([0, 0, 0], &old::BlockId::Stone(()));
+//! chunk.set_id([1, 2, 3], &old::BlockId::Furnace((YRotFacing::East, ())));
+//! let mut store = RegionStore::<old::BlockId>::open(&dir).unwrap();
+//! store.save_chunk([0, 0, 0], &chunk).unwrap();
+//! store.save_chunk([-1, 0, 0], &chunk).unwrap();
+//! drop(store);
+//!
+//! // the furnace keeps its state though its ids moved
+//! let mut store = RegionStore::<new::BlockId>::open(&dir).unwrap();
+//! let loaded = store.load_chunk([0, 0, 0]).unwrap().unwrap();
+//! assert_eq!(loaded.get_id::<new::BlockId>([1, 2, 3]).to_string(), "Furnace[facing: east]");
+//! assert!(store.load_chunk([0, 1, 0]).unwrap().is_none());
+//!
+//! // flip a byte of the first chunk, in the sector after the header and id map
+//! let path = store.region_path([0, 0, 0]);
+//! drop(store);
+//! let mut bytes = std::fs::read(&path).unwrap();
+//! bytes[4 * region::SECTOR_SIZE + 8] ^= 0xff;
+//! std::fs::write(&path, bytes).unwrap();
+//!
+//! let report = region::scan(&path).unwrap();
+//! assert_eq!(report.problems[0].chunk, Some([0, 0, 0]));
+//! assert_eq!(report.problems[0].kind, CorruptionKind::Checksum);
+//! region::repair(&path).unwrap();
+//! assert!(region::scan(&path).unwrap().problems.is_empty());
+//!
+//! let mut store = RegionStore::<new::BlockId>::open(&dir).unwrap();
+//! assert!(store.load_chunk([0, 0, 0]).unwrap().is_none());
+//! assert!(store.load_chunk([-1, 0, 0]).unwrap().is_some());
+//! ```
+
+use std::{collections::HashMap, fmt, fs::{File, OpenOptions}, io::{self, Read, Seek, SeekFrom, Write}, marker::PhantomData, path::{Path, PathBuf}};
+
+use crate::{chunk::{Chunk, ChunkGrid, ChunkPos}, AsId};
+
+/// The number of chunks on each side of a region
+pub const REGION_SIZE: usize = 8;
+/// The number of chunks in a region
+pub const REGION_VOLUME: usize = REGION_SIZE * REGION_SIZE * REGION_SIZE;
+/// The unit region files are allocated in
+pub const SECTOR_SIZE: usize = 4096;
+
+/// The position of a region, in regions rather than chunks
+pub type RegionPos = [i32; 3];
+
+const MAGIC: [u8; 4] = *b"OTRG";
+const VERSION: u32 = 1;
+const ENTRY_SIZE: usize = 16;
+/// The magic and version, then the id map's entry followed by every chunk's
+const HEADER_SIZE: usize = 8 + ENTRY_SIZE * (1 + REGION_VOLUME);
+const HEADER_SECTORS: u32 = HEADER_SIZE.div_ceil(SECTOR_SIZE) as u32;
+/// The most a chunk can take up once decompressed, which no valid chunk comes close to
+const MAX_CHUNK_SIZE: u64 = 1 << 20;
+const MAX_ID_MAP_SIZE: u64 = 1 << 26;
+
+/// The region containing a chunk
+pub fn region_pos(chunk: ChunkPos) -> RegionPos {
+    chunk.map(|c| c.div_euclid(REGION_SIZE as i32))
+}
+
+/// The position of a chunk relative to the region containing it
+pub fn local_chunk_pos(chunk: ChunkPos) -> [usize; 3] {
+    chunk.map(|c| c.rem_euclid(REGION_SIZE as i32) as usize)
+}
+
+/// How the id map and chunks are compressed when they're saved
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Compression {
+    None,
+    #[cfg(feature = "deflate")]
+    Deflate,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    fn tag(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            #[cfg(feature = "deflate")]
+            Compression::Deflate => 1,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Compression> {
+        match tag {
+            0 => Some(Compression::None),
+            #[cfg(feature = "deflate")]
+            1 => Some(Compression::Deflate),
+            #[cfg(feature = "zstd")]
+            2 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// The compression byte followed by the compressed bytes
+    fn store(self, bytes: &[u8]) -> Vec<u8> {
+        let mut stored = vec![self.tag()];
+        match self {
+            Compression::None => stored.extend_from_slice(bytes),
+            #[cfg(feature = "deflate")]
+            Compression::Deflate => {
+                let mut encoder = flate2::write::DeflateEncoder::new(stored, flate2::Compression::default());
+                encoder.write_all(bytes).expect("writing to a Vec can't fail");
+                stored = encoder.finish().expect("writing to a Vec can't fail");
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                stored.extend(zstd::stream::encode_all(bytes, 0).expect("compressing a slice can't fail"));
+            }
+        }
+        stored
+    }
+
+    /// Decompresses stored bytes, reading at most `limit` bytes
+    fn load(stored: &[u8], limit: u64) -> Result<Vec<u8>, ReadError> {
+        let (&tag, bytes) = stored.split_first().ok_or(ReadError::Corrupt(CorruptionKind::OutOfBounds))?;
+        let compression = Compression::from_tag(tag).ok_or(ReadError::Unsupported(tag))?;
+        let mut out = vec![];
+        let result: io::Result<usize> = match compression {
+            Compression::None => {
+                out.extend_from_slice(bytes);
+                Ok(0)
+            }
+            #[cfg(feature = "deflate")]
+            Compression::Deflate => flate2::read::DeflateDecoder::new(bytes).take(limit + 1).read_to_end(&mut out),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::stream::read::Decoder::new(bytes)
+                .and_then(|decoder| decoder.take(limit + 1).read_to_end(&mut out)),
+        };
+        result.map_err(|error| ReadError::Corrupt(CorruptionKind::Unreadable(error.to_string())))?;
+        if out.len() as u64 > limit {
+            return Err(ReadError::Corrupt(CorruptionKind::Unreadable("too large once decompressed".to_string())));
+        }
+        Ok(out)
+    }
+}
+
+impl Default for Compression {
+    /// The best compression enabled by the crate's features
+    fn default() -> Self {
+        DEFAULT_COMPRESSION
+    }
+}
+
+#[cfg(feature = "zstd")]
+const DEFAULT_COMPRESSION: Compression = Compression::Zstd;
+#[cfg(all(feature = "deflate", not(feature = "zstd")))]
+const DEFAULT_COMPRESSION: Compression = Compression::Deflate;
+#[cfg(not(any(feature = "deflate", feature = "zstd")))]
+const DEFAULT_COMPRESSION: Compression = Compression::None;
+
+/// Where something is stored in a region file, with its checksum
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+struct Entry {
+    sector: u32,
+    sectors: u32,
+    length: u32,
+    checksum: u32,
+}
+
+impl Entry {
+    fn is_empty(&self) -> bool {
+        self.sectors == 0
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Entry {
+        let word = |i: usize| u32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap());
+        Entry { sector: word(0), sectors: word(1), length: word(2), checksum: word(3) }
+    }
+
+    fn to_bytes(self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0; ENTRY_SIZE];
+        for (i, word) in [self.sector, self.sectors, self.length, self.checksum].into_iter().enumerate() {
+            bytes[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// One region file, holding up to [`REGION_VOLUME`] chunks.
+///
+/// `I` is the enum of the table the chunks' ids come from.
+pub struct Region<I> {
+    file: File,
+    /// The id map's entry followed by every chunk's
+    entries: Vec<Entry>,
+    /// The state of every id the file uses, by the id in the file
+    file_states: Vec<String>,
+    /// The table id of every id in the file, `None` for states the table doesn't have
+    to_table: Vec<Option<usize>>,
+    /// The id in the file of every table id the file uses
+    to_file: HashMap<usize, usize>,
+    compression: Compression,
+    /// The table id states the table doesn't have are loaded as
+    missing_state: Option<usize>,
+    table: PhantomData<fn() -> I>,
+}
+
+impl<I: AsId> Region<I> {
+    /// Opens a region file, creating it if it doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Region<I>, RegionError> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let mut region = Region {
+            file,
+            entries: vec![Entry::default(); 1 + REGION_VOLUME],
+            file_states: vec![],
+            to_table: vec![],
+            to_file: HashMap::new(),
+            compression: Compression::default(),
+            missing_state: None,
+            table: PhantomData,
+        };
+        if region.file.metadata()?.len() == 0 {
+            region.file_states = (0..I::get_id_span()).map(|id| I::from_id(id).to_string()).collect();
+            let mut header = vec![0; HEADER_SECTORS as usize * SECTOR_SIZE];
+            header[..4].copy_from_slice(&MAGIC);
+            header[4..8].copy_from_slice(&VERSION.to_le_bytes());
+            region.file.write_all(&header)?;
+            region.write_id_map()?;
+        } else {
+            region.entries = read_header(&mut region.file)?;
+            let stored = read_entry(&mut region.file, region.entries[0], MAX_ID_MAP_SIZE)
+                .map_err(|error| error.at(None, region.entries[0]))?;
+            region.file_states = decode_id_map(&stored)
+                .map_err(|message| ReadError::Corrupt(CorruptionKind::Unreadable(message)).at(None, region.entries[0]))?;
+        }
+        let table: HashMap<String, usize> = (0..I::get_id_span()).map(|id| (I::from_id(id).to_string(), id)).collect();
+        region.to_table = region.file_states.iter().map(|state| table.get(state).copied()).collect();
+        region.to_file = region.to_table.iter()
+            .enumerate()
+            .filter_map(|(file_id, id)| Some(((*id)?, file_id)))
+            .collect();
+        Ok(region)
+    }
+
+    /// Sets how chunks saved from now on are compressed
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Loads the states saved in the file that the table doesn't have as `block`.
+    ///
+    /// By default, loading a chunk with any of them fails with [`RegionError::MissingState`].
+    pub fn with_missing_state(mut self, block: &I) -> Self {
+        self.missing_state = Some(block.to_id());
+        self
+    }
+
+    /// The states saved in the file that the table doesn't have
+    pub fn missing_states(&self) -> impl Iterator<Item = &str> + '_ {
+        self.file_states.iter()
+            .zip(&self.to_table)
+            .filter(|(_, id)| id.is_none())
+            .map(|(state, _)| state.as_str())
+    }
+
+    /// Whether the file has a chunk, where `pos` is taken relative to the region
+    pub fn contains(&self, pos: ChunkPos) -> bool {
+        !self.entries[Self::slot(pos)].is_empty()
+    }
+
+    /// Loads a chunk, where `pos` is taken relative to the region,
+    /// returning `None` if it was never saved
+    pub fn load_chunk(&mut self, pos: ChunkPos) -> Result<Option<Chunk>, RegionError> {
+        let entry = self.entries[Self::slot(pos)];
+        if entry.is_empty() {
+            return Ok(None);
+        }
+        let local = Some(local_chunk_pos(pos));
+        let stored = read_entry(&mut self.file, entry, MAX_CHUNK_SIZE).map_err(|error| error.at(local, entry))?;
+        let corrupt = |message: &str| {
+            ReadError::Corrupt(CorruptionKind::Unreadable(message.to_string())).at(local, entry)
+        };
+        let (palette, bits, data) = decode_chunk(&stored).map_err(|message| corrupt(&message))?;
+        let palette = palette.into_iter()
+            .map(|file_id| match self.to_table.get(file_id) {
+                Some(Some(id)) => Ok(*id),
+                Some(None) => self.missing_state
+                    .ok_or_else(|| RegionError::MissingState(self.file_states[file_id].clone())),
+                None => Err(corrupt("the palette has an id missing from the id map")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // several missing states can load as the same block
+        Chunk::from_raw_parts(palette, bits, data)
+            .map(Some)
+            .ok_or_else(|| corrupt("the palette and the block data don't fit together"))
+    }
+
+    /// Saves a chunk, replacing the one at the same position, where `pos`
+    /// is taken relative to the region
+    pub fn save_chunk(&mut self, pos: ChunkPos, chunk: &Chunk) -> Result<(), RegionError> {
+        let (palette, bits, data) = chunk.raw_parts();
+        let known = self.file_states.len();
+        let palette = palette.iter()
+            .map(|id| self.file_id(*id))
+            .collect::<Result<Vec<_>, _>>()?;
+        if self.file_states.len() != known {
+            self.write_id_map()?;
+        }
+        let stored = self.compression.store(&encode_chunk(&palette, bits, data));
+        self.write_entry(Self::slot(pos), &stored)
+    }
+
+    /// Removes a chunk from the file, freeing its sectors
+    pub fn remove_chunk(&mut self, pos: ChunkPos) -> Result<(), RegionError> {
+        let slot = Self::slot(pos);
+        self.entries[slot] = Entry::default();
+        self.write_index(slot)
+    }
+
+    /// Every chunk in the file, relative to the region
+    pub fn chunks(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.entries[1..].iter()
+            .enumerate()
+            .filter(|(_, entry)| !entry.is_empty())
+            .map(|(index, _)| chunk_of_slot(index + 1))
+    }
+
+    fn slot(pos: ChunkPos) -> usize {
+        slot_of(local_chunk_pos(pos))
+    }
+
+    /// The id in the file of a table id, adding it to the id map if it's new
+    fn file_id(&mut self, id: usize) -> Result<usize, RegionError> {
+        if let Some(file_id) = self.to_file.get(&id) {
+            return Ok(*file_id);
+        }
+        if id >= I::get_id_span() {
+            return Err(RegionError::UnknownId(id));
+        }
+        let file_id = self.file_states.len();
+        self.file_states.push(I::from_id(id).to_string());
+        self.to_table.push(Some(id));
+        self.to_file.insert(id, file_id);
+        Ok(file_id)
+    }
+
+    fn write_id_map(&mut self) -> Result<(), RegionError> {
+        let stored = self.compression.store(&encode_id_map(&self.file_states));
+        self.write_entry(0, &stored)
+    }
+
+    /// Writes stored bytes where they fit, then points an entry at them
+    fn write_entry(&mut self, slot: usize, stored: &[u8]) -> Result<(), RegionError> {
+        let sectors = stored.len().div_ceil(SECTOR_SIZE) as u32;
+        let old = self.entries[slot];
+        let sector = if !old.is_empty() && old.sectors >= sectors {
+            old.sector
+        } else {
+            self.allocate(sectors, slot)
+        };
+        let mut padded = stored.to_vec();
+        padded.resize(sectors as usize * SECTOR_SIZE, 0);
+        self.file.seek(SeekFrom::Start(sector as u64 * SECTOR_SIZE as u64))?;
+        self.file.write_all(&padded)?;
+        self.entries[slot] = Entry {
+            sector,
+            sectors,
+            length: stored.len() as u32,
+            checksum: crc32(stored),
+        };
+        self.write_index(slot)
+    }
+
+    fn write_index(&mut self, slot: usize) -> Result<(), RegionError> {
+        self.file.seek(SeekFrom::Start((8 + slot * ENTRY_SIZE) as u64))?;
+        self.file.write_all(&self.entries[slot].to_bytes())?;
+        Ok(())
+    }
+
+    /// The first run of free sectors long enough, ignoring the sectors of `slot`
+    fn allocate(&self, sectors: u32, slot: usize) -> u32 {
+        let mut used: Vec<(u32, u32)> = self.entries.iter()
+            .enumerate()
+            .filter(|(other, entry)| *other != slot && !entry.is_empty())
+            .map(|(_, entry)| (entry.sector, entry.sector + entry.sectors))
+            .collect();
+        used.sort();
+        let mut free = HEADER_SECTORS;
+        for (start, end) in used {
+            if start >= free + sectors {
+                break;
+            }
+            free = free.max(end);
+        }
+        free
+    }
+}
+
+/// Every region of a world, as region files in one directory.
+///
+/// Regions are opened as their chunks are first used and kept open.
+pub struct RegionStore<I> {
+    dir: PathBuf,
+    regions: HashMap<RegionPos, Region<I>>,
+    compression: Compression,
+    missing_state: Option<usize>,
+}
+
+impl<I: AsId> RegionStore<I> {
+    /// Opens a directory of region files, creating it if it doesn't exist
+    pub fn open(dir: impl AsRef<Path>) -> Result<RegionStore<I>, RegionError> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(RegionStore {
+            dir: dir.as_ref().to_path_buf(),
+            regions: HashMap::new(),
+            compression: Compression::default(),
+            missing_state: None,
+        })
+    }
+
+    /// Sets how chunks are compressed when they're saved
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Loads states saved in region files that the table no longer has as `block`.
+    ///
+    /// By default, loading a chunk with any of them fails with [`RegionError::MissingState`].
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, chunk::Chunk, region::{RegionError, RegionStore}, AsId};
+    ///
+    /// mod old {
+    /// #   use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, AsId};
+    ///     table!(BlockType, enum BlockId, static BLOCKS = {
+    ///         let Stone: Basic = Block::new_basic("stone");
+    ///         let Moss: Basic = Block::new_basic("moss");
+    ///         let Lichen: Basic = Block::new_basic("lichen");
+    ///     });
+    /// }
+    ///
+    /// // moss and lichen were removed
+    /// table!(BlockType, enum BlockId, static BLOCKS = {
+    ///     let Stone: Basic = Block::new_basic("stone");
+    ///     let Gravel: Basic = Block::new_basic("gravel");
+    /// });
+    ///
+    /// let dir = std::env::temp_dir().join("opentale_blocks_missing_state_example");
+    /// let _ = std::fs::remove_dir_all(&dir);
+    ///
+    /// let mut chunk = Chunk::filled(old::BlockId::Air(()).to_id());
+    /// chunk.set_id([0, 0, 0], &old::BlockId::Moss(()));
+    /// chunk.set_id([1, 0, 0], &old::BlockId::Lichen(()));
+    /// RegionStore::<old::BlockId>::open(&dir).unwrap().save_chunk([0, 0, 0], &chunk).unwrap();
+    ///
+    /// let mut store = RegionStore::<BlockId>::open(&dir).unwrap();
+    /// assert!(matches!(store.load_chunk([0, 0, 0]), Err(RegionError::MissingState(_))));
+    ///
+    /// let mut store = RegionStore::open(&dir).unwrap().with_missing_state(&BlockId::Gravel(()));
+    /// let loaded = store.load_chunk([0, 0, 0]).unwrap().unwrap();
+    /// assert_eq!(loaded.get([0, 0, 0]), BlockId::Gravel(()).to_id());
+    /// assert_eq!(loaded.get([1, 0, 0]), BlockId::Gravel(()).to_id());
+    /// // both missing states share one palette entry
+    /// assert_eq!(loaded.palette().len(), 2);
+    /// ```
+    pub fn with_missing_state(mut self, block: &I) -> Self {
+        self.missing_state = Some(block.to_id());
+        self
+    }
+
+    /// The file of the region containing a chunk
+    pub fn region_path(&self, chunk: ChunkPos) -> PathBuf {
+        let [x, y, z] = region_pos(chunk);
+        self.dir.join(format!("r.{x}.{y}.{z}.otr"))
+    }
+
+    /// Loads a chunk, returning `None` if it was never saved
+    pub fn load_chunk(&mut self, pos: ChunkPos) -> Result<Option<Chunk>, RegionError> {
+        match self.region(pos, false)? {
+            Some(region) => region.load_chunk(pos),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_chunk(&mut self, pos: ChunkPos, chunk: &Chunk) -> Result<(), RegionError> {
+        self.region(pos, true)?.expect("regions are created when saving").save_chunk(pos, chunk)
+    }
+
+    /// Saves every chunk of a grid
+    pub fn save_grid(&mut self, grid: &ChunkGrid) -> Result<(), RegionError> {
+        for (pos, chunk) in grid.chunks() {
+            self.save_chunk(pos, chunk)?;
+        }
+        Ok(())
+    }
+
+    fn region(&mut self, chunk: ChunkPos, create: bool) -> Result<Option<&mut Region<I>>, RegionError> {
+        let pos = region_pos(chunk);
+        if !self.regions.contains_key(&pos) {
+            let path = self.region_path(chunk);
+            if !create && !path.exists() {
+                return Ok(None);
+            }
+            let mut region = Region::open(path)?.with_compression(self.compression);
+            region.missing_state = self.missing_state;
+            self.regions.insert(pos, region);
+        }
+        Ok(self.regions.get_mut(&pos))
+    }
+}
+
+/// Something wrong with a region file, found by [`scan`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Corruption {
+    /// The chunk relative to the region, or `None` for the id map
+    pub chunk: Option<[usize; 3]>,
+    /// The first sector of the damaged data
+    pub sector: u32,
+    pub kind: CorruptionKind,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CorruptionKind {
+    /// The index points into the header or past the end of the file
+    OutOfBounds,
+    /// The stored bytes don't match their checksum
+    Checksum,
+    /// The bytes match their checksum, but can't be decompressed or decoded
+    Unreadable(String),
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.chunk {
+            Some([x, y, z]) => write!(f, "chunk {x} {y} {z}")?,
+            None => write!(f, "id map")?,
+        }
+        write!(f, " at sector {}: ", self.sector)?;
+        match &self.kind {
+            CorruptionKind::OutOfBounds => write!(f, "stored outside of the file"),
+            CorruptionKind::Checksum => write!(f, "checksum mismatch"),
+            CorruptionKind::Unreadable(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// What [`scan`] found in a region file
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ScanReport {
+    /// The number of chunks that can be loaded
+    pub chunks: usize,
+    pub problems: Vec<Corruption>,
+}
+
+/// Checks every chunk of a region file and its id map against their
+/// checksums, and that they can be decompressed and decoded.
+///
+/// Chunks compressed in a way this build doesn't support are only checked
+/// against their checksums.
+pub fn scan(path: impl AsRef<Path>) -> Result<ScanReport, RegionError> {
+    let mut file = File::open(path)?;
+    let entries = read_header(&mut file)?;
+    let mut report = ScanReport::default();
+    let problem = |slot: usize, kind| {
+        let chunk = (slot != 0).then(|| chunk_of_slot(slot));
+        Corruption { chunk, sector: entries[slot].sector, kind }
+    };
+
+    let mut ids = None;
+    match read_entry(&mut file, entries[0], MAX_ID_MAP_SIZE) {
+        Ok(stored) => match decode_id_map(&stored) {
+            Ok(states) => ids = Some(states.len()),
+            Err(message) => report.problems.push(problem(0, CorruptionKind::Unreadable(message))),
+        },
+        Err(ReadError::Corrupt(kind)) => report.problems.push(problem(0, kind)),
+        Err(ReadError::Unsupported(_)) => {}
+        Err(ReadError::Io(error)) => return Err(error.into()),
+    }
+
+    for (slot, entry) in entries.iter().enumerate().skip(1) {
+        if entry.is_empty() {
+            continue;
+        }
+        let checked = match read_entry(&mut file, *entry, MAX_CHUNK_SIZE) {
+            Ok(stored) => decode_chunk(&stored).and_then(|(palette, bits, data)| {
+                if palette.iter().any(|file_id| ids.is_some_and(|ids| *file_id >= ids)) {
+                    return Err("the palette has an id missing from the id map".to_string());
+                }
+                Chunk::from_raw_parts(palette, bits, data)
+                    .map(|_| ())
+                    .ok_or_else(|| "the palette and the block data don't fit together".to_string())
+            }).map_err(CorruptionKind::Unreadable),
+            Err(ReadError::Corrupt(kind)) => Err(kind),
+            Err(ReadError::Unsupported(_)) => Ok(()),
+            Err(ReadError::Io(error)) => return Err(error.into()),
+        };
+        match checked {
+            Ok(()) => report.chunks += 1,
+            Err(kind) => report.problems.push(problem(slot, kind)),
+        }
+    }
+    Ok(report)
+}
+
+/// Removes every corrupt chunk from a region file, returning what [`scan`] found.
+///
+/// Fails without changing the file if its id map is corrupt, since none of
+/// its chunks could be loaded then.
+pub fn repair(path: impl AsRef<Path>) -> Result<ScanReport, RegionError> {
+    let path = path.as_ref();
+    let report = scan(path)?;
+    if let Some(problem) = report.problems.iter().find(|problem| problem.chunk.is_none()) {
+        return Err(RegionError::Corrupt(problem.clone()));
+    }
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    for problem in &report.problems {
+        let slot = slot_of(problem.chunk.expect("only chunks are left"));
+        file.seek(SeekFrom::Start((8 + slot * ENTRY_SIZE) as u64))?;
+        file.write_all(&Entry::default().to_bytes())?;
+    }
+    Ok(report)
+}
+
+/// The header entry of a chunk relative to its region, after the id map's,
+/// ordered by x, then z, then y like blocks in a chunk
+fn slot_of([x, y, z]: [usize; 3]) -> usize {
+    1 + x + z * REGION_SIZE + y * REGION_SIZE * REGION_SIZE
+}
+
+fn chunk_of_slot(slot: usize) -> [usize; 3] {
+    let index = slot - 1;
+    [index % REGION_SIZE, index / (REGION_SIZE * REGION_SIZE), (index / REGION_SIZE) % REGION_SIZE]
+}
+
+fn read_header(file: &mut File) -> Result<Vec<Entry>, RegionError> {
+    let mut header = vec![0; HEADER_SIZE];
+    file.seek(SeekFrom::Start(0))?;
+    match file.read_exact(&mut header) {
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Err(RegionError::NotARegion),
+        result => result?,
+    }
+    if header[..4] != MAGIC {
+        return Err(RegionError::NotARegion);
+    }
+    let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    if version != VERSION {
+        return Err(RegionError::UnsupportedVersion(version));
+    }
+    Ok(header[8..].chunks_exact(ENTRY_SIZE).map(Entry::from_bytes).collect())
+}
+
+enum ReadError {
+    Io(io::Error),
+    Corrupt(CorruptionKind),
+    /// Compressed in a way this build doesn't support
+    Unsupported(u8),
+}
+
+impl ReadError {
+    fn at(self, chunk: Option<[usize; 3]>, entry: Entry) -> RegionError {
+        match self {
+            ReadError::Io(error) => RegionError::Io(error),
+            ReadError::Corrupt(kind) => RegionError::Corrupt(Corruption { chunk, sector: entry.sector, kind }),
+            ReadError::Unsupported(tag) => RegionError::UnsupportedCompression(tag),
+        }
+    }
+}
+
+/// Reads what an entry points to, checks it against its checksum and decompresses it
+fn read_entry(file: &mut File, entry: Entry, limit: u64) -> Result<Vec<u8>, ReadError> {
+    let start = entry.sector as u64 * SECTOR_SIZE as u64;
+    let file_len = file.metadata().map_err(ReadError::Io)?.len();
+    if entry.sector < HEADER_SECTORS
+        || entry.length == 0
+        || entry.length as u64 > entry.sectors as u64 * SECTOR_SIZE as u64
+        || start + entry.length as u64 > file_len
+    {
+        return Err(ReadError::Corrupt(CorruptionKind::OutOfBounds));
+    }
+    let mut stored = vec![0; entry.length as usize];
+    file.seek(SeekFrom::Start(start)).map_err(ReadError::Io)?;
+    file.read_exact(&mut stored).map_err(ReadError::Io)?;
+    if crc32(&stored) != entry.checksum {
+        return Err(ReadError::Corrupt(CorruptionKind::Checksum));
+    }
+    Compression::load(&stored, limit)
+}
+
+fn encode_id_map(states: &[String]) -> Vec<u8> {
+    let mut bytes = vec![];
+    write_varint(&mut bytes, states.len());
+    for state in states {
+        write_varint(&mut bytes, state.len());
+        bytes.extend_from_slice(state.as_bytes());
+    }
+    bytes
+}
+
+fn decode_id_map(bytes: &[u8]) -> Result<Vec<String>, String> {
+    let mut reader = ByteReader(bytes);
+    let len = reader.varint()?;
+    let mut states = vec![];
+    for _ in 0..len {
+        let state_len = reader.varint()?;
+        let state = std::str::from_utf8(reader.take(state_len)?).map_err(|error| error.to_string())?;
+        states.push(state.to_string());
+    }
+    Ok(states)
+}
+
+fn encode_chunk(palette: &[usize], bits: u32, data: &[u64]) -> Vec<u8> {
+    let mut bytes = vec![];
+    write_varint(&mut bytes, palette.len());
+    for id in palette {
+        write_varint(&mut bytes, *id);
+    }
+    bytes.push(bits as u8);
+    for word in data {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+fn decode_chunk(bytes: &[u8]) -> Result<(Vec<usize>, u32, Vec<u64>), String> {
+    let mut reader = ByteReader(bytes);
+    let len = reader.varint()?;
+    let palette = (0..len).map(|_| reader.varint()).collect::<Result<Vec<_>, _>>()?;
+    let bits = reader.take(1)?[0] as u32;
+    if reader.0.len() % 8 != 0 {
+        return Err("the block data isn't a whole number of words".to_string());
+    }
+    let data = reader.0.chunks_exact(8).map(|word| u64::from_le_bytes(word.try_into().unwrap())).collect();
+    Ok((palette, bits, data))
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl ByteReader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        if self.0.len() < len {
+            return Err("ends too early".to_string());
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<usize, String> {
+        let mut value = 0usize;
+        for shift in (0..35).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("a number is too large".to_string())
+    }
+}
+
+/// The CRC-32 used by zlib and PNG
+fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+    !bytes.iter().fold(!0, |crc, byte| TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+/// Why a region file couldn't be read or written
+#[derive(Debug)]
+pub enum RegionError {
+    Io(io::Error),
+    /// The file doesn't start with a region header
+    NotARegion,
+    UnsupportedVersion(u32),
+    /// The data is compressed in a way that needs a feature this build doesn't have
+    UnsupportedCompression(u8),
+    Corrupt(Corruption),
+    /// A chunk has an id past the end of the table
+    UnknownId(usize),
+    /// A chunk has a state the table no longer has, and no block was chosen to load it as
+    MissingState(String),
+}
+
+impl From<io::Error> for RegionError {
+    fn from(error: io::Error) -> Self {
+        RegionError::Io(error)
+    }
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionError::Io(error) => write!(f, "couldn't access region file: {error}"),
+            RegionError::NotARegion => write!(f, "not a region file"),
+            RegionError::UnsupportedVersion(version) => write!(f, "unsupported region version {version}"),
+            RegionError::UnsupportedCompression(tag) => {
+                write!(f, "compression {tag} isn't supported, it may need the `deflate` or `zstd` feature")
+            }
+            RegionError::Corrupt(corruption) => write!(f, "corrupt region file: {corruption}"),
+            RegionError::UnknownId(id) => write!(f, "{id} isn't an id of the table"),
+            RegionError::MissingState(state) => write!(f, "the table no longer has the state `{state}`"),
+        }
+    }
+}
+
+impl std::error::Error for RegionError {}
@@ -0,0 +1,327 @@
+//! Translating between schematic block states and the ids of a table.
+
+use std::{collections::HashMap, fmt, marker::PhantomData, ops::Range};
+
+use crate::{blocks::{block::FaceDir, block_types::BlockType, geometry::BlockTransform}, model::face_name, table::Table, AsId};
+
+/// How the block and property names of a schematic differ from a table's.
+///
+/// Block names are matched against [`BlockType::name`] once the namespace
+/// is stripped, and properties against the `name: value` pairs of the
+/// table's state enums, so only the names that differ need an alias.
+///
+/// ### Example
+///
+/// ```rust
+/// # use opentale_blocks::schematic::Aliases;
+///
+/// let aliases = Aliases::new()
+///     .with_name("oak_log", "log")
+///     .with_property("half", "spin")
+///     .with_value("half=bottom", "spin: up")
+///     .with_value("half=top", "spin: down");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Aliases {
+    namespace: String,
+    /// Schematic block names and the table names they stand for
+    names: Vec<(String, String)>,
+    properties: Vec<(String, String)>,
+    /// Schematic properties with a value, and the table property and value they stand for
+    values: Vec<(Property, Property)>,
+}
+
+type Property = (String, String);
+
+impl Aliases {
+    /// No aliases, stripping the `minecraft` namespace from block names
+    pub fn new() -> Aliases {
+        Aliases {
+            namespace: "minecraft".to_string(),
+            names: vec![],
+            properties: vec![],
+            values: vec![],
+        }
+    }
+
+    /// The namespace stripped from block names when reading,
+    /// and added to them when writing
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = namespace.to_string();
+        self
+    }
+
+    /// Reads the block `schematic`, with or without the namespace, as the table block `table`
+    pub fn with_name(mut self, schematic: &str, table: &str) -> Self {
+        self.names.push((schematic.to_string(), table.to_string()));
+        self
+    }
+
+    /// Reads the property `schematic` as the table property `table`, keeping its value
+    pub fn with_property(mut self, schematic: &str, table: &str) -> Self {
+        self.properties.push((schematic.to_string(), table.to_string()));
+        self
+    }
+
+    /// Reads a `property=value` of the schematic as a `property: value` of the table.
+    ///
+    /// Panics if either isn't of that form.
+    pub fn with_value(mut self, schematic: &str, table: &str) -> Self {
+        let split = |text: &str, separator: &str| {
+            let (property, value) = text.split_once(separator)
+                .unwrap_or_else(|| panic!("expected `property{separator}value`, got `{text}`"));
+            (property.trim().to_string(), value.trim().to_string())
+        };
+        self.values.push((split(schematic, "="), split(table, ":")));
+        self
+    }
+
+    fn table_name<'a>(&'a self, name: &'a str) -> &'a str {
+        let stripped = name.strip_prefix(&self.namespace)
+            .and_then(|name| name.strip_prefix(':'))
+            .unwrap_or(name);
+        self.names.iter()
+            .find(|(schematic, _)| schematic == name || schematic == stripped)
+            .map_or(stripped, |(_, table)| table)
+    }
+
+    fn schematic_name(&self, name: &str) -> String {
+        let name = self.names.iter()
+            .find(|(_, table)| table == name)
+            .map_or(name, |(schematic, _)| schematic);
+        if name.contains(':') {
+            name.to_string()
+        } else {
+            format!("{}:{name}", self.namespace)
+        }
+    }
+
+    fn table_property(&self, property: &str, value: &str) -> Property {
+        if let Some((_, table)) = self.values.iter().find(|((p, v), _)| p == property && v == value) {
+            return table.clone();
+        }
+        let property = self.properties.iter()
+            .find(|(schematic, _)| schematic == property)
+            .map_or(property, |(_, table)| table);
+        (property.to_string(), value.to_string())
+    }
+
+    fn schematic_property(&self, property: &str, value: &str) -> Property {
+        if let Some((schematic, _)) = self.values.iter().find(|(_, (p, v))| p == property && v == value) {
+            return schematic.clone();
+        }
+        let property = self.properties.iter()
+            .find(|(_, table)| table == property)
+            .map_or(property, |(schematic, _)| schematic);
+        (property.to_string(), value.to_string())
+    }
+}
+
+impl Default for Aliases {
+    fn default() -> Self {
+        Aliases::new()
+    }
+}
+
+/// A schematic block state that has no id in the table
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Unmapped {
+    /// The state as written in the schematic, like `minecraft:oak_stairs[half=top]`
+    pub state: String,
+    pub reason: UnmappedReason,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum UnmappedReason {
+    /// No block of the table has the state's name
+    UnknownBlock,
+    /// The block has the property, but none of its states have the value
+    UnknownValue { property: String, value: String },
+    /// The state isn't of the form `name[property=value,...]`
+    Malformed,
+}
+
+impl fmt::Display for Unmapped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            UnmappedReason::UnknownBlock => write!(f, "`{}`: unknown block", self.state),
+            UnmappedReason::UnknownValue { property, value } => {
+                write!(f, "`{}`: the block has no state with {property}={value}", self.state)
+            }
+            UnmappedReason::Malformed => write!(f, "`{}`: expected `name[property=value,...]`", self.state),
+        }
+    }
+}
+
+/// The name and properties of a state id, as the table writes them
+struct State {
+    name: &'static str,
+    properties: Vec<Property>,
+}
+
+/// Converts between the block states of schematics and the ids of a table.
+///
+/// `I` is the enum of the table, whose `to_string` gives the properties of
+/// every state.  A schematic state maps to the first state of its block
+/// that has every property it sets.  Properties the block doesn't have,
+/// like `waterlogged`, are ignored, and properties it has but doesn't set
+/// keep the value of the block's first state.
+pub struct StateMapping<I> {
+    aliases: Aliases,
+    /// The state of every id, `None` for air
+    states: Vec<Option<State>>,
+    /// The ids of the states of every block, by name
+    blocks: HashMap<&'static str, Range<usize>>,
+    table: PhantomData<fn() -> I>,
+}
+
+impl<I: AsId> StateMapping<I> {
+    pub fn new<B: 'static + ?Sized + BlockType>(table: &Table<B>, aliases: Aliases) -> StateMapping<I> {
+        let states = (0..table.id_span())
+            .map(|id| {
+                let (block, _) = table.block_of(id)?;
+                Some(State { name: block.name(), properties: Self::properties(id) })
+            })
+            .collect();
+        let blocks = table.iter()
+            .map(|block| (block.name(), block.id()..block.id() + block.id_span()))
+            .collect();
+        StateMapping { aliases, states, blocks, table: PhantomData }
+    }
+
+    /// The properties of a state, from `Name[property: value, ...]`
+    fn properties(id: usize) -> Vec<Property> {
+        let text = I::from_id(id).to_string();
+        let Some(inner) = text.split_once('[').and_then(|(_, inner)| inner.strip_suffix(']')) else {
+            return vec![];
+        };
+        inner.split(", ")
+            .filter_map(|property| property.split_once(": "))
+            .map(|(property, value)| (property.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// The id of a schematic block state like `minecraft:oak_stairs[facing=east,half=top]`
+    pub fn id_of(&self, state: &str) -> Result<usize, Unmapped> {
+        let unmapped = |reason| Unmapped { state: state.to_string(), reason };
+        let (name, properties) = match state.split_once('[') {
+            Some((name, properties)) => {
+                let properties = properties.strip_suffix(']').ok_or_else(|| unmapped(UnmappedReason::Malformed))?;
+                (name, properties)
+            }
+            None => (state, ""),
+        };
+        let properties = properties.split(',')
+            .filter(|property| !property.trim().is_empty())
+            .map(|property| {
+                let (property, value) = property.split_once('=').ok_or_else(|| unmapped(UnmappedReason::Malformed))?;
+                Ok(((property.trim(), value.trim()), self.aliases.table_property(property.trim(), value.trim())))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let name = self.aliases.table_name(name.trim());
+        let Some(ids) = self.blocks.get(name) else {
+            // anything called air is air, unless the table has a block by that name
+            return match self.states.iter().position(Option::is_none) {
+                Some(air) if name == "air" => Ok(air),
+                _ => Err(unmapped(UnmappedReason::UnknownBlock)),
+            };
+        };
+
+        let has = |id: usize, property: &Property| {
+            self.state(id).properties.iter().any(|p| p == property)
+        };
+        let mut wanted = vec![];
+        for ((property, value), mapped) in properties {
+            let known = self.state(ids.start).properties.iter().any(|(p, _)| *p == mapped.0);
+            if !known {
+                continue;
+            }
+            if !ids.clone().any(|id| has(id, &mapped)) {
+                return Err(unmapped(UnmappedReason::UnknownValue {
+                    property: property.to_string(),
+                    value: value.to_string(),
+                }));
+            }
+            wanted.push(mapped);
+        }
+        Ok(ids.clone().find(|id| wanted.iter().all(|property| has(*id, property))).unwrap_or(ids.start))
+    }
+
+    pub fn get_id(&self, state: &str) -> Result<I, Unmapped> {
+        self.id_of(state).map(I::from_id)
+    }
+
+    /// The schematic block state of an id, the reverse of [`StateMapping::id_of`]
+    pub fn state_of(&self, id: usize) -> String {
+        let Some(Some(state)) = self.states.get(id) else {
+            return self.aliases.schematic_name("air");
+        };
+        let name = self.aliases.schematic_name(state.name);
+        if state.properties.is_empty() {
+            return name;
+        }
+        let properties: Vec<_> = state.properties.iter()
+            .map(|(property, value)| {
+                let (property, value) = self.aliases.schematic_property(property, value);
+                format!("{property}={value}")
+            })
+            .collect();
+        format!("{name}[{}]", properties.join(","))
+    }
+
+    /// The state a block turns into when its surroundings are rotated or mirrored.
+    ///
+    /// Transforms the `facing`, `direction`, `axis`, `spin`, slab `type`
+    /// and stair `shape` properties, and returns `id` unchanged if the
+    /// block has no state matching the result, like a block that only
+    /// turns around the Y axis being turned on its side.
+    pub fn transform(&self, id: usize, transform: BlockTransform) -> usize {
+        let Some(Some(state)) = self.states.get(id) else {
+            return id;
+        };
+        let flipped = transform.apply_face(FaceDir::Up) == FaceDir::Down;
+        // flipping a block upside down is itself a reflection, so only
+        // mirroring left and right if that doesn't account for it
+        let mirrored = transform.is_reflection() != flipped;
+        let upright = matches!(transform.apply_face(FaceDir::Up), FaceDir::Up | FaceDir::Down);
+        let properties: Option<Vec<Property>> = state.properties.iter()
+            .map(|(property, value)| {
+                let value = match (property.as_str(), value.as_str()) {
+                    ("facing" | "direction", face) => {
+                        let face = FaceDir::ALL.into_iter().find(|f| face_name(*f) == face)?;
+                        face_name(transform.apply_face(face)).to_string()
+                    }
+                    ("axis", axis) => {
+                        let index = ["x", "y", "z"].iter().position(|a| *a == axis)?;
+                        let mut vector = [0; 3];
+                        vector[index] = 1;
+                        let turned = transform.apply_vector(vector);
+                        ["x", "y", "z"][turned.iter().position(|c| *c != 0)?].to_string()
+                    }
+                    ("spin" | "type", _) if !upright => return None,
+                    ("spin", "up") if flipped => "down".to_string(),
+                    ("spin", "down") if flipped => "up".to_string(),
+                    ("type", "bottom") if flipped => "top".to_string(),
+                    ("type", "top") if flipped => "bottom".to_string(),
+                    ("shape", shape) if mirrored => match shape.rsplit_once('_') {
+                        Some((kind, "left")) => format!("{kind}_right"),
+                        Some((kind, "right")) => format!("{kind}_left"),
+                        _ => shape.to_string(),
+                    },
+                    (_, value) => value.to_string(),
+                };
+                Some((property.clone(), value))
+            })
+            .collect();
+        let Some(properties) = properties else {
+            return id;
+        };
+        let ids = &self.blocks[state.name];
+        ids.clone().find(|id| self.state(*id).properties == properties).unwrap_or(id)
+    }
+
+    fn state(&self, id: usize) -> &State {
+        self.states[id].as_ref().expect("only blocks have states")
+    }
+}
@@ -0,0 +1,373 @@
+//! Reading and writing prefabs as Sponge schematics (`.schem` files).
+//!
+//! A [`Schematic`] is a box of blocks whose states are written the way
+//! Minecraft writes them, like `minecraft:oak_stairs[facing=east,half=top]`.
+//! A [`StateMapping`] converts these to and from the ids of a table, using
+//! [`Aliases`] for the names and properties that differ, and reports every
+//! state it can't convert.  Versions 1 to 3 of the format can be read, and
+//! schematics are written as version 2.  Block entity data isn't read or
+//! written.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, full::Full, rotateable::{Rotateable, RotDir, AxisRotFacing}, reflectable::ReflectionFacing, rotateable::YRotFacing, stair::{Stair, StairShape}, BlockType}, geometry::BlockTransform}, chunk::ChunkGrid, schematic::{Aliases, Schematic, StateMapping, UnmappedReason}, AsId};
+//!
+//! table!(BlockType, #[derive(PartialEq, Debug)] enum BlockId, static BLOCKS = {
+//!     let Stone: Basic = Block::new_basic("stone");
+//!     let Log: Rotateable<Full, { RotDir::Axis }> = Block::new_rotateable(Block::new_full("log"));
+//!     let WoodStairs: Stair = Block::new_stair("wood_stairs");
+//! });
+//!
+//! let mapping = StateMapping::<BlockId>::new(&BLOCKS, Aliases::new()
+//!     .with_name("oak_log", "log")
+//!     .with_name("oak_stairs", "wood_stairs")
+//!     .with_value("half=bottom", "spin: up")
+//!     .with_value("half=top", "spin: down"));
+//!
+//! let stair = mapping.get_id("minecraft:oak_stairs[facing=east,half=top,shape=inner_left,waterlogged=false]").unwrap();
+//! assert_eq!(stair, BlockId::WoodStairs((ReflectionFacing::Down, (YRotFacing::East, (StairShape::InnerLeft, ())))));
+//! assert_eq!(mapping.state_of(stair.to_id()), "minecraft:oak_stairs[half=top,facing=east,shape=inner_left]");
+//! assert_eq!(mapping.id_of("minecraft:glass").unwrap_err().reason, UnmappedReason::UnknownBlock);
+//!
+//! // copy a log lying along x and a stair, then paste them turned a quarter turn
+//! let mut world = ChunkGrid::new(BlockId::Air(()).to_id());
+//! world.set_id([10, 0, 10], &BlockId::Log((AxisRotFacing::X, ())));
+//! world.set_id([11, 0, 10], &BlockId::WoodStairs((ReflectionFacing::Up, (YRotFacing::North, (StairShape::Straight, ())))));
+//! let schematic = Schematic::copy(&world, [10, 0, 10], [2, 1, 1], &mapping);
+//! assert_eq!(schematic.get([0, 0, 0]), "minecraft:oak_log[axis=x]");
+//!
+//! // it survives being written and read back
+//! let schematic = Schematic::from_bytes(&schematic.to_bytes()).unwrap();
+//! let unmapped = schematic.paste(&mut world, [0, 0, 0], BlockTransform::rotate_y(1), &mapping);
+//! assert!(unmapped.is_empty());
+//! assert_eq!(world.get_id::<BlockId>([0, 0, 0]), BlockId::Log((AxisRotFacing::Z, ())));
+//! assert_eq!(world.get_id::<BlockId>([0, 0, 1]), BlockId::WoodStairs((ReflectionFacing::Up, (YRotFacing::East, (StairShape::Straight, ())))));
+//!
+//! // states the table doesn't have are reported and left out
+//! let mut other = Schematic::new([1, 1, 1]);
+//! other.set([0, 0, 0], "minecraft:oak_log[axis=w]");
+//! let unmapped = other.paste(&mut world, [5, 5, 5], BlockTransform::IDENTITY, &mapping);
+//! assert_eq!(unmapped[0].reason, UnmappedReason::UnknownValue { property: "axis".to_string(), value: "w".to_string() });
+//! assert_eq!(world.get([5, 5, 5]), 0);
+//! ```
+
+use std::{fmt, io::{self, Read, Write}, path::Path};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::{behavior::World, blocks::geometry::BlockTransform, chunk::BlockPos, AsId};
+
+mod mapping;
+mod nbt;
+
+pub use mapping::{Aliases, StateMapping, Unmapped, UnmappedReason};
+
+use nbt::{Compound, Tag};
+
+/// The Minecraft data version written to schematics unless another one is set, 1.20.4's
+pub const DEFAULT_DATA_VERSION: i32 = 3700;
+
+/// A box of blocks, stored as indices into a palette of block states
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Schematic {
+    /// The size along x, y and z, which the format calls width, height and length
+    size: [usize; 3],
+    /// Added to the position of every block when pasting
+    offset: BlockPos,
+    data_version: i32,
+    /// Block states like `minecraft:stone`
+    palette: Vec<String>,
+    /// The palette index of every block, ordered by x, then z, then y
+    blocks: Vec<usize>,
+}
+
+impl Schematic {
+    /// A schematic filled with air.
+    ///
+    /// Panics if a side is longer than 65535 blocks, the most the format can store.
+    pub fn new(size: [usize; 3]) -> Schematic {
+        assert!(size.iter().all(|side| *side <= u16::MAX as usize), "schematics can't be larger than 65535 blocks on a side");
+        Schematic {
+            size,
+            offset: [0; 3],
+            data_version: DEFAULT_DATA_VERSION,
+            palette: vec!["minecraft:air".to_string()],
+            blocks: vec![0; size.iter().product()],
+        }
+    }
+
+    pub fn size(&self) -> [usize; 3] {
+        self.size
+    }
+
+    pub fn offset(&self) -> BlockPos {
+        self.offset
+    }
+
+    /// Sets the offset added to the position of every block when pasting
+    pub fn set_offset(&mut self, offset: BlockPos) {
+        self.offset = offset;
+    }
+
+    pub fn data_version(&self) -> i32 {
+        self.data_version
+    }
+
+    pub fn set_data_version(&mut self, data_version: i32) {
+        self.data_version = data_version;
+    }
+
+    /// Every distinct block state in the schematic
+    pub fn palette(&self) -> &[String] {
+        &self.palette
+    }
+
+    /// The block state at a position in the schematic
+    pub fn get(&self, pos: [usize; 3]) -> &str {
+        &self.palette[self.blocks[self.index(pos)]]
+    }
+
+    /// Sets the block state at a position in the schematic
+    pub fn set(&mut self, pos: [usize; 3], state: &str) {
+        let palette_index = match self.palette.iter().position(|entry| entry == state) {
+            Some(palette_index) => palette_index,
+            None => {
+                self.palette.push(state.to_string());
+                self.palette.len() - 1
+            }
+        };
+        let index = self.index(pos);
+        self.blocks[index] = palette_index;
+    }
+
+    fn index(&self, pos: [usize; 3]) -> usize {
+        assert!((0..3).all(|axis| pos[axis] < self.size[axis]), "{pos:?} is outside of the schematic");
+        pos[0] + pos[2] * self.size[0] + pos[1] * self.size[0] * self.size[2]
+    }
+
+    /// Every position in the schematic, in storage order
+    fn positions(&self) -> impl Iterator<Item = [usize; 3]> + use<> {
+        let [width, height, length] = self.size;
+        (0..height).flat_map(move |y| (0..length).flat_map(move |z| (0..width).map(move |x| [x, y, z])))
+    }
+
+    /// Copies the blocks of a world in the box starting at `min`
+    pub fn copy<I: AsId>(world: &dyn World, min: BlockPos, size: [usize; 3], mapping: &StateMapping<I>) -> Schematic {
+        let mut schematic = Schematic::new(size);
+        schematic.palette.clear();
+        let mut ids = vec![];
+        for (index, pos) in schematic.positions().enumerate() {
+            let id = world.get([0, 1, 2].map(|axis| min[axis] + pos[axis] as i32));
+            schematic.blocks[index] = match ids.iter().position(|known| *known == id) {
+                Some(palette_index) => palette_index,
+                None => {
+                    ids.push(id);
+                    schematic.palette.push(mapping.state_of(id));
+                    ids.len() - 1
+                }
+            };
+        }
+        schematic
+    }
+
+    /// The states of the palette that have no id in the table
+    pub fn unmapped<I: AsId>(&self, mapping: &StateMapping<I>) -> Vec<Unmapped> {
+        self.palette.iter().filter_map(|state| mapping.id_of(state).err()).collect()
+    }
+
+    /// Pastes the blocks into a world, with the schematic's origin at `origin`.
+    ///
+    /// Every block is moved by the offset, then by `transform` around the
+    /// origin, and its state transformed to match with
+    /// [`StateMapping::transform`].  Blocks whose state has no id in the
+    /// table are left out, and every such state is returned once.
+    pub fn paste<I: AsId>(
+        &self,
+        world: &mut dyn World,
+        origin: BlockPos,
+        transform: BlockTransform,
+        mapping: &StateMapping<I>
+    ) -> Vec<Unmapped> {
+        let mut unmapped = vec![];
+        let ids: Vec<_> = self.palette.iter()
+            .map(|state| match mapping.id_of(state) {
+                Ok(id) => Some(mapping.transform(id, transform)),
+                Err(error) => {
+                    unmapped.push(error);
+                    None
+                }
+            })
+            .collect();
+        for (pos, palette_index) in self.positions().zip(&self.blocks) {
+            let Some(id) = ids[*palette_index] else {
+                continue;
+            };
+            let moved = transform.apply_vector([0, 1, 2].map(|axis| pos[axis] as i32 + self.offset[axis]));
+            world.set([0, 1, 2].map(|axis| origin[axis] + moved[axis]), id);
+        }
+        unmapped
+    }
+
+    /// Reads a schematic, gzipped as `.schem` files are or not
+    pub fn from_bytes(bytes: &[u8]) -> Result<Schematic, SchematicError> {
+        let (_, root) = if bytes.starts_with(&[0x1f, 0x8b]) {
+            let mut decompressed = vec![];
+            GzDecoder::new(bytes).read_to_end(&mut decompressed).map_err(SchematicError::Io)?;
+            nbt::read(&decompressed)?
+        } else {
+            nbt::read(bytes)?
+        };
+        // version 3 puts everything in a compound inside of the root
+        let root = match root.get("Schematic") {
+            Some(Tag::Compound(schematic)) => schematic,
+            _ => &root,
+        };
+
+        let int = |compound: &Compound, name: &'static str| {
+            compound.get(name).and_then(Tag::as_int).ok_or(SchematicError::Missing(name))
+        };
+        let version = int(root, "Version")?;
+        if !(1..=3).contains(&version) {
+            return Err(SchematicError::UnsupportedVersion(version));
+        }
+        let side = |name| int(root, name).map(|side| side as u16 as usize);
+        let size = [side("Width")?, side("Height")?, side("Length")?];
+        let offset = match root.get("Offset") {
+            Some(Tag::IntArray(offset)) if offset.len() == 3 => [offset[0], offset[1], offset[2]],
+            _ => [0; 3],
+        };
+        let data_version = int(root, "DataVersion").map_or(DEFAULT_DATA_VERSION, |version| version as i32);
+
+        let (palette, data) = if version == 3 {
+            let Some(Tag::Compound(blocks)) = root.get("Blocks") else {
+                return Err(SchematicError::Missing("Blocks"));
+            };
+            (blocks.get("Palette"), blocks.get("Data"))
+        } else {
+            (root.get("Palette"), root.get("BlockData"))
+        };
+        let Some(Tag::Compound(palette)) = palette else {
+            return Err(SchematicError::Missing("Palette"));
+        };
+        let Some(Tag::ByteArray(data)) = data else {
+            return Err(SchematicError::Missing(if version == 3 { "Data" } else { "BlockData" }));
+        };
+
+        let mut states = vec![None; palette.len()];
+        for (state, index) in palette {
+            let slot = index.as_int()
+                .and_then(|index| states.get_mut(usize::try_from(index).ok()?))
+                .ok_or_else(|| SchematicError::Invalid(format!("`{state}` has an invalid palette index")))?;
+            *slot = Some(state.clone());
+        }
+        let palette = states.into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| SchematicError::Invalid("two states share a palette index".to_string()))?;
+
+        let blocks = read_varints(data)?;
+        if blocks.len() != size.iter().product::<usize>() {
+            return Err(SchematicError::Invalid(format!(
+                "expected {} blocks, found {}", size.iter().product::<usize>(), blocks.len()
+            )));
+        }
+        if let Some(index) = blocks.iter().find(|index| **index >= palette.len()) {
+            return Err(SchematicError::Invalid(format!("palette index {index} is out of range")));
+        }
+        Ok(Schematic { size, offset, data_version, palette, blocks })
+    }
+
+    /// Writes the schematic as a gzipped version 2 `.schem` file
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut root = Compound::new();
+        root.insert("Version".to_string(), Tag::Int(2));
+        root.insert("DataVersion".to_string(), Tag::Int(self.data_version));
+        root.insert("Width".to_string(), Tag::Short(self.size[0] as u16 as i16));
+        root.insert("Height".to_string(), Tag::Short(self.size[1] as u16 as i16));
+        root.insert("Length".to_string(), Tag::Short(self.size[2] as u16 as i16));
+        root.insert("Offset".to_string(), Tag::IntArray(self.offset.to_vec()));
+        root.insert("PaletteMax".to_string(), Tag::Int(self.palette.len() as i32));
+        let palette = self.palette.iter()
+            .enumerate()
+            .map(|(index, state)| (state.clone(), Tag::Int(index as i32)))
+            .collect();
+        root.insert("Palette".to_string(), Tag::Compound(palette));
+        root.insert("BlockData".to_string(), Tag::ByteArray(write_varints(&self.blocks)));
+        root.insert("BlockEntities".to_string(), Tag::List(vec![]));
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&nbt::write("Schematic", &root)).expect("writing to a Vec can't fail");
+        encoder.finish().expect("writing to a Vec can't fail")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Schematic, SchematicError> {
+        Schematic::from_bytes(&std::fs::read(path).map_err(SchematicError::Io)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+}
+
+/// Palette indices are stored as unsigned LEB128 varints
+fn read_varints(bytes: &[u8]) -> Result<Vec<usize>, SchematicError> {
+    let mut values = vec![];
+    let mut value = 0usize;
+    let mut shift = 0;
+    for byte in bytes {
+        if shift >= 32 {
+            return Err(SchematicError::Invalid("a palette index is too large".to_string()));
+        }
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            values.push(value);
+            value = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+        }
+    }
+    if shift != 0 {
+        return Err(SchematicError::Invalid("the block data ends in the middle of an index".to_string()));
+    }
+    Ok(values)
+}
+
+fn write_varints(values: &[usize]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for value in values {
+        let mut value = *value;
+        while value >= 0x80 {
+            bytes.push((value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+    bytes
+}
+
+/// Why a schematic couldn't be read
+#[derive(Debug)]
+pub enum SchematicError {
+    Io(io::Error),
+    /// A field the format requires is missing or has the wrong type
+    Missing(&'static str),
+    UnsupportedVersion(i64),
+    /// The file isn't valid NBT, or its fields don't fit together
+    Invalid(String),
+}
+
+impl fmt::Display for SchematicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchematicError::Io(error) => write!(f, "couldn't read schematic: {error}"),
+            SchematicError::Missing(field) => write!(f, "the schematic has no valid `{field}`"),
+            SchematicError::UnsupportedVersion(version) => write!(f, "unsupported schematic version {version}"),
+            SchematicError::Invalid(message) => write!(f, "invalid schematic: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SchematicError {}
@@ -0,0 +1,248 @@
+//! The parts of Minecraft's NBT format used by schematics.
+//!
+//! NBT is big endian, and every compound entry is a tag id, a name and
+//! a payload.  Strings are read as UTF-8, which covers every name a
+//! schematic palette uses.
+
+use std::collections::BTreeMap;
+
+use super::SchematicError;
+
+const END: u8 = 0;
+const BYTE: u8 = 1;
+const SHORT: u8 = 2;
+const INT: u8 = 3;
+const LONG: u8 = 4;
+const FLOAT: u8 = 5;
+const DOUBLE: u8 = 6;
+const BYTE_ARRAY: u8 = 7;
+const STRING: u8 = 8;
+const LIST: u8 = 9;
+const COMPOUND: u8 = 10;
+const INT_ARRAY: u8 = 11;
+const LONG_ARRAY: u8 = 12;
+
+/// How deep compounds and lists can nest before a file is rejected
+const MAX_DEPTH: usize = 512;
+
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<u8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(Compound),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+pub(crate) type Compound = BTreeMap<String, Tag>;
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => BYTE,
+            Tag::Short(_) => SHORT,
+            Tag::Int(_) => INT,
+            Tag::Long(_) => LONG,
+            Tag::Float(_) => FLOAT,
+            Tag::Double(_) => DOUBLE,
+            Tag::ByteArray(_) => BYTE_ARRAY,
+            Tag::String(_) => STRING,
+            Tag::List(_) => LIST,
+            Tag::Compound(_) => COMPOUND,
+            Tag::IntArray(_) => INT_ARRAY,
+            Tag::LongArray(_) => LONG_ARRAY,
+        }
+    }
+
+    /// The value of any integer tag
+    pub(crate) fn as_int(&self) -> Option<i64> {
+        match self {
+            Tag::Byte(value) => Some(*value as i64),
+            Tag::Short(value) => Some(*value as i64),
+            Tag::Int(value) => Some(*value as i64),
+            Tag::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the root compound of a file, along with its name
+pub(crate) fn read(bytes: &[u8]) -> Result<(String, Compound), SchematicError> {
+    let mut reader = Reader { bytes, depth: 0 };
+    if reader.u8()? != COMPOUND {
+        return Err(SchematicError::Invalid("the file doesn't start with a compound".to_string()));
+    }
+    let name = reader.string()?;
+    match reader.payload(COMPOUND)? {
+        Tag::Compound(root) => Ok((name, root)),
+        _ => unreachable!(),
+    }
+}
+
+/// Writes a root compound with the given name
+pub(crate) fn write(name: &str, root: &Compound) -> Vec<u8> {
+    let mut out = vec![COMPOUND];
+    write_string(&mut out, name);
+    write_compound(&mut out, root);
+    out
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], SchematicError> {
+        if self.bytes.len() < len {
+            return Err(SchematicError::Invalid("the file ends in the middle of a tag".to_string()));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SchematicError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, SchematicError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn len(&mut self) -> Result<usize, SchematicError> {
+        let len = i32::from_be_bytes(self.array()?);
+        usize::try_from(len).map_err(|_| SchematicError::Invalid(format!("negative length {len}")))
+    }
+
+    fn string(&mut self) -> Result<String, SchematicError> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+
+    /// Reads `len` values of `size` bytes each, checking the file is long enough first
+    fn values<T>(
+        &mut self,
+        len: usize,
+        size: usize,
+        value: impl Fn(&[u8]) -> T
+    ) -> Result<Vec<T>, SchematicError> {
+        let bytes = self.take(len.saturating_mul(size))?;
+        Ok(bytes.chunks_exact(size).map(value).collect())
+    }
+
+    fn payload(&mut self, id: u8) -> Result<Tag, SchematicError> {
+        Ok(match id {
+            BYTE => Tag::Byte(i8::from_be_bytes(self.array()?)),
+            SHORT => Tag::Short(i16::from_be_bytes(self.array()?)),
+            INT => Tag::Int(i32::from_be_bytes(self.array()?)),
+            LONG => Tag::Long(i64::from_be_bytes(self.array()?)),
+            FLOAT => Tag::Float(f32::from_be_bytes(self.array()?)),
+            DOUBLE => Tag::Double(f64::from_be_bytes(self.array()?)),
+            BYTE_ARRAY => {
+                let len = self.len()?;
+                Tag::ByteArray(self.take(len)?.to_vec())
+            }
+            STRING => Tag::String(self.string()?),
+            LIST => {
+                let id = self.u8()?;
+                let len = self.len()?;
+                self.nested(|reader| {
+                    (0..len).map(|_| reader.payload(id)).collect::<Result<_, _>>().map(Tag::List)
+                })?
+            }
+            COMPOUND => self.nested(|reader| {
+                let mut compound = Compound::new();
+                loop {
+                    let id = reader.u8()?;
+                    if id == END {
+                        return Ok(Tag::Compound(compound));
+                    }
+                    let name = reader.string()?;
+                    let value = reader.payload(id)?;
+                    compound.insert(name, value);
+                }
+            })?,
+            INT_ARRAY => {
+                let len = self.len()?;
+                Tag::IntArray(self.values(len, 4, |b| i32::from_be_bytes(b.try_into().unwrap()))?)
+            }
+            LONG_ARRAY => {
+                let len = self.len()?;
+                Tag::LongArray(self.values(len, 8, |b| i64::from_be_bytes(b.try_into().unwrap()))?)
+            }
+            _ => return Err(SchematicError::Invalid(format!("unknown tag id {id}"))),
+        })
+    }
+
+    fn nested(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<Tag, SchematicError>
+    ) -> Result<Tag, SchematicError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(SchematicError::Invalid("tags are nested too deeply".to_string()));
+        }
+        let tag = read(self);
+        self.depth -= 1;
+        tag
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    let bytes = &value.as_bytes()[..value.len().min(u16::MAX as usize)];
+    out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn write_compound(out: &mut Vec<u8>, compound: &Compound) {
+    for (name, value) in compound {
+        out.push(value.id());
+        write_string(out, name);
+        write_payload(out, value);
+    }
+    out.push(END);
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) {
+    match tag {
+        Tag::Byte(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Tag::Short(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Tag::Int(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Tag::Long(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Tag::Float(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Tag::Double(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Tag::ByteArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            out.extend_from_slice(values);
+        }
+        Tag::String(value) => write_string(out, value),
+        Tag::List(values) => {
+            out.push(values.first().map_or(END, Tag::id));
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for value in values {
+                write_payload(out, value);
+            }
+        }
+        Tag::Compound(compound) => write_compound(out, compound),
+        Tag::IntArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for value in values {
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+        Tag::LongArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for value in values {
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+}
@@ -0,0 +1,456 @@
+//! A headless world simulation running block behaviours.
+//!
+//! Every [`Simulation::tick`] runs, in order, the scheduled ticks that are
+//! due, the random ticks of every loaded chunk, then the neighbour updates
+//! caused by blocks that changed.  Chunks are visited in order of position
+//! and random ticks are picked from a seeded generator, so the same world,
+//! seed and actions always give the same result, and mechanics like
+//! redstone can be replayed and tested.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, behavior::{BlockBehavior, World}, chunk::{BlockPos, ChunkGrid}, simulation::Simulation, AsId};
+//!
+//! table!(BlockType, enum BlockId, static BLOCKS = {
+//!     #[behavior(Clock)]
+//!     let ClockOff: Basic = Block::new_basic("clock_off");
+//!     #[behavior(Clock)]
+//!     let ClockOn: Basic = Block::new_basic("clock_on");
+//!     #[behavior(Powered)]
+//!     let Lamp: Basic = Block::new_basic("lamp");
+//!     #[behavior(Powered)]
+//!     let LitLamp: Basic = Block::new_basic("lit_lamp");
+//!     #[behavior(Grows)]
+//!     let Sapling: Basic = Block::new_basic("sapling");
+//!     let Log: Basic = Block::new_basic("log");
+//! });
+//!
+//! /// Turns on and off every 2 ticks
+//! struct Clock;
+//!
+//! impl BlockBehavior<BlockId> for Clock {
+//!     fn on_scheduled_tick(&self, world: &mut dyn World, pos: BlockPos, state: &BlockId) {
+//!         let next = match state {
+//!             BlockId::ClockOff(()) => BlockId::ClockOn(()),
+//!             _ => BlockId::ClockOff(()),
+//!         };
+//!         world.set_id(pos, &next);
+//!         world.schedule_tick(pos, 2);
+//!     }
+//! }
+//!
+//! /// Lit while the block below is a clock that's on
+//! struct Powered;
+//!
+//! impl BlockBehavior<BlockId> for Powered {
+//!     fn on_neighbor_changed(&self, world: &mut dyn World, pos: BlockPos, _state: &BlockId, _neighbor: BlockPos) {
+//!         let powered = world.get_id::<BlockId>([pos[0], pos[1] - 1, pos[2]]).to_string() == "ClockOn";
+//!         world.set_id(pos, &if powered { BlockId::LitLamp(()) } else { BlockId::Lamp(()) });
+//!     }
+//! }
+//!
+//! /// Grows into a log when picked for a random tick
+//! struct Grows;
+//!
+//! impl BlockBehavior<BlockId> for Grows {
+//!     fn ticks_randomly(&self) -> bool {
+//!         true
+//!     }
+//!
+//!     fn on_random_tick(&self, world: &mut dyn World, pos: BlockPos, _state: &BlockId) {
+//!         world.set_id(pos, &BlockId::Log(()));
+//!     }
+//! }
+//!
+//! let run = |seed| {
+//!     let mut simulation = Simulation::<BlockId>::new(ChunkGrid::new(BlockId::Air(()).to_id()), seed)
+//!         .with_random_tick_speed(512);
+//!     simulation.place([0, 0, 0], &BlockId::ClockOff(()));
+//!     simulation.place([0, 1, 0], &BlockId::Lamp(()));
+//!     simulation.schedule_tick([0, 0, 0], 1);
+//!     for x in 0..16 {
+//!         simulation.place([x, 5, 5], &BlockId::Sapling(()));
+//!     }
+//!
+//!     let mut lamp = vec![];
+//!     for _ in 0..6 {
+//!         simulation.tick();
+//!         lamp.push(simulation.grid().get_id::<BlockId>([0, 1, 0]).to_string());
+//!     }
+//!     let logs = (0..16).filter(|x| simulation.grid().get_id::<BlockId>([*x, 5, 5]).to_string() == "Log").count();
+//!     (lamp, logs)
+//! };
+//!
+//! let (lamp, logs) = run(7);
+//! assert_eq!(lamp, ["LitLamp", "LitLamp", "Lamp", "Lamp", "LitLamp", "LitLamp"]);
+//! assert!(logs > 0 && logs < 16);
+//! // the same seed grows the same saplings
+//! assert_eq!(run(7), (lamp, logs));
+//! ```
+
+use std::{collections::{BTreeSet, VecDeque}, marker::PhantomData, ops::Range};
+
+use crate::{
+    behavior::{HasBehavior, World},
+    block_entity::{BlockEntity, HasBlockEntity},
+    blocks::block::FaceDir,
+    chunk::{offset, world_pos, BlockPos, Chunk, ChunkGrid, CHUNK_VOLUME},
+};
+
+/// How many blocks of every chunk are picked for random ticks each tick by default
+pub const DEFAULT_RANDOM_TICK_SPEED: u32 = 3;
+/// How many neighbour updates run each tick by default before the rest wait for the next tick
+pub const DEFAULT_MAX_UPDATES: usize = 1 << 16;
+
+/// A tick a block asked for, run if the block still has the same id when it's due
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct ScheduledTick {
+    pub tick: u64,
+    pub pos: BlockPos,
+    pub id: usize,
+}
+
+/// A world of chunks along with the ticks and updates waiting to run in it.
+///
+/// `I` is the enum of the table the world's ids come from.
+pub struct Simulation<I> {
+    grid: ChunkGrid,
+    tick: u64,
+    scheduled: BTreeSet<ScheduledTick>,
+    /// Blocks that changed, whose neighbours haven't been updated yet
+    changed: VecDeque<BlockPos>,
+    random: SplitMix64,
+    random_tick_speed: u32,
+    max_updates: usize,
+    table: PhantomData<fn() -> I>,
+}
+
+impl<I: HasBehavior + HasBlockEntity + 'static> Simulation<I> {
+    pub fn new(grid: ChunkGrid, seed: u64) -> Simulation<I> {
+        Simulation {
+            grid,
+            tick: 0,
+            scheduled: BTreeSet::new(),
+            changed: VecDeque::new(),
+            random: SplitMix64(seed),
+            random_tick_speed: DEFAULT_RANDOM_TICK_SPEED,
+            max_updates: DEFAULT_MAX_UPDATES,
+            table: PhantomData,
+        }
+    }
+
+    /// Sets how many blocks of every chunk are picked for random ticks each tick
+    pub fn with_random_tick_speed(mut self, speed: u32) -> Self {
+        self.random_tick_speed = speed;
+        self
+    }
+
+    /// Sets how many neighbour updates run each tick, so blocks that keep
+    /// changing each other can't stop a tick from finishing.
+    ///
+    /// Loops aren't detected any other way, so blocks that keep changing
+    /// each other keep using up the whole budget every tick.
+    pub fn with_max_updates(mut self, max_updates: usize) -> Self {
+        self.max_updates = max_updates;
+        self
+    }
+
+    pub fn grid(&self) -> &ChunkGrid {
+        &self.grid
+    }
+
+    /// The world's chunks, changing them directly doesn't cause any updates
+    pub fn grid_mut(&mut self) -> &mut ChunkGrid {
+        &mut self.grid
+    }
+
+    pub fn into_grid(self) -> ChunkGrid {
+        self.grid
+    }
+
+    /// The number of ticks run so far
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Every tick waiting to run, in the order they'll run
+    pub fn scheduled(&self) -> impl Iterator<Item = &ScheduledTick> + '_ {
+        self.scheduled.iter()
+    }
+
+    /// Schedules a tick for the block at `pos` in at least one tick,
+    /// which runs if the block still has the same id by then
+    pub fn schedule_tick(&mut self, pos: BlockPos, delay: u64) {
+        self.scheduled.insert(ScheduledTick {
+            tick: self.tick + delay.max(1),
+            pos,
+            id: self.grid.get(pos),
+        });
+    }
+
+    /// Places a block the way a player would, creating its block
+    /// entity, then runs its placement hook and updates its neighbours
+    pub fn place(&mut self, pos: BlockPos, block: &I) {
+        let old = self.grid.place(pos, block);
+        if old != block.to_id() {
+            self.changed.push_back(pos);
+        }
+        block.behavior().on_place(self, pos, block);
+        self.update_neighbors();
+    }
+
+    /// Breaks a block the way a player would, replacing it with `replacement`,
+    /// usually air or whatever fills the world, then runs its breaking hook
+    /// and updates its neighbours.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, behavior::{BlockBehavior, World}, chunk::{BlockPos, ChunkGrid}, simulation::Simulation, AsId};
+    ///
+    /// // a world under water has no air
+    /// table!(BlockType, #[derive(PartialEq, Debug)] enum BlockId(), static BLOCKS = {
+    ///     let Water: Basic = Block::new_basic("water");
+    ///     #[behavior(LeavesBubble)]
+    ///     let Coral: Basic = Block::new_basic("coral");
+    ///     let Bubble: Basic = Block::new_basic("bubble");
+    /// });
+    ///
+    /// struct LeavesBubble;
+    ///
+    /// impl BlockBehavior<BlockId> for LeavesBubble {
+    ///     fn on_break(&self, world: &mut dyn World, pos: BlockPos, _state: &BlockId) {
+    ///         world.set_id([pos[0], pos[1] + 1, pos[2]], &BlockId::Bubble(()));
+    ///     }
+    /// }
+    ///
+    /// let mut simulation = Simulation::<BlockId>::new(ChunkGrid::new(BlockId::Water(()).to_id()), 0);
+    /// simulation.place([0, 0, 0], &BlockId::Coral(()));
+    /// simulation.break_block([0, 0, 0], &BlockId::Water(()));
+    /// assert_eq!(simulation.grid().get_id::<BlockId>([0, 0, 0]), BlockId::Water(()));
+    /// assert_eq!(simulation.grid().get_id::<BlockId>([0, 1, 0]), BlockId::Bubble(()));
+    /// assert_eq!(simulation.grid().get_id::<BlockId>([0, 64, 0]), BlockId::Water(()));
+    /// ```
+    pub fn break_block(&mut self, pos: BlockPos, replacement: &I) {
+        let old = World::set(self, pos, replacement.to_id());
+        let state = I::from_id(old);
+        state.behavior().on_break(self, pos, &state);
+        self.update_neighbors();
+    }
+
+    /// Runs the scheduled ticks that are due, random ticks,
+    /// then neighbour updates, and moves on to the next tick
+    pub fn tick(&mut self) {
+        self.tick += 1;
+        self.run_scheduled_ticks();
+        self.run_random_ticks();
+        self.update_neighbors();
+    }
+
+    /// Tells the neighbours of every block that changed, and of the blocks
+    /// that changed because of them, in the order the blocks changed.
+    ///
+    /// Returns whether every update ran, or if some were left for
+    /// later because the tick ran out of updates.  Running out of updates
+    /// is the only thing that stops blocks that keep changing each other,
+    /// see [`Simulation::with_max_updates`].
+    pub fn update_neighbors(&mut self) -> bool {
+        let mut updates = 0;
+        while updates < self.max_updates {
+            let Some(source) = self.changed.pop_front() else {
+                return true;
+            };
+            for face in FaceDir::ALL {
+                let pos = offset(source, face);
+                if !self.grid.is_loaded(pos) {
+                    continue;
+                }
+                let state = I::from_id(self.grid.get(pos));
+                state.behavior().on_neighbor_changed(self, pos, &state, source);
+            }
+            updates += 1;
+        }
+        self.changed.is_empty()
+    }
+
+    fn run_scheduled_ticks(&mut self) {
+        while let Some(scheduled) = self.scheduled.first().copied() {
+            if scheduled.tick > self.tick {
+                break;
+            }
+            self.scheduled.pop_first();
+            if self.grid.get(scheduled.pos) != scheduled.id {
+                continue;
+            }
+            let state = I::from_id(scheduled.id);
+            state.behavior().on_scheduled_tick(self, scheduled.pos, &state);
+        }
+    }
+
+    fn run_random_ticks(&mut self) {
+        let mut chunks: Vec<_> = self.grid.chunks()
+            .filter(|(_, chunk)| Self::ticks_randomly(chunk))
+            .map(|(pos, _)| pos)
+            .collect();
+        // palettes can still hold blocks that were replaced, which mustn't
+        // change how many random numbers are drawn
+        chunks.retain(|pos| {
+            let chunk = self.grid.chunk_mut(*pos).unwrap();
+            chunk.compact();
+            Self::ticks_randomly(chunk)
+        });
+        chunks.sort();
+        for chunk_pos in chunks {
+            for _ in 0..self.random_tick_speed {
+                let local = Chunk::position(self.random.next_below(CHUNK_VOLUME as u64) as usize);
+                let pos = world_pos(chunk_pos, local);
+                let state = I::from_id(self.grid.get(pos));
+                let behavior = state.behavior();
+                if behavior.ticks_randomly() {
+                    behavior.on_random_tick(self, pos, &state);
+                }
+            }
+        }
+    }
+
+    /// Whether any block in a chunk's palette ticks randomly
+    fn ticks_randomly(chunk: &Chunk) -> bool {
+        chunk.palette().iter().any(|id| I::from_id(*id).behavior().ticks_randomly())
+    }
+}
+
+impl<I: HasBehavior + HasBlockEntity + 'static> World for Simulation<I> {
+    fn get(&self, pos: BlockPos) -> usize {
+        self.grid.get(pos)
+    }
+
+    /// Sets the id of a block, and updates its neighbours if it changed
+    fn set(&mut self, pos: BlockPos, id: usize) -> usize {
+        let old = self.grid.set(pos, id);
+        if old != id {
+            self.changed.push_back(pos);
+        }
+        old
+    }
+
+    fn block_entity_mut(&mut self, pos: BlockPos) -> Option<&mut dyn BlockEntity> {
+        self.grid.block_entity_mut(pos)
+    }
+
+    fn insert_block_entity(&mut self, pos: BlockPos, ids: Range<usize>, data: Box<dyn BlockEntity>) {
+        World::insert_block_entity(&mut self.grid, pos, ids, data);
+    }
+
+    fn schedule_tick(&mut self, pos: BlockPos, delay: u64) {
+        Simulation::schedule_tick(self, pos, delay);
+    }
+}
+
+/// A small seeded random number generator, the same on every machine
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+#[cfg(test)]
+// lints look inside `table!` when it's used in this crate, and
+// the entries are only used through their ids
+#[allow(dead_code, unused_parens, unused_assignments)]
+mod tests {
+    use crate::{blocks::{block::Block, block_types::{basic::Basic, BlockType}}, behavior::{BlockBehavior, World}, chunk::{BlockPos, ChunkGrid}, table, AsId};
+
+    use super::Simulation;
+
+    table!(BlockType, enum BlockId, static BLOCKS = {
+        #[behavior(Grows)]
+        let Sapling: Basic = Block::new_basic("sapling");
+        let Log: Basic = Block::new_basic("log");
+        #[behavior(Flips)]
+        let Up: Basic = Block::new_basic("up");
+        #[behavior(Flips)]
+        let Down: Basic = Block::new_basic("down");
+    });
+
+    /// Grows into a log when picked for a random tick
+    struct Grows;
+
+    impl BlockBehavior<BlockId> for Grows {
+        fn ticks_randomly(&self) -> bool {
+            true
+        }
+
+        fn on_random_tick(&self, world: &mut dyn World, pos: BlockPos, _state: &BlockId) {
+            world.set_id(pos, &BlockId::Log(()));
+        }
+    }
+
+    /// Flips whenever a neighbour changes, so two of them keep flipping each other
+    struct Flips;
+
+    impl BlockBehavior<BlockId> for Flips {
+        fn on_neighbor_changed(&self, world: &mut dyn World, pos: BlockPos, state: &BlockId, _neighbor: BlockPos) {
+            let next = match state {
+                BlockId::Up(()) => BlockId::Down(()),
+                _ => BlockId::Up(()),
+            };
+            world.set_id(pos, &next);
+        }
+    }
+
+    fn grown(simulation: &Simulation<BlockId>) -> Vec<i32> {
+        (0..16)
+            .filter(|x| simulation.grid().get_id::<BlockId>([*x, 0, 0]).to_string() == "Log")
+            .collect()
+    }
+
+    #[test]
+    fn random_ticks_ignore_replaced_blocks() {
+        let run = |replaced: bool| {
+            let mut grid = ChunkGrid::new(BlockId::Air(()).to_id());
+            for x in 0..16 {
+                grid.set_id([x, 0, 0], &BlockId::Sapling(()));
+            }
+            // a chunk before the saplings that only ever had logs, or
+            // had saplings that were all replaced by logs
+            for x in -16..0 {
+                grid.set_id([x, 0, 0], &if replaced { BlockId::Sapling(()) } else { BlockId::Log(()) });
+                grid.set_id([x, 0, 0], &BlockId::Log(()));
+            }
+            let mut simulation = Simulation::<BlockId>::new(grid, 3).with_random_tick_speed(256);
+            (0..4).map(|_| {
+                simulation.tick();
+                grown(&simulation)
+            }).collect::<Vec<_>>()
+        };
+        let grown = run(false);
+        assert!(grown.last().unwrap().len() < 16);
+        assert_eq!(run(true), grown);
+    }
+
+    #[test]
+    fn max_updates_stops_blocks_flipping_each_other() {
+        let mut simulation = Simulation::<BlockId>::new(ChunkGrid::new(BlockId::Air(()).to_id()), 0)
+            .with_max_updates(100);
+        simulation.place([0, 0, 0], &BlockId::Up(()));
+        simulation.place([1, 0, 0], &BlockId::Up(()));
+        assert!(!simulation.update_neighbors());
+        simulation.tick();
+        assert!(!simulation.update_neighbors());
+    }
+}
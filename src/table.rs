@@ -1,3 +1,8 @@
+use std::path::Path;
+
+use crate::{assets::{self, Report}, blocks::block_types::BlockType};
+
+/// Every entry of a [`table!`](crate::table) in order, indexed by its enum
 pub struct Table<T: 'static + ?Sized>(pub &'static [&'static T]);
 
 impl<T: 'static + ?Sized> Table<T> {
@@ -6,6 +11,99 @@ impl<T: 'static + ?Sized> Table<T> {
     }
 }
 
+impl<T: 'static + ?Sized + BlockType> Table<T> {
+    /// Finds the block a state id belongs to, 
+    /// along with the state relative to that block's id.
+    /// 
+    /// Returns `None` for air and for ids past the end of the table.
+    pub fn block_of(&self, id: usize) -> Option<(&'static T, usize)> {
+        let i = self.0.partition_point(|block| block.id() <= id);
+        let block = *self.0.get(i.checked_sub(1)?)?;
+        let state = id - block.id();
+        (state < block.id_span()).then_some((block, state))
+    }
+
+    /// The index of the block a state id belongs to, as given by the `index`
+    /// method of the table's enum, which counts the null variant if there is one.
+    ///
+    /// Returns `None` for the null variant and for ids past the end of the table.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, slab::Slab, BlockType}}, AsId};
+    ///
+    /// table!(BlockType, enum BlockId, static BLOCKS = {
+    ///     let StoneSlab: Slab = Block::new_slab("stone_slab");
+    ///     let Dirt: Basic = Block::new_basic("dirt");
+    /// });
+    ///
+    /// // without a null variant, the first block has index 0
+    /// table!(BlockType, enum PaletteId(), static PALETTE = {
+    ///     let Sand: Basic = Block::new_basic("sand");
+    /// });
+    ///
+    /// let dirt = BlockId::Dirt(());
+    /// assert_eq!(BLOCKS.index_of(dirt.to_id()), Some(dirt.index()));
+    /// assert_eq!(BLOCKS.index_of(0), None);
+    ///
+    /// let sand = PaletteId::Sand(());
+    /// assert_eq!(PALETTE.index_of(sand.to_id()), Some(0));
+    /// assert_eq!(sand.index(), 0);
+    /// ```
+    pub fn index_of(&self, id: usize) -> Option<usize> {
+        let (block, _) = self.block_of(id)?;
+        // the null variant takes id 0, so tables with one start their blocks at 1
+        let first = self.0.first()?.id();
+        let position = self.0.partition_point(|entry| entry.id() < block.id());
+        Some(position + first)
+    }
+
+    /// The number of ids used by the table, which is one past the last id
+    /// of its last block, or 0 if the table has no blocks
+    pub fn id_span(&self) -> usize {
+        self.0.last().map_or(0, |block| block.id() + block.id_span())
+    }
+
+    /// Checks the texture and model files referenced by every block,
+    /// relative to the `root` asset directory.
+    ///
+    /// Missing or unreadable files, malformed models and textures of a different
+    /// size than the rest are errors.  Files referenced by several blocks and
+    /// assets no block uses are warnings.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, assets::Problem, AsId};
+    ///
+    /// table!(BlockType, enum BlockId, static BLOCKS = {
+    ///     let Dirt: Basic = Block::new_basic("dirt")
+    ///         .with_texture("dirt.gif");
+    ///     let Furnace: Basic = Block::new_basic("furnace")
+    ///         .with_texture("furnace_top.gif");
+    /// });
+    ///
+    /// let root = std::env::temp_dir().join("opentale_blocks_validate_example");
+    /// std::fs::create_dir_all(&root).unwrap();
+    /// // a 16x16 gif header
+    /// std::fs::write(root.join("dirt.gif"), b"GIF89a\x10\x00\x10\x00").unwrap();
+    /// std::fs::write(root.join("furnace_tpo.gif"), b"GIF89a\x10\x00\x10\x00").unwrap();
+    ///
+    /// let report = BLOCKS.validate_assets(&root);
+    /// assert!(report.has_errors());
+    /// assert!(matches!(report.errors().next(), Some(Problem::MissingFile { block: "furnace", .. })));
+    /// assert!(matches!(report.warnings().next(), Some(Problem::UnusedFile { .. })));
+    /// ```
+    pub fn validate_assets(&self, root: &Path) -> Report {
+        assets::validate(self, root)
+    }
+}
+
 impl<T: 'static + ?Sized> IntoIterator for &Table<T> {
     type Item = &'static T;
 
@@ -14,4 +112,4 @@ impl<T: 'static + ?Sized> IntoIterator for &Table<T> {
     fn into_iter(self) -> Self::IntoIter {
         self.0.iter().copied()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,279 @@
+use std::{borrow::Cow, fmt, marker::PhantomData, path::Path};
+
+use crate::{blocks::block_types::BlockType, table::Table};
+
+/// A set of blocks, stored as one bit per block index.
+///
+/// Block indices are those given by the `index` method of a table's enum,
+/// so in tables with a null variant, like air, the first block has index 1.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BlockSet {
+    words: Cow<'static, [u64]>,
+}
+
+impl BlockSet {
+    pub const fn new() -> BlockSet {
+        BlockSet::from_words(&[])
+    }
+
+    /// A set backed by bits computed at compile time
+    pub const fn from_words(words: &'static [u64]) -> BlockSet {
+        BlockSet {
+            words: Cow::Borrowed(words),
+        }
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word >> (index % 64) & 1 == 1)
+    }
+
+    /// Adds a block to the set, returning whether it was newly added
+    pub fn insert(&mut self, index: usize) -> bool {
+        if self.contains(index) {
+            return false;
+        }
+        let words = self.words.to_mut();
+        if words.len() <= index / 64 {
+            words.resize(index / 64 + 1, 0);
+        }
+        words[index / 64] |= 1 << (index % 64);
+        true
+    }
+
+    /// Removes a block from the set, returning whether it was present
+    pub fn remove(&mut self, index: usize) -> bool {
+        if !self.contains(index) {
+            return false;
+        }
+        self.words.to_mut()[index / 64] &= !(1 << (index % 64));
+        true
+    }
+
+    /// Adds every block of another set to this one
+    pub fn union(&mut self, other: &BlockSet) {
+        for (index, word) in other.words.iter().enumerate() {
+            if *word == 0 {
+                continue;
+            }
+            let words = self.words.to_mut();
+            if words.len() <= index {
+                words.resize(index + 1, 0);
+            }
+            words[index] |= word;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// The index of every block in the set, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, word)| {
+            (0..64)
+                .filter(move |bit| word >> bit & 1 == 1)
+                .map(move |bit| index * 64 + bit)
+        })
+    }
+}
+
+/// A tag enum generated by [`table!`](crate::table)
+///
+/// Entries are given tags with `#[tags(..)]`.  Doc comments can go on entries
+/// too, but any other attribute is an error, so a misspelt one isn't ignored:
+///
+/// ```compile_fail
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{pillar::Pillar, BlockType}}, AsId};
+/// table!(BlockType, enum BlockId, tags Tag { log }, static BLOCKS = {
+///     /// Grows in forests
+///     #[tag(log)]
+///     let OakLog: Pillar = Block::new_pillar("oak_log");
+/// });
+/// ```
+pub trait BlockTag: Copy + Eq + 'static {
+    /// Every tag, in the order they were declared
+    const ALL: &'static [Self];
+
+    /// The name used for this tag in tag files
+    fn name(&self) -> &'static str;
+
+    /// The position of this tag in [`BlockTag::ALL`]
+    fn index(&self) -> usize;
+
+    /// The blocks given this tag in the table
+    fn members(&self) -> BlockSet;
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|tag| tag.name() == name)
+    }
+}
+
+/// The members of every tag, starting from the tags given in the table
+/// and extended at runtime by tag files.
+///
+/// Tag files list extra blocks for existing tags, one tag per line,
+/// referring to blocks by their technical name.  Blank lines and
+/// lines starting with `#` are ignored.
+///
+/// ```text
+/// # logs added by the forest pack
+/// log: birch_log, spruce_log
+/// flammable: birch_log, spruce_log
+/// ```
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, pillar::Pillar, BlockType}}, tags::{BlockTag, Tags}, AsId};
+///
+/// table!(BlockType, enum BlockId, tags Tag { log, flammable }, static BLOCKS = {
+///     #[tags(log, flammable)]
+///     let OakLog: Pillar = Block::new_pillar("oak_log");
+///     let BirchLog: Pillar = Block::new_pillar("birch_log");
+///     #[tags(flammable)]
+///     let Planks: Basic = Block::new_basic("planks");
+/// });
+///
+/// assert!(BlockId::OakLog(()).has_tag(Tag::Log));
+/// assert!(!BlockId::BirchLog(()).has_tag(Tag::Log));
+/// assert_eq!(Tag::Flammable.blocks().count(), 2);
+///
+/// let mut tags = Tags::<Tag>::new();
+/// tags.merge_str("log: birch_log", &BLOCKS).unwrap();
+/// assert!(tags.contains(Tag::Log, BlockId::BirchLog(()).index()));
+/// assert!(tags.merge_str("leaves: birch_log", &BLOCKS).is_err());
+/// ```
+pub struct Tags<T: BlockTag> {
+    members: Vec<BlockSet>,
+    tag: PhantomData<T>,
+}
+
+impl<T: BlockTag> Tags<T> {
+    pub fn new() -> Tags<T> {
+        Tags {
+            members: T::ALL.iter().map(|tag| tag.members()).collect(),
+            tag: PhantomData,
+        }
+    }
+
+    pub fn members(&self, tag: T) -> &BlockSet {
+        &self.members[tag.index()]
+    }
+
+    /// Whether a block, by its index, has a tag
+    pub fn contains(&self, tag: T, index: usize) -> bool {
+        self.members[tag.index()].contains(index)
+    }
+
+    /// Adds a block, by its index, to a tag
+    pub fn insert(&mut self, tag: T, index: usize) -> bool {
+        self.members[tag.index()].insert(index)
+    }
+
+    /// Adds the entries of a tag file to these tags.
+    ///
+    /// If any line is invalid, no entries are added.
+    pub fn merge_str<B: 'static + ?Sized + BlockType>(
+        &mut self,
+        text: &str,
+        table: &Table<B>
+    ) -> Result<(), TagFileError> {
+        let mut entries = vec![];
+        for (number, line) in text.lines().enumerate() {
+            let line_number = number + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((tag, blocks)) = line.split_once(':') else {
+                return Err(TagFileError::Syntax { line: line_number });
+            };
+            let tag = tag.trim();
+            let tag = T::from_name(tag).ok_or_else(|| TagFileError::UnknownTag {
+                line: line_number,
+                tag: tag.to_string(),
+            })?;
+            for block in blocks.split(',').map(str::trim).filter(|block| !block.is_empty()) {
+                let entry = table.iter()
+                    .find(|entry| entry.name() == block)
+                    .ok_or_else(|| TagFileError::UnknownBlock {
+                        line: line_number,
+                        block: block.to_string(),
+                    })?;
+                let index = table.index_of(entry.id()).expect("every block of a table has an index");
+                entries.push((tag, index));
+            }
+        }
+        for (tag, index) in entries {
+            self.insert(tag, index);
+        }
+        Ok(())
+    }
+
+    /// Reads a tag file and adds its entries to these tags
+    pub fn merge_file<B: 'static + ?Sized + BlockType>(
+        &mut self,
+        path: impl AsRef<Path>,
+        table: &Table<B>
+    ) -> Result<(), TagFileError> {
+        let text = std::fs::read_to_string(path).map_err(TagFileError::Io)?;
+        self.merge_str(&text, table)
+    }
+}
+
+impl<T: BlockTag> Default for Tags<T> {
+    fn default() -> Self {
+        Tags::new()
+    }
+}
+
+/// Why a tag file couldn't be merged
+#[derive(Debug)]
+pub enum TagFileError {
+    Io(std::io::Error),
+    /// A line isn't of the form `tag: block, block`
+    Syntax { line: usize },
+    UnknownTag { line: usize, tag: String },
+    UnknownBlock { line: usize, block: String },
+}
+
+impl fmt::Display for TagFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagFileError::Io(error) => write!(f, "couldn't read tag file: {error}"),
+            TagFileError::Syntax { line } => write!(f, "line {line}: expected `tag: block, block`"),
+            TagFileError::UnknownTag { line, tag } => write!(f, "line {line}: unknown tag `{tag}`"),
+            TagFileError::UnknownBlock { line, block } => write!(f, "line {line}: unknown block `{block}`"),
+        }
+    }
+}
+
+impl std::error::Error for TagFileError {}
+
+/// Sets the bits of every block given a tag, for building tag bitsets at compile time
+#[doc(hidden)]
+pub const fn tag_words<const WORDS: usize>(tag: usize, block_tags: &[&[usize]]) -> [u64; WORDS] {
+    let mut words = [0; WORDS];
+    let mut index = 0;
+    while index < block_tags.len() {
+        let mut i = 0;
+        while i < block_tags[index].len() {
+            if block_tags[index][i] == tag {
+                words[index / 64] |= 1 << (index % 64);
+            }
+            i += 1;
+        }
+        index += 1;
+    }
+    words
+}
@@ -0,0 +1,378 @@
+//! Loading the textures of a table into a texture array.
+//!
+//! Layers are laid out by a [`TextureManifest`], so animated GIFs
+//! take one layer per frame, and connected textures are split into
+//! one layer per tile.  Every layer is resized to a common size,
+//! reporting the textures that weren't that size, and each mip level is packed into one RGBA buffer holding every
+//! layer in order, ready to be uploaded as a 2D texture array.
+
+use std::{fmt, io, path::{Path, PathBuf}};
+
+use crate::{animation::{gif_frames, TextureManifest}, blocks::block_types::BlockType, ctm::CtmLayout, table::Table};
+
+/// Loads the textures of a table from files relative to a root directory.
+///
+/// ### Example
+///
+/// ```rust
+/// # #![feature(const_trait_impl)]
+/// #
+/// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, textures::TextureLoader, AsId};
+///
+/// table!(BlockType, enum BlockId, static BLOCKS = {
+///     let Dirt: Basic = Block::new_basic("dirt")
+///         .with_texture("dirt.png");
+/// });
+///
+/// // dirt.png doesn't exist, so it gets the checkerboard debug texture
+/// let textures = TextureLoader::new("assets").with_size(16).load(&BLOCKS);
+/// assert_eq!(textures.errors.len(), 1);
+/// assert_eq!(textures.layer_count(), 2);
+/// // 16x16, 8x8, 4x4, 2x2 and 1x1
+/// assert_eq!(textures.mips.len(), 5);
+/// assert_eq!(textures.mips[1].len(), 8 * 8 * 4 * 2);
+/// assert_eq!(textures.layer(0, 1), textures.layer(0, 0));
+/// ```
+pub struct TextureLoader {
+    root: PathBuf,
+    size: Option<u32>,
+    mip_levels: Option<u32>,
+}
+
+impl TextureLoader {
+    pub fn new(root: impl AsRef<Path>) -> TextureLoader {
+        TextureLoader {
+            root: root.as_ref().to_path_buf(),
+            size: None,
+            mip_levels: None,
+        }
+    }
+
+    /// The width and height every layer is resized to.
+    ///
+    /// By default, this is the size most textures are.
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = Some(size.max(1));
+        self
+    }
+
+    /// The number of mip levels to generate, including the full size one.
+    ///
+    /// By default, levels are generated down to 1x1.
+    pub fn with_mip_levels(mut self, mip_levels: u32) -> Self {
+        self.mip_levels = Some(mip_levels.max(1));
+        self
+    }
+
+    /// Loads every texture used by the table.
+    ///
+    /// Textures that can't be loaded are replaced by a checkerboard, and
+    /// textures of the wrong size are resized.  Both are listed in the
+    /// returned array's `errors`.
+    pub fn load<T: 'static + ?Sized + BlockType>(&self, table: &Table<T>) -> TextureArray {
+        let manifest = TextureManifest::load(table, &self.root);
+        let mut errors = vec![];
+        let mut images: Vec<Option<Image>> = vec![];
+        let mut loaded: Vec<(&'static str, Vec<Image>)> = vec![];
+        for layer in manifest.layers() {
+            let Some(path) = layer.path else {
+                images.push(None);
+                continue;
+            };
+            if !loaded.iter().any(|(file, _)| *file == path) {
+                let full_path = self.root.join(path);
+                match load_frames(&full_path) {
+                    Ok(frames) => loaded.push((path, frames)),
+                    Err(kind) => {
+                        errors.push(TextureError { path: full_path, kind });
+                        loaded.push((path, vec![]));
+                    }
+                }
+            }
+            let (_, frames) = loaded.iter().find(|(file, _)| *file == path).unwrap();
+            let image = frames.get(layer.frame as usize);
+            images.push(match (image, layer.tile) {
+                (Some(sheet), Some((layout, tile))) => match sheet.tile(layout, tile) {
+                    Some(image) => Some(image),
+                    None => {
+                        let path = self.root.join(path);
+                        if !errors.iter().any(|error: &TextureError| error.path == path) {
+                            errors.push(TextureError {
+                                path,
+                                kind: TextureErrorKind::NotTileable {
+                                    size: [sheet.width, sheet.height],
+                                    grid: layout.grid(),
+                                },
+                            });
+                        }
+                        None
+                    }
+                },
+                (image, _) => image.cloned(),
+            });
+        }
+
+        let size = self.size.unwrap_or_else(|| common_size(images.iter().flatten()));
+        let mut base = Vec::with_capacity(size as usize * size as usize * 4 * images.len());
+        for (layer, image) in images.iter().enumerate() {
+            let image = match image {
+                Some(image) if image.width != image.height => {
+                    let path = self.root.join(manifest.layers()[layer].path.unwrap());
+                    if !errors.iter().any(|error: &TextureError| error.path == path) {
+                        errors.push(TextureError {
+                            path,
+                            kind: TextureErrorKind::NotSquare { size: [image.width, image.height] },
+                        });
+                    }
+                    checkerboard(size)
+                }
+                Some(image) => {
+                    if image.width != size {
+                        let path = self.root.join(manifest.layers()[layer].path.unwrap());
+                        if !errors.iter().any(|error: &TextureError| error.path == path) {
+                            errors.push(TextureError {
+                                path,
+                                kind: TextureErrorKind::WrongSize { size: image.width, expected: size },
+                            });
+                        }
+                    }
+                    image.resized(size)
+                }
+                None => checkerboard(size),
+            };
+            base.extend_from_slice(&image.pixels);
+        }
+
+        let full_chain = 32 - size.leading_zeros();
+        let mip_levels = self.mip_levels.unwrap_or(full_chain).min(full_chain);
+        let mut mips = vec![base];
+        let mut mip_size = size;
+        for _ in 1..mip_levels {
+            let next = downsample(mips.last().unwrap(), mip_size, images.len());
+            mip_size = (mip_size / 2).max(1);
+            mips.push(next);
+        }
+
+        TextureArray { size, mips, manifest, errors }
+    }
+}
+
+/// The textures of a table, as a texture array
+#[derive(Debug)]
+pub struct TextureArray {
+    /// The width and height of the full size layers
+    pub size: u32,
+    /// Every layer of each mip level as RGBA, starting from the full size one
+    pub mips: Vec<Vec<u8>>,
+    /// Which layers each texture index uses
+    pub manifest: TextureManifest,
+    /// The textures replaced by the checkerboard or resized
+    pub errors: Vec<TextureError>,
+}
+
+impl TextureArray {
+    pub fn layer_count(&self) -> usize {
+        self.manifest.layers().len()
+    }
+
+    /// The width and height of the layers of a mip level
+    pub fn mip_size(&self, level: usize) -> u32 {
+        (self.size >> level).max(1)
+    }
+
+    /// The pixels of one layer of a mip level
+    pub fn layer(&self, level: usize, layer: usize) -> &[u8] {
+        let size = self.mip_size(level) as usize;
+        let length = size * size * 4;
+        &self.mips[level][layer * length..][..length]
+    }
+}
+
+/// An RGBA image
+#[derive(Clone)]
+pub(crate) struct Image {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) pixels: Vec<u8>,
+}
+
+impl Image {
+    /// One tile of a connected texture's sheet, or `None` if
+    /// the sheet can't be split into square tiles
+    fn tile(&self, layout: CtmLayout, tile: u32) -> Option<Image> {
+        let [columns, rows] = layout.grid();
+        let size = self.width / columns;
+        if size == 0 || !self.width.is_multiple_of(columns) || self.height != size * rows {
+            return None;
+        }
+        let (left, top) = ((tile % columns * size) as usize, (tile / columns * size) as usize);
+        let mut pixels = Vec::with_capacity(size as usize * size as usize * 4);
+        for y in top..top + size as usize {
+            let start = (y * self.width as usize + left) * 4;
+            pixels.extend_from_slice(&self.pixels[start..start + size as usize * 4]);
+        }
+        Some(Image { width: size, height: size, pixels })
+    }
+
+    /// Nearest neighbour resizing, which keeps pixel art sharp
+    fn resized(&self, size: u32) -> Image {
+        if self.width == size && self.height == size {
+            return self.clone();
+        }
+        let mut pixels = Vec::with_capacity(size as usize * size as usize * 4);
+        for y in 0..size {
+            for x in 0..size {
+                let sx = (x as u64 * self.width as u64 / size as u64) as usize;
+                let sy = (y as u64 * self.height as u64 / size as u64) as usize;
+                pixels.extend_from_slice(&self.pixels[(sy * self.width as usize + sx) * 4..][..4]);
+            }
+        }
+        Image { width: size, height: size, pixels }
+    }
+}
+
+/// A magenta and black checkerboard of 2x2 squares
+fn checkerboard(size: u32) -> Image {
+    let half = (size / 2).max(1);
+    let mut pixels = Vec::with_capacity(size as usize * size as usize * 4);
+    for y in 0..size {
+        for x in 0..size {
+            if (x / half + y / half).is_multiple_of(2) {
+                pixels.extend_from_slice(&[255, 0, 255, 255]);
+            } else {
+                pixels.extend_from_slice(&[0, 0, 0, 255]);
+            }
+        }
+    }
+    Image { width: size, height: size, pixels }
+}
+
+/// The size of most square images, or 16 if there are none
+fn common_size<'a>(images: impl Iterator<Item = &'a Image>) -> u32 {
+    let mut counts: Vec<(u32, usize)> = vec![];
+    for image in images.filter(|image| image.width == image.height) {
+        match counts.iter_mut().find(|(size, _)| *size == image.width) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((image.width, 1)),
+        }
+    }
+    counts.iter().rev().max_by_key(|(_, count)| *count).map_or(16, |(size, _)| *size)
+}
+
+/// Halves every layer, averaging each 2x2 block of pixels.
+///
+/// Colors are weighted by their alpha, so transparent pixels
+/// don't darken the edges of cutout textures.
+fn downsample(pixels: &[u8], size: u32, layers: usize) -> Vec<u8> {
+    let size = size as usize;
+    let next = (size / 2).max(1);
+    let mut output = Vec::with_capacity(next * next * 4 * layers);
+    for layer in pixels.chunks_exact(size * size * 4) {
+        for y in 0..next {
+            for x in 0..next {
+                let mut color = [0u32; 3];
+                let mut alpha = 0u32;
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let sx = (x * 2 + dx).min(size - 1);
+                    let sy = (y * 2 + dy).min(size - 1);
+                    let pixel = &layer[(sy * size + sx) * 4..][..4];
+                    for channel in 0..3 {
+                        color[channel] += pixel[channel] as u32 * pixel[3] as u32;
+                    }
+                    alpha += pixel[3] as u32;
+                }
+                let color = color.map(|channel| channel.checked_div(alpha).unwrap_or(0) as u8);
+                output.extend_from_slice(&[color[0], color[1], color[2], (alpha / 4) as u8]);
+            }
+        }
+    }
+    output
+}
+
+fn load_frames(path: &Path) -> Result<Vec<Image>, TextureErrorKind> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("gif") => {
+            let gif = gif_frames(path).map_err(io_error)?;
+            let [width, height] = gif.size;
+            Ok(gif.frames.into_iter().map(|pixels| Image { width, height, pixels }).collect())
+        }
+        Some("png") => read_png(path).map(|image| vec![image]),
+        _ => Err(TextureErrorKind::Decode("expected a .png or .gif file".to_string())),
+    }
+}
+
+fn io_error(error: io::Error) -> TextureErrorKind {
+    match error.kind() {
+        io::ErrorKind::NotFound => TextureErrorKind::Missing,
+        io::ErrorKind::InvalidData => TextureErrorKind::Decode(error.to_string()),
+        _ => TextureErrorKind::Io(error),
+    }
+}
+
+/// Decodes a PNG into RGBA
+pub(crate) fn read_png(path: &Path) -> Result<Image, TextureErrorKind> {
+    let file = std::fs::File::open(path).map_err(io_error)?;
+    let mut decoder = png::Decoder::new(io::BufReader::new(file));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let decode_error = |error: png::DecodingError| TextureErrorKind::Decode(error.to_string());
+    let mut reader = decoder.read_info().map_err(decode_error)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(decode_error)?;
+    let bytes = &buffer[..info.buffer_size()];
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => bytes.to_vec(),
+        png::ColorType::Rgb => bytes.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => bytes.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => bytes.iter().flat_map(|p| [*p, *p, *p, 255]).collect(),
+        png::ColorType::Indexed => return Err(TextureErrorKind::Decode("unexpanded palette".to_string())),
+    };
+    Ok(Image { width: info.width, height: info.height, pixels })
+}
+
+/// A texture that couldn't be loaded
+#[derive(Debug)]
+pub struct TextureError {
+    pub path: PathBuf,
+    pub kind: TextureErrorKind,
+}
+
+#[derive(Debug)]
+pub enum TextureErrorKind {
+    /// The file doesn't exist
+    Missing,
+    /// The file couldn't be read
+    Io(io::Error),
+    /// The file isn't a valid PNG or GIF image
+    Decode(String),
+    /// The image isn't square, so it can't be a layer
+    NotSquare { size: [u32; 2] },
+    /// The sheet of a connected texture can't be split into its grid of square tiles
+    NotTileable { size: [u32; 2], grid: [u32; 2] },
+    /// The image isn't the size of the other layers, so it was resized
+    WrongSize { size: u32, expected: u32 },
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.display();
+        match &self.kind {
+            TextureErrorKind::Missing => write!(f, "texture {path} doesn't exist"),
+            TextureErrorKind::Io(error) => write!(f, "couldn't read texture {path}: {error}"),
+            TextureErrorKind::Decode(error) => write!(f, "couldn't decode texture {path}: {error}"),
+            TextureErrorKind::NotSquare { size } => {
+                write!(f, "texture {path} is {}x{}, but textures must be square", size[0], size[1])
+            }
+            TextureErrorKind::NotTileable { size, grid } => write!(
+                f,
+                "connected texture {path} is {}x{}, which can't be split into {}x{} square tiles",
+                size[0], size[1], grid[0], grid[1]
+            ),
+            TextureErrorKind::WrongSize { size, expected } => write!(
+                f,
+                "texture {path} is {size}x{size}, but was resized to {expected}x{expected} to match the other textures"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
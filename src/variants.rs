@@ -0,0 +1,187 @@
+//! Weighted random texture variants, picked by block position.
+//!
+//! Blocks like dirt and stone look tiled when every face is the same,
+//! so a texture slot can list alternatives with a weight each, and be
+//! turned a random number of quarter turns.  The choice only depends on
+//! the block's position through [`position_hash`], so every client and
+//! the server agree on it without storing anything.
+//!
+//! Variants take a run of layers each in the texture array, in order,
+//! the same way the tiles of a connected texture do, and meshes tell them
+//! apart by their vertices' `tile`.
+//!
+//! ### Example
+//!
+//! ```rust
+//! # #![feature(const_trait_impl)]
+//! #
+//! # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, variants::{position_hash, TextureVariants}, AsId};
+//!
+//! table!(BlockType, enum BlockId, static BLOCKS = {
+//!     let Dirt: Basic = Block::new_basic("dirt")
+//!         .with_texture_variants([("dirt1.gif", 3), ("dirt2.gif", 1)]);
+//!     let Stone: Basic = Block::new_basic("stone")
+//!         .with_texture_variants(TextureVariants::new([("stone.gif", 1)]).with_random_rotation());
+//! });
+//!
+//! let dirt = BLOCKS[BlockId::Dirt(())].texture_variants(0).unwrap();
+//! let picks: Vec<usize> = (0..1000).map(|x| dirt.pick(position_hash([x, 64, -x]))).collect();
+//! let common = picks.iter().filter(|variant| **variant == 0).count();
+//! assert!((700..800).contains(&common));
+//!
+//! // the same position always picks the same variant
+//! assert_eq!(dirt.pick(position_hash([5, 64, -5])), picks[5]);
+//! ```
+
+use crate::{blocks::const_into::ConstInto, chunk::BlockPos};
+
+/// The most variants a texture slot can have
+pub const MAX_VARIANTS: usize = 8;
+
+/// The weighted alternatives of a texture slot
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TextureVariants {
+    variants: [(&'static str, u32); MAX_VARIANTS],
+    len: usize,
+    random_rotation: bool,
+}
+
+impl TextureVariants {
+    /// Texture files along with their weights, where a variant with weight 3
+    /// is picked three times as often as one with weight 1.
+    ///
+    /// Panics if there are no variants, more than [`MAX_VARIANTS`],
+    /// or if every weight is 0.
+    pub const fn new<const N: usize>(variants: [(&'static str, u32); N]) -> TextureVariants {
+        assert!(N > 0 && N <= MAX_VARIANTS, "a texture slot must have between 1 and 8 variants");
+        let mut result = TextureVariants {
+            variants: [("", 0); MAX_VARIANTS],
+            len: N,
+            random_rotation: false,
+        };
+        let mut total = 0;
+        let mut i = 0;
+        while i < N {
+            result.variants[i] = variants[i];
+            total += variants[i].1;
+            i += 1;
+        }
+        assert!(total > 0, "at least one texture variant must have a weight");
+        result
+    }
+
+    /// Turns the texture a random number of quarter turns on every face.
+    ///
+    /// Turned faces aren't merged with their neighbours when meshing,
+    /// so each one keeps a texture one block wide.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(const_trait_impl)]
+    /// #
+    /// # use opentale_blocks::{table, blocks::{block::Block, block_types::{basic::Basic, BlockType}}, chunk::Chunk, meshing::{MeshTable, Mesher, PaddedChunk}, variants::{position_hash, TextureVariants}, AsId};
+    ///
+    /// table!(BlockType, enum BlockId, static BLOCKS = {
+    ///     let Stone: Basic = Block::new_basic("stone")
+    ///         .with_texture_variants(TextureVariants::new([("stone.gif", 1)]).with_random_rotation());
+    /// });
+    ///
+    /// let air = BlockId::Air(()).to_id();
+    /// let mut chunk = Chunk::filled(air);
+    /// for x in 0..16 {
+    ///     for z in 0..16 {
+    ///         chunk.set_id([x, 0, z], &BlockId::Stone(()));
+    ///     }
+    /// }
+    /// let mesh = Mesher::new(MeshTable::new(&BLOCKS)).mesh(&PaddedChunk::new(&chunk, air));
+    ///
+    /// // the top faces that were merged only cover blocks whose texture isn't turned
+    /// let stone = BLOCKS[BlockId::Stone(())].texture_variants(0).unwrap();
+    /// let top = mesh.vertices.chunks(4).filter(|quad| quad[0].normal == [0.0, 1.0, 0.0]);
+    /// for quad in top {
+    ///     let x = quad.iter().map(|vertex| vertex.position[0] as i32);
+    ///     let z = quad.iter().map(|vertex| vertex.position[2] as i32);
+    ///     let (x0, x1, z0, z1) = (x.clone().min().unwrap(), x.max().unwrap(), z.clone().min().unwrap(), z.max().unwrap());
+    ///     if (x1 - x0) * (z1 - z0) > 1 {
+    ///         for (x, z) in (x0..x1).flat_map(|x| (z0..z1).map(move |z| (x, z))) {
+    ///             assert_eq!(stone.rotation(position_hash([x, 0, z])), 0);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub const fn with_random_rotation(mut self) -> Self {
+        self.random_rotation = true;
+        self
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn random_rotation(&self) -> bool {
+        self.random_rotation
+    }
+
+    /// The texture file of the first variant
+    pub const fn first(&self) -> &'static str {
+        self.variants[0].0
+    }
+
+    /// Every texture file along with its weight
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, u32)> + '_ {
+        self.variants[..self.len].iter().copied()
+    }
+
+    pub fn total_weight(&self) -> u32 {
+        self.iter().map(|(_, weight)| weight).sum()
+    }
+
+    /// The variant to use at a position, given its [`position_hash`]
+    pub fn pick(&self, hash: u64) -> usize {
+        let mut roll = ((hash >> 32) % self.total_weight() as u64) as u32;
+        for (variant, (_, weight)) in self.iter().enumerate() {
+            if roll < weight {
+                return variant;
+            }
+            roll -= weight;
+        }
+        unreachable!()
+    }
+
+    /// The quarter turns to rotate the texture by at a position, given its
+    /// [`position_hash`], or 0 when the variants don't rotate randomly
+    pub fn rotation(&self, hash: u64) -> u8 {
+        if self.random_rotation {
+            (hash & 3) as u8
+        } else {
+            0
+        }
+    }
+}
+
+impl<const N: usize> const ConstInto<TextureVariants> for [(&'static str, u32); N] {
+    fn const_into(self) -> TextureVariants {
+        TextureVariants::new(self)
+    }
+}
+
+/// A well mixed hash of a block position, the same on every machine.
+///
+/// Used to pick texture variants, but anything that should look random
+/// while staying the same for a position can use it.
+pub const fn position_hash(pos: BlockPos) -> u64 {
+    let mut hash = (pos[0] as u32 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (pos[1] as u32 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (pos[2] as u32 as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    // the splitmix64 finalizer
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94D0_49BB_1331_11EB);
+    hash ^ (hash >> 31)
+}